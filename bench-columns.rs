@@ -0,0 +1,74 @@
+extern crate rustic;
+
+use std::time::Instant;
+use rustic::sql::{ColumnData, Connection};
+use rustic::sql::DbType::SQLite3;
+
+const ROWS : i64 = 1000000;
+
+//Sum a million rows of an integer and a real column with the Cursor, with Rows and with column batches of several sizes,
+//each scan five times, keeping the best time. Batches read the storage class of every value before the value itself,
+//so they cost about as much as Rows checking NULL values, a half more than Rows reading the values alone, and not much
+//less than the Cursor: they are worth it for their layout, not for their speed. Build the crate and this program with
+//optimizations:
+//	cargo build --release && rustc -O --edition 2015 bench-columns.rs --extern rustic=target/release/librustic.rlib -L target/release/deps
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	if let Err(e) = db.execute_batch(&format!("CREATE TABLE m(i INTEGER, r REAL);
+											   WITH RECURSIVE s(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM s WHERE x < {})
+											   INSERT INTO m SELECT x, x * 0.5 FROM s", ROWS)) {
+		println!("{}", e); return;
+	}
+	let sql = "SELECT i, r FROM m";
+	let expected = (ROWS * (ROWS + 1) / 2, ROWS as f64 * (ROWS + 1) as f64 / 4.0);
+	time("Cursor (OwnedRow per row)", expected, || {
+		let mut st = db.prepare_statement(sql).unwrap();
+		st.execute_query().map(|r| r.unwrap()).fold((0, 0.0), |(i, r), row| (i + row.get_long(0), r + row.get_double(1)))
+	});
+	time("Rows (in place)", expected, || {
+		let mut st = db.prepare_statement(sql).unwrap();
+		let mut rows = st.query();
+		let mut sum = (0, 0.0);
+		while let Some(row) = rows.next() {
+			let row = row.unwrap();
+			sum = (sum.0 + row.get_long(0), sum.1 + row.get_double(1));
+		}
+		sum
+	});
+	time("Rows, NULL checked", expected, || {
+		let mut st = db.prepare_statement(sql).unwrap();
+		let mut rows = st.query();
+		let mut sum = (0, 0.0);
+		while let Some(row) = rows.next() {
+			let row = row.unwrap();
+			if !row.is_null(0) { sum.0 += row.get_long(0); }
+			if !row.is_null(1) { sum.1 += row.get_double(1); }
+		}
+		sum
+	});
+	for &size in &[64, 1024, 65536] {
+		time(&format!("fetch_columns({})", size), expected, || {
+			let mut st = db.prepare_statement(sql).unwrap();
+			let mut sum = (0, 0.0);
+			for b in st.fetch_columns(size) {
+				let b = b.unwrap();
+				if let (Some(&ColumnData::Integer(ref i, _)), Some(&ColumnData::Real(ref r, _))) = (b.column(0), b.column(1)) {
+					sum = (sum.0 + i.iter().sum::<i64>(), sum.1 + r.iter().sum::<f64>());
+				}
+			}
+			sum
+		});
+	}
+}
+
+fn time<F : FnMut() -> (i64, f64)>(name : &str, expected : (i64, f64), mut scan : F) {
+	let mut best = None;
+	for _ in 0..5 {
+		let start = Instant::now();
+		let sum = scan();
+		let elapsed = start.elapsed();
+		if sum != expected { println!("{}: wrong sums {:?}", name, sum); return; }
+		if best.is_none_or(|b| elapsed < b) { best = Some(elapsed); }
+	}
+	println!("{:<28} {:>8.1} ms", name, best.map_or(0.0, |b| b.as_secs_f64() * 1000.0));
+}
@@ -4,6 +4,9 @@
 #![crate_type = "lib"]
 #![crate_name = "rustic"]
 
+#[cfg(feature = "serde")]
+extern crate serde;
+
 ///Miscellaneous utility classes.
 pub mod util {
 	pub use self::properties::Properties;
@@ -15,10 +18,68 @@ pub mod sql {
 	pub use self::connection::Connection;
 	pub use self::connection::Statement;
 	pub use self::connection::Cursor;
+	pub use self::connection::Rows;
+	pub use self::connection::Row;
+	pub use self::value::{Value, ValueRef, OwnedRow, BlobEncoding};
+	pub use self::types::{Timestamp, Date, Uuid, Decimal, TimeFormat, TimeUnit, UuidFormat, DecimalFormat};
+	pub use self::resultset::{ResultSet, ResultRow, ResultSetIter, Column};
+	pub use self::columnar::{ColumnBatches, ColumnBatch, ColumnData, ColumnType, Field, Schema, NullBitmap};
+	pub use self::driver::{Driver, RawConnection, RawStatement, register_driver, unregister_driver, get_driver};
+	pub use self::sqlite3::{Sqlite3Driver, Sqlite3Connection, Sqlite3Statement, CheckpointMode, WalCheckpoint};
+	pub use self::postgres::{PgDriver, PgConnection, PgStatement, PgError};
+	pub use self::mysql::{MySqlDriver, MySqlConnection, MySqlStatement, MySqlError};
+	pub use self::mock::{MockDb, MockConnection, MockStatement, Expectation, Execution};
+	pub use self::replay::{RecordDriver, RecordConnection, RecordStatement, ReplayDriver, ReplayConnection, ReplayStatement};
+	///Describes the structure of a database: tables with their columns, indexes and foreign keys, views and triggers.
+	///
+	///An Introspector reads the descriptors from a connection, <i>Connection::introspector</i> returns the one of its
+	///backend. Descriptors are plain values, which an Introspector of another backend can build from information_schema.
+	pub mod catalog;
+	pub use self::migrate::{Migration, Plan, Step, StepKind};
+	pub use self::dump::{DumpOptions, DumpContent};
+	pub use self::csv::{CsvOptions, CsvReport, CsvError};
+	pub use self::json::{JsonOptions, JsonFormat};
+	pub use self::pragma::{ConnectionConfig, JournalMode, Synchronous, TempStore};
+	pub use self::maintenance::{ForeignKeyViolation, PageStats, ObjectStats};
+	pub use self::attach::{AttachTarget, OpenMode, Database};
+	pub use self::builder::{Select, Insert, Update, Delete, Query, Expr, col, val, raw, func};
+	#[cfg(feature = "serde")]
+	pub use self::serde::QueryAs;
 	mod connection;
+	mod value;
+	mod types;
+	mod resultset;
+	mod columnar;
+	mod driver;
+	mod sqlite3;
+	mod postgres;
+	mod mysql;
+	mod mock;
+	mod replay;
+	mod migrate;
+	mod dump;
+	mod csv;
+	mod json;
+	mod pragma;
+	mod maintenance;
+	mod attach;
+	mod builder;
+	mod expand;
+	#[cfg(feature = "serde")]
+	mod serde;
+	mod crypto;
+	mod net;
+	mod regex;
+	mod script;
 	///Supported Databases
+	///
+	///A DbType is the key under which a Driver is registered. Backends provided outside of this crate are registered
+	///under a <i>Custom</i> name.
+	#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 	pub enum DbType {
-		SQLite3
+		SQLite3,
+		PostgreSQL,
+		MySQL,
+		Custom(&'static str)
 	}
-	//impl Copy for DbType {}
 }
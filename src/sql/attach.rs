@@ -0,0 +1,130 @@
+use std::io::{Error, ErrorKind, Result};
+use sql::{Connection, DbType};
+use sql::resultset::ResultSet;
+use sql::sqlite3::Sqlite3Connection;
+
+///The database to attach with <i>Connection::attach</i>.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttachTarget<'a> {
+	///A database file, by its path.
+	File(&'a str),
+	///A new, empty, in-memory database, private to the connection.
+	Memory
+}
+
+///How a database file is attached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpenMode {
+	ReadOnly,
+	///Read and write an existing file.
+	ReadWrite,
+	///Read and write the file, created if it does not exist.
+	Create
+}
+
+///A database of a connection: main, temp or attached, as listed by <i>Connection::databases</i>.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Database {
+	name : String,
+	file : Option<String>,
+	read_only : bool
+}
+
+impl Database {
+	///Return the schema name of the database, the prefix of its tables in SQL statements.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	///Return the path of the database file, None for an in-memory or temporary database.
+	pub fn file(&self) -> Option<&str> {
+		self.file.as_deref()
+	}
+
+	///Return true if the database cannot be written.
+	pub fn is_read_only(&self) -> bool {
+		self.read_only
+	}
+
+	///Return true if the database was attached, false for main and temp.
+	pub fn is_attached(&self) -> bool {
+		!is_builtin(&self.name)
+	}
+}
+
+fn is_builtin(name : &str) -> bool {
+	name.eq_ignore_ascii_case("main") || name.eq_ignore_ascii_case("temp")
+}
+
+//Run a statement of an SQLite database with text parameters and return its whole result.
+fn query(con : &Connection, sql : &str, params : &[&str]) -> Result<ResultSet> {
+	if con.db_type() != DbType::SQLite3 {
+		return Err(Error::new(ErrorKind::Unsupported, format!("no attached databases for {:?}", con.db_type())));
+	}
+	let mut st = con.prepare_statement(sql)?;
+	for (i, p) in params.iter().enumerate() {
+		if let Some(e) = st.set_string(i as i32 + 1, p) { return Err(e); }
+	}
+	st.fetch_all()
+}
+
+//Build the URI of a file opened in <i>mode</i>, escaping the characters that end the path of a URI.
+fn uri(path : &str, mode : OpenMode) -> String {
+	let mut uri = String::from("file:");
+	for c in path.chars() {
+		match c {
+			'%' => uri.push_str("%25"),
+			'?' => uri.push_str("%3f"),
+			'#' => uri.push_str("%23"),
+			c => uri.push(c)
+		}
+	}
+	uri.push_str(match mode { OpenMode::ReadOnly => "?mode=ro", OpenMode::ReadWrite => "?mode=rw", OpenMode::Create => "?mode=rwc" });
+	uri
+}
+
+//List the databases of the connection in order: main, temp once used, then the attached ones.
+pub fn databases(con : &Connection) -> Result<Vec<Database>> {
+	let rs = query(con, "SELECT name, file FROM pragma_database_list ORDER BY seq", &[])?;
+	let sqlite = con.raw().as_any().downcast_ref::<Sqlite3Connection>();
+	Ok(rs.iter().map(|r| {
+		let name = r.get_string(0);
+		let file = r.get_string(1);
+		Database {
+			read_only : sqlite.and_then(|s| s.is_read_only(&name)).unwrap_or(false),
+			file : if file.is_empty() { None } else { Some(file) },
+			name
+		}
+	}).collect())
+}
+
+//Attach a database under the schema name <i>name</i>. The name is checked first, as SQLite reports a name in use
+//with a generic error, and so is the limit on attached databases.
+pub fn attach(con : &Connection, target : AttachTarget, name : &str, mode : OpenMode) -> Result<()> {
+	let databases = databases(con)?;
+	if is_builtin(name) || databases.iter().any(|d| d.name.eq_ignore_ascii_case(name)) {
+		return Err(Error::new(ErrorKind::AlreadyExists, format!("database {} is already in use", name)));
+	}
+	if let Some(sqlite) = con.raw().as_any().downcast_ref::<Sqlite3Connection>() {
+		let attached = databases.iter().filter(|d| d.is_attached()).count();
+		if attached >= sqlite.attach_limit().max(0) as usize {
+			return Err(Error::new(ErrorKind::QuotaExceeded, format!("too many attached databases, {} at most", sqlite.attach_limit())));
+		}
+	}
+	let target = match target {
+		AttachTarget::File(path) => uri(path, mode),
+		AttachTarget::Memory => ":memory:".to_string()
+	};
+	query(con, "ATTACH ?1 AS ?2", &[&target, name]).map(|_| ())
+}
+
+//Detach a database attached under <i>name</i>.
+pub fn detach(con : &Connection, name : &str) -> Result<()> {
+	if is_builtin(name) {
+		return Err(Error::new(ErrorKind::InvalidInput, format!("database {} cannot be detached", name)));
+	}
+	if !databases(con)?.iter().any(|d| d.name.eq_ignore_ascii_case(name)) {
+		return Err(Error::new(ErrorKind::NotFound, format!("no database attached as {}", name)));
+	}
+	query(con, "DETACH ?1", &[name]).map(|_| ())
+}
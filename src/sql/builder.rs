@@ -0,0 +1,748 @@
+use std::fmt;
+use std::io::{Error, ErrorKind, Result};
+use std::ops::{Add, Div, Mul, Not, Sub};
+use sql::{Connection, DbType, Statement};
+use sql::script::tokens;
+use sql::value::Value;
+
+///An SQL expression of a query built with Select, Insert, Update or Delete: a column, a value bound as a parameter, a
+///raw SQL fragment, or a combination of them.
+///
+///Expressions start from <i>col</i>, <i>val</i>, <i>raw</i> or <i>func</i>, and values of the usual Rust types convert
+///into expressions, so that <i>col("age").ge(18).and(col("name").like("A%"))</i> is a condition. The operators + - * /
+///and ! build arithmetic and negation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expr(Node);
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+	Column(String),
+	Value(Value),
+	Raw(String, Vec<Value>),
+	Binary(Box<Node>, &'static str, Box<Node>),
+	Logical(&'static str, Vec<Node>),
+	Not(Box<Node>),
+	Postfix(Box<Node>, &'static str),
+	In(Box<Node>, Vec<Node>, bool),
+	Between(Box<Node>, Box<Node>, Box<Node>),
+	Function(String, Vec<Node>)
+}
+
+///Return the expression of a column, written <i>name</i> or <i>table.name</i>. The parts of the name are quoted;
+///"*" and "table.*" are kept as such.
+pub fn col(name : &str) -> Expr {
+	Expr(Node::Column(name.to_string()))
+}
+
+///Return the expression of a value, bound as a parameter.
+pub fn val<V : Into<Value>>(value : V) -> Expr {
+	Expr(Node::Value(value.into()))
+}
+
+///Return the expression of an SQL fragment, written as is. Its parameters are written '?' and bound to <i>params</i>,
+///in order, with the placeholders of the dialect.
+pub fn raw(sql : &str, params : Vec<Value>) -> Expr {
+	Expr(Node::Raw(sql.to_string(), params))
+}
+
+///Return the call of the SQL function <i>name</i>, such as <i>func("count", vec![col("*")])</i>.
+pub fn func(name : &str, args : Vec<Expr>) -> Expr {
+	Expr(Node::Function(name.to_string(), args.into_iter().map(|a| a.0).collect()))
+}
+
+impl<T : Into<Value>> From<T> for Expr {
+	fn from(value : T) -> Expr {
+		val(value)
+	}
+}
+
+impl Expr {
+	fn binary<E : Into<Expr>>(self, op : &'static str, other : E) -> Expr {
+		Expr(Node::Binary(Box::new(self.0), op, Box::new(other.into().0)))
+	}
+
+	fn logical<E : Into<Expr>>(self, op : &'static str, other : E) -> Expr {
+		let mut terms = match self.0 { Node::Logical(o, t) if o == op => t, n => vec![n] };
+		match other.into().0 { Node::Logical(o, t) if o == op => terms.extend(t), n => terms.push(n) }
+		Expr(Node::Logical(op, terms))
+	}
+
+	///Return the condition <i>self = other</i>.
+	pub fn eq<E : Into<Expr>>(self, other : E) -> Expr {
+		self.binary("=", other)
+	}
+
+	///Return the condition <i>self &lt;&gt; other</i>.
+	pub fn ne<E : Into<Expr>>(self, other : E) -> Expr {
+		self.binary("<>", other)
+	}
+
+	///Return the condition <i>self &lt; other</i>.
+	pub fn lt<E : Into<Expr>>(self, other : E) -> Expr {
+		self.binary("<", other)
+	}
+
+	///Return the condition <i>self &lt;= other</i>.
+	pub fn le<E : Into<Expr>>(self, other : E) -> Expr {
+		self.binary("<=", other)
+	}
+
+	///Return the condition <i>self &gt; other</i>.
+	pub fn gt<E : Into<Expr>>(self, other : E) -> Expr {
+		self.binary(">", other)
+	}
+
+	///Return the condition <i>self &gt;= other</i>.
+	pub fn ge<E : Into<Expr>>(self, other : E) -> Expr {
+		self.binary(">=", other)
+	}
+
+	///Return the condition <i>self LIKE pattern</i>.
+	pub fn like<E : Into<Expr>>(self, pattern : E) -> Expr {
+		self.binary("LIKE", pattern)
+	}
+
+	///Return the condition <i>self IS NULL</i>.
+	pub fn is_null(self) -> Expr {
+		Expr(Node::Postfix(Box::new(self.0), "IS NULL"))
+	}
+
+	///Return the condition <i>self IS NOT NULL</i>.
+	pub fn is_not_null(self) -> Expr {
+		Expr(Node::Postfix(Box::new(self.0), "IS NOT NULL"))
+	}
+
+	///Return the condition <i>self IN (values...)</i>, false when there is no value.
+	pub fn in_list<E : Into<Expr>>(self, values : Vec<E>) -> Expr {
+		Expr(Node::In(Box::new(self.0), values.into_iter().map(|v| v.into().0).collect(), false))
+	}
+
+	///Return the condition <i>self NOT IN (values...)</i>, true when there is no value.
+	pub fn not_in<E : Into<Expr>>(self, values : Vec<E>) -> Expr {
+		Expr(Node::In(Box::new(self.0), values.into_iter().map(|v| v.into().0).collect(), true))
+	}
+
+	///Return the condition <i>self BETWEEN low AND high</i>.
+	pub fn between<L : Into<Expr>, H : Into<Expr>>(self, low : L, high : H) -> Expr {
+		Expr(Node::Between(Box::new(self.0), Box::new(low.into().0), Box::new(high.into().0)))
+	}
+
+	///Return the condition <i>self AND other</i>.
+	pub fn and<E : Into<Expr>>(self, other : E) -> Expr {
+		self.logical("AND", other)
+	}
+
+	///Return the condition <i>self OR other</i>.
+	pub fn or<E : Into<Expr>>(self, other : E) -> Expr {
+		self.logical("OR", other)
+	}
+}
+
+macro_rules! arithmetic {
+	($($trait:ident $method:ident $op:expr),*) => {
+		$(impl<E : Into<Expr>> $trait<E> for Expr {
+			type Output = Expr;
+
+			fn $method(self, other : E) -> Expr {
+				self.binary($op, other)
+			}
+		})*
+	}
+}
+
+arithmetic!(Add add "+", Sub sub "-", Mul mul "*", Div div "/");
+
+impl Not for Expr {
+	type Output = Expr;
+
+	fn not(self) -> Expr {
+		Expr(Node::Not(Box::new(self.0)))
+	}
+}
+
+///SQL text and the values of its parameters, built by Select, Insert, Update or Delete for one type of database.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+	sql : String,
+	params : Vec<Value>
+}
+
+impl Query {
+	///Return the SQL text, with the parameter placeholders of the database: $1, $2... for PostgreSQL, ? otherwise.
+	pub fn sql(&self) -> &str {
+		&self.sql
+	}
+
+	///Return the values of the parameters, in order.
+	pub fn params(&self) -> &[Value] {
+		&self.params
+	}
+
+	///Prepare the statement on a connection and bind its parameters.
+	///
+	///Returns the Io Error of the preparation or of a binding that failed.
+	pub fn prepare<'a>(&self, con : &'a Connection) -> Result<Statement<'a>> {
+		let mut st = con.prepare_statement(&self.sql)?;
+		for (i, v) in self.params.iter().enumerate() {
+			if let Some(e) = st.set_value(i as i32 + 1, v) { return Err(e); }
+		}
+		Ok(st)
+	}
+}
+
+impl fmt::Display for Query {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(&self.sql)
+	}
+}
+
+//Quote an identifier, or each part of a dotted name, in the dialect of a type of database.
+#[cfg(feature = "serde")]
+pub fn ident(db_type : DbType, name : &str) -> String {
+	let mut w = Writer::new(db_type);
+	w.ident(name);
+	w.sql
+}
+
+//Return the placeholder of the parameter with index <i>index</i>, the first is 1.
+pub fn placeholder(db_type : DbType, index : usize) -> String {
+	if db_type == DbType::PostgreSQL { format!("${}", index) } else { "?".to_string() }
+}
+
+//Writes the SQL text of a query in the dialect of a type of database, collecting the values of its parameters.
+struct Writer {
+	db_type : DbType,
+	sql : String,
+	params : Vec<Value>
+}
+
+impl Writer {
+	fn new(db_type : DbType) -> Writer {
+		Writer { db_type, sql : String::new(), params : Vec::new() }
+	}
+
+	fn push(&mut self, s : &str) {
+		self.sql.push_str(s);
+	}
+
+	//Write an identifier, quoting each part of a dotted name.
+	fn ident(&mut self, name : &str) {
+		for (i, part) in name.split('.').enumerate() {
+			if i > 0 { self.sql.push('.'); }
+			if part == "*" { self.sql.push('*'); continue; }
+			let q = if self.db_type == DbType::MySQL { '`' } else { '"' };
+			self.sql.push(q);
+			self.sql.push_str(&part.replace(q, &format!("{}{}", q, q)));
+			self.sql.push(q);
+		}
+	}
+
+	//Write a table name, followed by its alias when written "table alias" or "table AS alias".
+	fn table(&mut self, name : &str) {
+		let words : Vec<&str> = name.split_whitespace().collect();
+		match words.len() {
+			2 | 3 if words.len() == 2 || words[1].eq_ignore_ascii_case("AS") => {
+				self.ident(words[0]);
+				self.push(" AS ");
+				self.ident(words[words.len() - 1]);
+			},
+			_ => self.ident(name)
+		}
+	}
+
+	fn idents(&mut self, names : &[String]) {
+		for (i, n) in names.iter().enumerate() {
+			if i > 0 { self.push(", "); }
+			self.ident(n);
+		}
+	}
+
+	fn param(&mut self, v : &Value) {
+		self.params.push(v.clone());
+		self.sql.push_str(&placeholder(self.db_type, self.params.len()));
+	}
+
+	fn node(&mut self, n : &Node) -> Result<()> {
+		match *n {
+			Node::Column(ref c) => self.ident(c),
+			Node::Value(ref v) => self.param(v),
+			Node::Raw(ref sql, ref params) => {
+				let marks : Vec<usize> = tokens(sql).into_iter().filter(|&(s, e)| &sql[s..e] == "?").map(|t| t.0).collect();
+				if marks.len() != params.len() {
+					return Err(Error::new(ErrorKind::InvalidInput, format!("{} parameter(s) for {} placeholder(s) in {}", params.len(), marks.len(), sql)));
+				}
+				let mut last = 0;
+				for (m, v) in marks.iter().zip(params) {
+					self.push(&sql[last..*m]);
+					self.param(v);
+					last = m + 1;
+				}
+				self.push(&sql[last..]);
+			},
+			Node::Binary(ref l, op, ref r) => {
+				self.operand(l)?;
+				self.push(&format!(" {} ", op));
+				self.operand(r)?;
+			},
+			Node::Logical(op, ref terms) => {
+				for (i, t) in terms.iter().enumerate() {
+					if i > 0 { self.push(&format!(" {} ", op)); }
+					self.operand(t)?;
+				}
+			},
+			Node::Not(ref e) => {
+				self.push("NOT ");
+				self.operand(e)?;
+			},
+			Node::Postfix(ref e, op) => {
+				self.operand(e)?;
+				self.push(&format!(" {}", op));
+			},
+			Node::In(ref e, ref values, negated) => {
+				if values.is_empty() { self.push(if negated { "1 = 1" } else { "1 = 0" }); return Ok(()); }
+				self.operand(e)?;
+				self.push(if negated { " NOT IN (" } else { " IN (" });
+				for (i, v) in values.iter().enumerate() {
+					if i > 0 { self.push(", "); }
+					self.node(v)?;
+				}
+				self.push(")");
+			},
+			Node::Between(ref e, ref low, ref high) => {
+				self.operand(e)?;
+				self.push(" BETWEEN ");
+				self.operand(low)?;
+				self.push(" AND ");
+				self.operand(high)?;
+			},
+			Node::Function(ref name, ref args) => {
+				self.push(name);
+				self.push("(");
+				for (i, a) in args.iter().enumerate() {
+					if i > 0 { self.push(", "); }
+					self.node(a)?;
+				}
+				self.push(")");
+			}
+		}
+		Ok(())
+	}
+
+	//Write an operand of an operator, in parentheses unless it is a column, a value or a function call.
+	fn operand(&mut self, n : &Node) -> Result<()> {
+		match *n {
+			Node::Column(_) | Node::Value(_) | Node::Function(_, _) => self.node(n),
+			_ => { self.push("("); self.node(n)?; self.push(")"); Ok(()) }
+		}
+	}
+
+	fn condition(&mut self, keyword : &str, filter : &Option<Node>) -> Result<()> {
+		if let Some(ref f) = *filter {
+			self.push(keyword);
+			self.node(f)?;
+		}
+		Ok(())
+	}
+
+	fn returning(&mut self, columns : &[String]) -> Result<()> {
+		if columns.is_empty() { return Ok(()); }
+		if self.db_type == DbType::MySQL { return Err(Error::new(ErrorKind::Unsupported, "no RETURNING clause for MySQL")); }
+		self.push(" RETURNING ");
+		self.idents(columns);
+		Ok(())
+	}
+
+	fn finish(self) -> Query {
+		Query { sql : self.sql, params : self.params }
+	}
+}
+
+fn and(filter : Option<Node>, e : Expr) -> Option<Node> {
+	Some(match filter { Some(f) => Expr(f).and(e).0, None => e.0 })
+}
+
+fn strings(columns : &[&str]) -> Vec<String> {
+	columns.iter().map(|c| c.to_string()).collect()
+}
+
+///Builder of a SELECT statement.
+///
+///Without columns the query selects *. Conditions given to <i>filter</i> and <i>having</i> are combined with AND.
+///Table names may carry an alias, written "table alias" or "table AS alias".
+#[derive(Clone, Debug)]
+pub struct Select {
+	table : String,
+	distinct : bool,
+	columns : Vec<(Node, Option<String>)>,
+	joins : Vec<(&'static str, String, Node)>,
+	filter : Option<Node>,
+	group_by : Vec<String>,
+	having : Option<Node>,
+	order_by : Vec<(Node, bool)>,
+	limit : Option<u64>,
+	offset : Option<u64>
+}
+
+impl Select {
+	///Start a SELECT from a table.
+	pub fn from(table : &str) -> Select {
+		Select { table : table.to_string(), distinct : false, columns : Vec::new(), joins : Vec::new(), filter : None,
+				 group_by : Vec::new(), having : None, order_by : Vec::new(), limit : None, offset : None }
+	}
+
+	///Select distinct rows.
+	pub fn distinct(mut self) -> Select {
+		self.distinct = true;
+		self
+	}
+
+	///Add a column to the result.
+	pub fn column(mut self, name : &str) -> Select {
+		self.columns.push((col(name).0, None));
+		self
+	}
+
+	///Add columns to the result.
+	pub fn columns(mut self, names : &[&str]) -> Select {
+		self.columns.extend(names.iter().map(|n| (col(n).0, None)));
+		self
+	}
+
+	///Add an expression to the result, named <i>alias</i>.
+	pub fn expr_as(mut self, expr : Expr, alias : &str) -> Select {
+		self.columns.push((expr.0, Some(alias.to_string())));
+		self
+	}
+
+	///Join a table on a condition.
+	pub fn join(mut self, table : &str, on : Expr) -> Select {
+		self.joins.push(("JOIN", table.to_string(), on.0));
+		self
+	}
+
+	///Left join a table on a condition.
+	pub fn left_join(mut self, table : &str, on : Expr) -> Select {
+		self.joins.push(("LEFT JOIN", table.to_string(), on.0));
+		self
+	}
+
+	///Add a condition to the WHERE clause.
+	pub fn filter(mut self, condition : Expr) -> Select {
+		self.filter = and(self.filter, condition);
+		self
+	}
+
+	///Group the rows by columns.
+	pub fn group_by(mut self, names : &[&str]) -> Select {
+		self.group_by.extend(names.iter().map(|n| n.to_string()));
+		self
+	}
+
+	///Add a condition to the HAVING clause.
+	pub fn having(mut self, condition : Expr) -> Select {
+		self.having = and(self.having, condition);
+		self
+	}
+
+	///Sort the rows by an expression, such as <i>col("name")</i>, in ascending order.
+	pub fn order_by(mut self, expr : Expr) -> Select {
+		self.order_by.push((expr.0, false));
+		self
+	}
+
+	///Sort the rows by an expression in descending order.
+	pub fn order_by_desc(mut self, expr : Expr) -> Select {
+		self.order_by.push((expr.0, true));
+		self
+	}
+
+	///Return at most <i>limit</i> rows.
+	pub fn limit(mut self, limit : u64) -> Select {
+		self.limit = Some(limit);
+		self
+	}
+
+	///Skip the first <i>offset</i> rows.
+	pub fn offset(mut self, offset : u64) -> Select {
+		self.offset = Some(offset);
+		self
+	}
+
+	///Build the query in the dialect of <i>db_type</i>.
+	///
+	///Returns an <i>InvalidInput</i> IoError if a raw fragment has not as many placeholders as values.
+	pub fn build(&self, db_type : DbType) -> Result<Query> {
+		let mut w = Writer::new(db_type);
+		w.push(if self.distinct { "SELECT DISTINCT " } else { "SELECT " });
+		if self.columns.is_empty() { w.push("*"); }
+		for (i, (c, alias)) in self.columns.iter().enumerate() {
+			if i > 0 { w.push(", "); }
+			w.node(c)?;
+			if let Some(ref a) = *alias { w.push(" AS "); w.ident(a); }
+		}
+		w.push(" FROM ");
+		w.table(&self.table);
+		for &(kind, ref table, ref on) in &self.joins {
+			w.push(&format!(" {} ", kind));
+			w.table(table);
+			w.push(" ON ");
+			w.node(on)?;
+		}
+		w.condition(" WHERE ", &self.filter)?;
+		if !self.group_by.is_empty() {
+			w.push(" GROUP BY ");
+			w.idents(&self.group_by);
+		}
+		w.condition(" HAVING ", &self.having)?;
+		for (i, &(ref e, desc)) in self.order_by.iter().enumerate() {
+			w.push(if i == 0 { " ORDER BY " } else { ", " });
+			w.node(e)?;
+			if desc { w.push(" DESC"); }
+		}
+		match (self.limit, self.offset) {
+			(Some(l), _) => w.push(&format!(" LIMIT {}", l)),
+			//SQLite and MySQL have no OFFSET without LIMIT.
+			(None, Some(_)) => match db_type {
+				DbType::PostgreSQL => (),
+				DbType::MySQL => w.push(" LIMIT 18446744073709551615"),
+				_ => w.push(" LIMIT -1")
+			},
+			(None, None) => ()
+		}
+		if let Some(o) = self.offset { w.push(&format!(" OFFSET {}", o)); }
+		Ok(w.finish())
+	}
+}
+
+//What an INSERT does when a row conflicts with a unique key.
+#[derive(Clone, Debug)]
+enum Conflict {
+	Fail,
+	Nothing,
+	Update(Vec<String>)
+}
+
+///Builder of an INSERT statement, of one or several rows, with an optional upsert clause.
+///
+///<i>do_update</i> updates the given columns of the conflicting row with the values of the inserted row, <i>do_nothing</i>
+///keeps the conflicting row. They are written ON CONFLICT for SQLite and PostgreSQL, which need the columns of the
+///conflicting key to update, and ON DUPLICATE KEY UPDATE for MySQL, which finds the key itself.
+#[derive(Clone, Debug)]
+pub struct Insert {
+	table : String,
+	columns : Vec<String>,
+	rows : Vec<Vec<Node>>,
+	conflict_target : Vec<String>,
+	conflict : Conflict,
+	returning : Vec<String>
+}
+
+impl Insert {
+	///Start an INSERT into a table.
+	pub fn into(table : &str) -> Insert {
+		Insert { table : table.to_string(), columns : Vec::new(), rows : Vec::new(), conflict_target : Vec::new(),
+				 conflict : Conflict::Fail, returning : Vec::new() }
+	}
+
+	///Set the columns filled by the rows.
+	pub fn columns(mut self, names : &[&str]) -> Insert {
+		self.columns = strings(names);
+		self
+	}
+
+	///Add a row of values, one per column, such as <i>vec![1.into(), "name".into()]</i>.
+	pub fn values(mut self, values : Vec<Expr>) -> Insert {
+		self.rows.push(values.into_iter().map(|v| v.0).collect());
+		self
+	}
+
+	///Set the columns of the unique key whose conflicts are handled by do_update or do_nothing.
+	pub fn on_conflict(mut self, names : &[&str]) -> Insert {
+		self.conflict_target = strings(names);
+		self
+	}
+
+	///On conflict, update the columns <i>names</i> of the existing row with the values of the inserted row.
+	pub fn do_update(mut self, names : &[&str]) -> Insert {
+		self.conflict = Conflict::Update(strings(names));
+		self
+	}
+
+	///On conflict, keep the existing row and skip the inserted one.
+	pub fn do_nothing(mut self) -> Insert {
+		self.conflict = Conflict::Nothing;
+		self
+	}
+
+	///Return columns of the inserted rows, not supported by MySQL.
+	pub fn returning(mut self, names : &[&str]) -> Insert {
+		self.returning = strings(names);
+		self
+	}
+
+	///Build the query in the dialect of <i>db_type</i>.
+	///
+	///Returns an <i>InvalidInput</i> IoError if there is no row, if a row has not one value per column, or if do_update
+	///has no conflict columns for SQLite or PostgreSQL; an <i>Unsupported</i> IoError for RETURNING with MySQL.
+	pub fn build(&self, db_type : DbType) -> Result<Query> {
+		if self.rows.is_empty() { return Err(Error::new(ErrorKind::InvalidInput, format!("no row to insert into {}", self.table))); }
+		if let Some(r) = self.rows.iter().find(|r| r.len() != self.columns.len()) {
+			return Err(Error::new(ErrorKind::InvalidInput, format!("{} value(s) for {} column(s)", r.len(), self.columns.len())));
+		}
+		let mut w = Writer::new(db_type);
+		w.push("INSERT INTO ");
+		w.table(&self.table);
+		w.push(" (");
+		w.idents(&self.columns);
+		w.push(") VALUES ");
+		for (i, row) in self.rows.iter().enumerate() {
+			w.push(if i == 0 { "(" } else { ", (" });
+			for (j, v) in row.iter().enumerate() {
+				if j > 0 { w.push(", "); }
+				w.node(v)?;
+			}
+			w.push(")");
+		}
+		match (&self.conflict, db_type) {
+			(&Conflict::Fail, _) => (),
+			(&Conflict::Nothing, DbType::MySQL) => {
+				//Setting a column to itself changes nothing.
+				let c = self.conflict_target.first().unwrap_or(&self.columns[0]).clone();
+				w.push(" ON DUPLICATE KEY UPDATE ");
+				w.ident(&c);
+				w.push(" = ");
+				w.ident(&c);
+			},
+			(Conflict::Update(columns), DbType::MySQL) => {
+				w.push(" ON DUPLICATE KEY UPDATE ");
+				for (i, c) in columns.iter().enumerate() {
+					if i > 0 { w.push(", "); }
+					w.ident(c);
+					w.push(" = VALUES(");
+					w.ident(c);
+					w.push(")");
+				}
+			},
+			(conflict, _) => {
+				w.push(" ON CONFLICT");
+				if !self.conflict_target.is_empty() {
+					w.push(" (");
+					w.idents(&self.conflict_target);
+					w.push(")");
+				}
+				match *conflict {
+					Conflict::Update(ref columns) => {
+						if self.conflict_target.is_empty() {
+							return Err(Error::new(ErrorKind::InvalidInput, "do_update needs the conflict columns, set by on_conflict"));
+						}
+						w.push(" DO UPDATE SET ");
+						for (i, c) in columns.iter().enumerate() {
+							if i > 0 { w.push(", "); }
+							w.ident(c);
+							w.push(" = excluded.");
+							w.ident(c);
+						}
+					},
+					_ => w.push(" DO NOTHING")
+				}
+			}
+		}
+		w.returning(&self.returning)?;
+		Ok(w.finish())
+	}
+}
+
+///Builder of an UPDATE statement. Conditions given to <i>filter</i> are combined with AND; without condition every
+///row is updated.
+#[derive(Clone, Debug)]
+pub struct Update {
+	table : String,
+	set : Vec<(String, Node)>,
+	filter : Option<Node>,
+	returning : Vec<String>
+}
+
+impl Update {
+	///Start an UPDATE of a table.
+	pub fn table(table : &str) -> Update {
+		Update { table : table.to_string(), set : Vec::new(), filter : None, returning : Vec::new() }
+	}
+
+	///Set a column to a value or an expression, such as <i>col("count") + 1</i>.
+	pub fn set<E : Into<Expr>>(mut self, column : &str, value : E) -> Update {
+		self.set.push((column.to_string(), value.into().0));
+		self
+	}
+
+	///Add a condition to the WHERE clause.
+	pub fn filter(mut self, condition : Expr) -> Update {
+		self.filter = and(self.filter, condition);
+		self
+	}
+
+	///Return columns of the updated rows, not supported by MySQL.
+	pub fn returning(mut self, names : &[&str]) -> Update {
+		self.returning = strings(names);
+		self
+	}
+
+	///Build the query in the dialect of <i>db_type</i>.
+	///
+	///Returns an <i>InvalidInput</i> IoError if no column is set; an <i>Unsupported</i> IoError for RETURNING with MySQL.
+	pub fn build(&self, db_type : DbType) -> Result<Query> {
+		if self.set.is_empty() { return Err(Error::new(ErrorKind::InvalidInput, format!("no column to update in {}", self.table))); }
+		let mut w = Writer::new(db_type);
+		w.push("UPDATE ");
+		w.table(&self.table);
+		w.push(" SET ");
+		for (i, (c, v)) in self.set.iter().enumerate() {
+			if i > 0 { w.push(", "); }
+			w.ident(c);
+			w.push(" = ");
+			w.node(v)?;
+		}
+		w.condition(" WHERE ", &self.filter)?;
+		w.returning(&self.returning)?;
+		Ok(w.finish())
+	}
+}
+
+///Builder of a DELETE statement. Conditions given to <i>filter</i> are combined with AND; without condition every
+///row is deleted.
+#[derive(Clone, Debug)]
+pub struct Delete {
+	table : String,
+	filter : Option<Node>,
+	returning : Vec<String>
+}
+
+impl Delete {
+	///Start a DELETE from a table.
+	pub fn from(table : &str) -> Delete {
+		Delete { table : table.to_string(), filter : None, returning : Vec::new() }
+	}
+
+	///Add a condition to the WHERE clause.
+	pub fn filter(mut self, condition : Expr) -> Delete {
+		self.filter = and(self.filter, condition);
+		self
+	}
+
+	///Return columns of the deleted rows, not supported by MySQL.
+	pub fn returning(mut self, names : &[&str]) -> Delete {
+		self.returning = strings(names);
+		self
+	}
+
+	///Build the query in the dialect of <i>db_type</i>.
+	///
+	///Returns an <i>Unsupported</i> IoError for RETURNING with MySQL.
+	pub fn build(&self, db_type : DbType) -> Result<Query> {
+		let mut w = Writer::new(db_type);
+		w.push("DELETE FROM ");
+		w.table(&self.table);
+		w.condition(" WHERE ", &self.filter)?;
+		w.returning(&self.returning)?;
+		Ok(w.finish())
+	}
+}
@@ -0,0 +1,600 @@
+use std::io::{Error, ErrorKind, Result};
+use sql::Connection;
+use sql::resultset::{ResultSet, ResultRow};
+use sql::value::ValueRef;
+
+///Reads the structure of a database.
+///
+///A <i>schema</i> is a namespace of the database: main, temp or an attached database with SQLite, a schema with
+///information_schema. None designates the default one.
+pub trait Introspector {
+	///Return the names of the schemas of the connection.
+	fn schemas(&self) -> Result<Vec<String>>;
+
+	///Return the names of the tables of a schema, in alphabetical order. Internal tables of the database are omitted.
+	fn table_names(&self, schema : Option<&str>) -> Result<Vec<String>>;
+
+	///Describe a table, None if the schema has no table named <i>name</i>.
+	fn table(&self, schema : Option<&str>, name : &str) -> Result<Option<Table>>;
+
+	///Describe all the tables of a schema, in alphabetical order.
+	fn tables(&self, schema : Option<&str>) -> Result<Vec<Table>> {
+		let mut tables = Vec::new();
+		for name in self.table_names(schema)? {
+			if let Some(t) = self.table(schema, &name)? { tables.push(t); }
+		}
+		Ok(tables)
+	}
+
+	///Describe the views of a schema, in alphabetical order.
+	fn views(&self, schema : Option<&str>) -> Result<Vec<View>>;
+
+	///Describe the triggers of a schema, in alphabetical order.
+	fn triggers(&self, schema : Option<&str>) -> Result<Vec<Trigger>>;
+}
+
+///Introspector of SQLite databases, built on sqlite_schema and the table_xinfo, index_list, index_xinfo,
+///foreign_key_list and table_list pragmas. The default schema is main.
+pub struct SqliteIntrospector<'c> {
+	con : &'c Connection
+}
+
+///Describes a table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+	schema : String,
+	name : String,
+	columns : Vec<Column>,
+	indexes : Vec<Index>,
+	foreign_keys : Vec<ForeignKey>,
+	without_rowid : bool,
+	strict : bool,
+	is_virtual : bool,
+	sql : Option<String>
+}
+
+///Describes a column of a table or a view.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+	name : String,
+	decl_type : Option<String>,
+	not_null : bool,
+	default : Option<String>,
+	primary_key : u32,
+	hidden : bool,
+	generated : Option<Generated>
+}
+
+///How the value of a generated column is produced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Generated {
+	///Computed when the column is read.
+	Virtual,
+	///Computed when the row is written, and stored.
+	Stored
+}
+
+///Describes an index of a table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Index {
+	name : String,
+	table : String,
+	unique : bool,
+	origin : IndexOrigin,
+	partial : bool,
+	columns : Vec<IndexColumn>,
+	sql : Option<String>
+}
+
+///Why an index exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexOrigin {
+	///Created by a CREATE INDEX statement.
+	CreateIndex,
+	///Created for a UNIQUE constraint.
+	Unique,
+	///Created for a PRIMARY KEY constraint.
+	PrimaryKey
+}
+
+///Describes a key of an index.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexColumn {
+	name : Option<String>,
+	descending : bool,
+	collation : Option<String>
+}
+
+///Describes a foreign key of a table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForeignKey {
+	columns : Vec<String>,
+	referenced_table : String,
+	referenced_columns : Vec<Option<String>>,
+	on_update : Action,
+	on_delete : Action
+}
+
+///What happens to the referencing rows when a referenced row is updated or deleted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+	NoAction,
+	Restrict,
+	SetNull,
+	SetDefault,
+	Cascade
+}
+
+///Describes a view.
+#[derive(Clone, Debug, PartialEq)]
+pub struct View {
+	schema : String,
+	name : String,
+	columns : Vec<Column>,
+	sql : Option<String>
+}
+
+///Describes a trigger.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trigger {
+	schema : String,
+	name : String,
+	table : String,
+	timing : Timing,
+	event : Event,
+	sql : Option<String>
+}
+
+///When a trigger runs, relative to the statement that fires it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Timing {
+	Before,
+	After,
+	InsteadOf
+}
+
+///The kind of statement that fires a trigger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+	Insert,
+	Update,
+	Delete
+}
+
+impl<'c> SqliteIntrospector<'c> {
+	///Create an introspector reading the structure of the database of <i>con</i>.
+	pub fn new(con : &'c Connection) -> SqliteIntrospector<'c> {
+		SqliteIntrospector { con }
+	}
+
+	//Run a query with text parameters and return its whole result.
+	fn query(&self, sql : &str, params : &[&str]) -> Result<ResultSet> {
+		let mut st = self.con.prepare_statement(sql)?;
+		for (i, p) in params.iter().enumerate() {
+			if let Some(e) = st.set_string(i as i32 + 1, p) { return Err(e); }
+		}
+		st.fetch_all()
+	}
+
+	//Return the rows of sqlite_schema of type <i>kind</i>: name, tbl_name, sql.
+	fn objects(&self, schema : &str, kind : &str, name : Option<&str>) -> Result<ResultSet> {
+		let sql = format!("SELECT name, tbl_name, sql FROM {}.sqlite_schema WHERE type = ?1 AND (?2 IS NULL OR name = ?2) ORDER BY name",
+						  quote(schema));
+		let mut st = self.con.prepare_statement(&sql)?;
+		if let Some(e) = st.set_string(1, kind) { return Err(e); }
+		if let Some(e) = match name { Some(n) => st.set_string(2, n), None => st.set_null(2) } { return Err(e); }
+		st.fetch_all()
+	}
+
+	fn columns(&self, schema : &str, table : &str) -> Result<Vec<Column>> {
+		let rs = self.query("SELECT name, type, \"notnull\", dflt_value, pk, hidden FROM pragma_table_xinfo(?1, ?2) ORDER BY cid",
+							&[table, schema])?;
+		Ok(rs.iter().map(|r| Column {
+			name : r.get_string(0),
+			decl_type : text(&r, 1).filter(|t| !t.is_empty()),
+			not_null : r.get_long(2) != 0,
+			default : text(&r, 3),
+			primary_key : r.get_long(4) as u32,
+			hidden : r.get_long(5) == 1,
+			generated : match r.get_long(5) { 2 => Some(Generated::Virtual), 3 => Some(Generated::Stored), _ => None }
+		}).collect())
+	}
+
+	fn indexes(&self, schema : &str, table : &str) -> Result<Vec<Index>> {
+		let list = self.query("SELECT name, \"unique\", origin, partial FROM pragma_index_list(?1, ?2) ORDER BY name", &[table, schema])?;
+		let mut indexes = Vec::new();
+		for r in &list {
+			let name = r.get_string(0);
+			let keys = self.query("SELECT name, \"desc\", coll FROM pragma_index_xinfo(?1, ?2) WHERE key = 1 ORDER BY seqno",
+								  &[&name, schema])?;
+			let sql = match self.objects(schema, "index", Some(&name))?.row(0) { Some(o) => text(&o, 2), None => None };
+			indexes.push(Index {
+				table : table.to_string(),
+				unique : r.get_long(1) != 0,
+				origin : match &*r.get_string(2) { "u" => IndexOrigin::Unique, "pk" => IndexOrigin::PrimaryKey, _ => IndexOrigin::CreateIndex },
+				partial : r.get_long(3) != 0,
+				columns : keys.iter().map(|k| IndexColumn { name : text(&k, 0), descending : k.get_long(1) != 0, collation : text(&k, 2) }).collect(),
+				sql,
+				name
+			});
+		}
+		Ok(indexes)
+	}
+
+	fn foreign_keys(&self, schema : &str, table : &str) -> Result<Vec<ForeignKey>> {
+		let rs = self.query("SELECT id, \"table\", \"from\", \"to\", on_update, on_delete FROM pragma_foreign_key_list(?1, ?2) ORDER BY id, seq",
+							&[table, schema])?;
+		let mut keys : Vec<(i64, ForeignKey)> = Vec::new();
+		for r in &rs {
+			let id = r.get_long(0);
+			if keys.last().is_none_or(|k| k.0 != id) {
+				keys.push((id, ForeignKey { columns : Vec::new(), referenced_table : r.get_string(1), referenced_columns : Vec::new(),
+											on_update : Action::parse(&r.get_string(4)), on_delete : Action::parse(&r.get_string(5)) }));
+			}
+			let k = &mut keys.last_mut().unwrap().1;
+			k.columns.push(r.get_string(2));
+			k.referenced_columns.push(text(&r, 3));
+		}
+		//SQLite lists the foreign keys in reverse order of declaration.
+		Ok(keys.into_iter().rev().map(|k| k.1).collect())
+	}
+}
+
+impl<'c> Introspector for SqliteIntrospector<'c> {
+	fn schemas(&self) -> Result<Vec<String>> {
+		Ok(self.query("SELECT name FROM pragma_database_list ORDER BY seq", &[])?.iter().map(|r| r.get_string(0)).collect())
+	}
+
+	fn table_names(&self, schema : Option<&str>) -> Result<Vec<String>> {
+		let schema = schema.unwrap_or("main");
+		self.check_schema(schema)?;
+		let rs = self.query("SELECT name FROM pragma_table_list WHERE schema = ?1 AND type IN ('table', 'virtual') \
+							 AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\' ORDER BY name", &[schema])?;
+		Ok(rs.iter().map(|r| r.get_string(0)).collect())
+	}
+
+	fn table(&self, schema : Option<&str>, name : &str) -> Result<Option<Table>> {
+		let schema = schema.unwrap_or("main");
+		self.check_schema(schema)?;
+		let list = self.query("SELECT type, wr, strict FROM pragma_table_list WHERE schema = ?1 AND name = ?2 AND type IN ('table', 'virtual')",
+							  &[schema, name])?;
+		let info = match list.row(0) { Some(r) => r, None => return Ok(None) };
+		let sql = match self.objects(schema, "table", Some(name))?.row(0) { Some(o) => text(&o, 2), None => None };
+		Ok(Some(Table {
+			schema : schema.to_string(),
+			name : name.to_string(),
+			columns : self.columns(schema, name)?,
+			indexes : self.indexes(schema, name)?,
+			foreign_keys : self.foreign_keys(schema, name)?,
+			without_rowid : info.get_long(1) != 0,
+			strict : info.get_long(2) != 0,
+			is_virtual : info.get_string(0) == "virtual",
+			sql
+		}))
+	}
+
+	fn views(&self, schema : Option<&str>) -> Result<Vec<View>> {
+		let schema = schema.unwrap_or("main");
+		self.check_schema(schema)?;
+		let mut views = Vec::new();
+		for r in &self.objects(schema, "view", None)? {
+			let name = r.get_string(0);
+			views.push(View { schema : schema.to_string(), columns : self.columns(schema, &name)?, sql : text(&r, 2), name });
+		}
+		Ok(views)
+	}
+
+	fn triggers(&self, schema : Option<&str>) -> Result<Vec<Trigger>> {
+		let schema = schema.unwrap_or("main");
+		self.check_schema(schema)?;
+		Ok(self.objects(schema, "trigger", None)?.iter().map(|r| {
+			let sql = text(&r, 2);
+			let (timing, event) = trigger_kind(sql.as_deref().unwrap_or(""));
+			Trigger { schema : schema.to_string(), name : r.get_string(0), table : r.get_string(1), timing, event, sql }
+		}).collect())
+	}
+}
+
+impl<'c> SqliteIntrospector<'c> {
+	//The pragma functions return nothing for an unknown schema, report it instead. The temp schema exists before
+	//pragma database_list shows it, when the first temporary object is created.
+	fn check_schema(&self, schema : &str) -> Result<()> {
+		if schema.eq_ignore_ascii_case("temp") || self.schemas()?.iter().any(|s| s.eq_ignore_ascii_case(schema)) { return Ok(()); }
+		Err(Error::new(ErrorKind::NotFound, format!("unknown schema {}", schema)))
+	}
+}
+
+impl Table {
+	///Return the name of the schema of the table.
+	pub fn schema(&self) -> &str {
+		&self.schema
+	}
+
+	///Return the name of the table.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	///Return the columns of the table, in declaration order.
+	pub fn columns(&self) -> &[Column] {
+		&self.columns
+	}
+
+	///Return the column named <i>name</i>, compared case-insensitively.
+	pub fn column(&self, name : &str) -> Option<&Column> {
+		self.columns.iter().find(|c| c.name.eq_ignore_ascii_case(name))
+	}
+
+	///Return the names of the columns of the primary key, in key order. Empty if the table has no declared primary key.
+	pub fn primary_key(&self) -> Vec<&str> {
+		let mut pk : Vec<&Column> = self.columns.iter().filter(|c| c.primary_key > 0).collect();
+		pk.sort_by_key(|c| c.primary_key);
+		pk.into_iter().map(|c| c.name.as_str()).collect()
+	}
+
+	///Return the indexes of the table, including those created for UNIQUE and PRIMARY KEY constraints.
+	pub fn indexes(&self) -> &[Index] {
+		&self.indexes
+	}
+
+	///Return the foreign keys of the table, in declaration order.
+	pub fn foreign_keys(&self) -> &[ForeignKey] {
+		&self.foreign_keys
+	}
+
+	///Return true if the table is a WITHOUT ROWID table.
+	pub fn is_without_rowid(&self) -> bool {
+		self.without_rowid
+	}
+
+	///Return true if the table is a STRICT table.
+	pub fn is_strict(&self) -> bool {
+		self.strict
+	}
+
+	///Return true if the table is a virtual table.
+	pub fn is_virtual(&self) -> bool {
+		self.is_virtual
+	}
+
+	///Return the SQL text that created the table, None if unknown.
+	pub fn sql(&self) -> Option<&str> {
+		self.sql.as_deref()
+	}
+}
+
+impl Column {
+	///Return the name of the column.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	///Return the declared type of the column, None if the column has no declared type.
+	pub fn decl_type(&self) -> Option<&str> {
+		self.decl_type.as_deref()
+	}
+
+	///Return true if the column has a NOT NULL constraint.
+	pub fn is_not_null(&self) -> bool {
+		self.not_null
+	}
+
+	///Return the SQL text of the default value of the column, None if the column has no default value.
+	pub fn default(&self) -> Option<&str> {
+		self.default.as_deref()
+	}
+
+	///Return the position of the column in the primary key, starting at 1, or 0 if it is not part of the primary key.
+	pub fn primary_key(&self) -> u32 {
+		self.primary_key
+	}
+
+	///Return true if the column is a hidden column of a virtual table.
+	pub fn is_hidden(&self) -> bool {
+		self.hidden
+	}
+
+	///Return how the value of a generated column is produced, None if the column is not generated.
+	pub fn generated(&self) -> Option<Generated> {
+		self.generated
+	}
+}
+
+impl Index {
+	///Return the name of the index.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	///Return the name of the indexed table.
+	pub fn table(&self) -> &str {
+		&self.table
+	}
+
+	///Return true if the index is UNIQUE.
+	pub fn is_unique(&self) -> bool {
+		self.unique
+	}
+
+	///Return why the index exists.
+	pub fn origin(&self) -> IndexOrigin {
+		self.origin
+	}
+
+	///Return true if the index is a partial index, with a WHERE clause.
+	pub fn is_partial(&self) -> bool {
+		self.partial
+	}
+
+	///Return the keys of the index, in key order.
+	pub fn columns(&self) -> &[IndexColumn] {
+		&self.columns
+	}
+
+	///Return the SQL text that created the index, None for an index created for a constraint.
+	pub fn sql(&self) -> Option<&str> {
+		self.sql.as_deref()
+	}
+}
+
+impl IndexColumn {
+	///Return the name of the indexed column, None if the key is an expression.
+	pub fn name(&self) -> Option<&str> {
+		self.name.as_deref()
+	}
+
+	///Return true if the key is sorted in descending order.
+	pub fn is_descending(&self) -> bool {
+		self.descending
+	}
+
+	///Return the name of the collating sequence of the key, None if unknown.
+	pub fn collation(&self) -> Option<&str> {
+		self.collation.as_deref()
+	}
+}
+
+impl ForeignKey {
+	///Return the referencing columns, in key order.
+	pub fn columns(&self) -> &[String] {
+		&self.columns
+	}
+
+	///Return the name of the referenced table.
+	pub fn referenced_table(&self) -> &str {
+		&self.referenced_table
+	}
+
+	///Return the referenced columns, in key order. A column is None when the key references the primary key of the
+	///referenced table implicitly.
+	pub fn referenced_columns(&self) -> &[Option<String>] {
+		&self.referenced_columns
+	}
+
+	///Return the action run when a referenced row is updated.
+	pub fn on_update(&self) -> Action {
+		self.on_update
+	}
+
+	///Return the action run when a referenced row is deleted.
+	pub fn on_delete(&self) -> Action {
+		self.on_delete
+	}
+}
+
+impl Action {
+	///Parse an action as written in SQL, case-insensitively; anything else is NO ACTION.
+	pub fn parse(s : &str) -> Action {
+		match &*s.to_ascii_uppercase() {
+			"RESTRICT" => Action::Restrict,
+			"SET NULL" => Action::SetNull,
+			"SET DEFAULT" => Action::SetDefault,
+			"CASCADE" => Action::Cascade,
+			_ => Action::NoAction
+		}
+	}
+
+	///Return the action as written in SQL.
+	pub fn as_sql(&self) -> &'static str {
+		match *self {
+			Action::NoAction => "NO ACTION",
+			Action::Restrict => "RESTRICT",
+			Action::SetNull => "SET NULL",
+			Action::SetDefault => "SET DEFAULT",
+			Action::Cascade => "CASCADE"
+		}
+	}
+}
+
+impl View {
+	///Return the name of the schema of the view.
+	pub fn schema(&self) -> &str {
+		&self.schema
+	}
+
+	///Return the name of the view.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	///Return the columns of the view.
+	pub fn columns(&self) -> &[Column] {
+		&self.columns
+	}
+
+	///Return the SQL text that created the view, None if unknown.
+	pub fn sql(&self) -> Option<&str> {
+		self.sql.as_deref()
+	}
+}
+
+impl Trigger {
+	///Return the name of the schema of the trigger.
+	pub fn schema(&self) -> &str {
+		&self.schema
+	}
+
+	///Return the name of the trigger.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	///Return the name of the table or view the trigger is attached to.
+	pub fn table(&self) -> &str {
+		&self.table
+	}
+
+	///Return when the trigger runs.
+	pub fn timing(&self) -> Timing {
+		self.timing
+	}
+
+	///Return the kind of statement that fires the trigger.
+	pub fn event(&self) -> Event {
+		self.event
+	}
+
+	///Return the SQL text that created the trigger, None if unknown.
+	pub fn sql(&self) -> Option<&str> {
+		self.sql.as_deref()
+	}
+}
+
+//Quote an SQL identifier.
+pub fn quote(name : &str) -> String {
+	format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn text(r : &ResultRow, i : i32) -> Option<String> {
+	match r.get_value_ref(i) {
+		ValueRef::Null => None,
+		v => Some(v.as_text().into_owned())
+	}
+}
+
+//Find the timing and the event of a trigger in its CREATE TRIGGER statement. Quoted names are skipped, the first
+//BEFORE, AFTER or INSTEAD keyword gives the timing (BEFORE by default) and the first DELETE, INSERT or UPDATE the event.
+fn trigger_kind(sql : &str) -> (Timing, Event) {
+	let (mut timing, mut event) = (None, None);
+	let mut word = String::new();
+	let mut quote : Option<char> = None;
+	for c in sql.chars().chain(Some(' ')) {
+		if let Some(q) = quote {
+			if c == q || (q == '[' && c == ']') { quote = None; }
+			continue;
+		}
+		if c.is_alphanumeric() || c == '_' { word.push(c.to_ascii_uppercase()); continue; }
+		match &*word {
+			"BEFORE" if timing.is_none() => timing = Some(Timing::Before),
+			"AFTER" if timing.is_none() => timing = Some(Timing::After),
+			"INSTEAD" if timing.is_none() => timing = Some(Timing::InsteadOf),
+			"DELETE" => { event = Some(Event::Delete); break; },
+			"INSERT" => { event = Some(Event::Insert); break; },
+			"UPDATE" => { event = Some(Event::Update); break; },
+			_ => ()
+		}
+		word.clear();
+		if c == '"' || c == '\'' || c == '`' || c == '[' { quote = Some(c); }
+	}
+	(timing.unwrap_or(Timing::Before), event.unwrap_or(Event::Insert))
+}
@@ -0,0 +1,386 @@
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind, Result};
+use std::sync::Arc;
+use sql::connection::{Row, Rows};
+use sql::value::{Value, ValueRef};
+
+///Type of the values of a column in a ColumnBatch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnType {
+	Integer,
+	Real,
+	Text,
+	Blob
+}
+
+///Describes a column of a ColumnBatch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+	name : String,
+	column_type : ColumnType,
+	decl_type : Option<String>
+}
+
+impl Field {
+	///Return the name of the column.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	///Return the type of the values of the column.
+	pub fn column_type(&self) -> ColumnType {
+		self.column_type
+	}
+
+	///Return the declared type of the column, None if the column is an expression or a subquery.
+	pub fn decl_type(&self) -> Option<&str> {
+		self.decl_type.as_ref().map(|s| s.as_ref())
+	}
+}
+
+///Describes the columns of a query result fetched in batches.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schema {
+	fields : Vec<Field>
+}
+
+impl Schema {
+	///Return the columns.
+	pub fn fields(&self) -> &[Field] {
+		&self.fields
+	}
+
+	///Return the index of the first column named <i>name</i>, compared case-insensitively. Returns None if not found.
+	pub fn index_of(&self, name : &str) -> Option<usize> {
+		self.fields.iter().position(|f| f.name.eq_ignore_ascii_case(name))
+	}
+}
+
+///A bitmap telling which values of a column are NULL.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct NullBitmap {
+	bits : Vec<u64>,
+	len : usize
+}
+
+impl NullBitmap {
+	fn with_capacity(capacity : usize) -> NullBitmap {
+		NullBitmap { bits : Vec::with_capacity(capacity.div_ceil(64)), len : 0 }
+	}
+
+	fn push(&mut self, null : bool) {
+		if self.len.is_multiple_of(64) { self.bits.push(0); }
+		if null { self.bits[self.len / 64] |= 1 << (self.len % 64); }
+		self.len += 1;
+	}
+
+	///Return the number of values described by the bitmap.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	///Return true if the bitmap describes no value.
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	///Return true if the value with index <i>index</i> is NULL. Returns false if out of range.
+	pub fn is_null(&self, index : usize) -> bool {
+		index < self.len && self.bits[index / 64] & (1 << (index % 64)) != 0
+	}
+
+	///Return the number of NULL values.
+	pub fn null_count(&self) -> usize {
+		self.bits.iter().map(|w| w.count_ones() as usize).sum()
+	}
+
+	///Return the bitmap words, value <i>i</i> is NULL when bit <i>i % 64</i> of word <i>i / 64</i> is set.
+	pub fn words(&self) -> &[u64] {
+		&self.bits
+	}
+}
+
+///The values of a column in a ColumnBatch.
+///
+///NULL numbers are stored as 0 in the value vector, and flagged in the null bitmap.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnData {
+	Integer(Vec<i64>, NullBitmap),
+	Real(Vec<f64>, NullBitmap),
+	Text(Vec<Option<String>>),
+	Blob(Vec<Option<Vec<u8>>>)
+}
+
+impl ColumnData {
+	fn with_capacity(column_type : ColumnType, capacity : usize) -> ColumnData {
+		match column_type {
+			ColumnType::Integer => ColumnData::Integer(Vec::with_capacity(capacity), NullBitmap::with_capacity(capacity)),
+			ColumnType::Real => ColumnData::Real(Vec::with_capacity(capacity), NullBitmap::with_capacity(capacity)),
+			ColumnType::Text => ColumnData::Text(Vec::with_capacity(capacity)),
+			ColumnType::Blob => ColumnData::Blob(Vec::with_capacity(capacity))
+		}
+	}
+
+	//Append a value converted to the type of the column. Returns false, appending nothing, if a number cannot be
+	//converted without loss.
+	fn push_value(&mut self, value : ValueRef) -> bool {
+		match *self {
+			ColumnData::Integer(ref mut v, ref mut n) => match value {
+				ValueRef::Null => { v.push(0); n.push(true); }
+				_ => match to_integer(value) { Some(x) => { v.push(x); n.push(false); } None => return false }
+			},
+			ColumnData::Real(ref mut v, ref mut n) => match value {
+				ValueRef::Null => { v.push(0.0); n.push(true); }
+				_ => match to_real(value) { Some(x) => { v.push(x); n.push(false); } None => return false }
+			},
+			ColumnData::Text(ref mut v) => v.push(if value.is_null() { None } else { Some(value.as_text().into_owned()) }),
+			ColumnData::Blob(ref mut v) => v.push(if value.is_null() { None } else { Some(value.as_bytes().into_owned()) })
+		}
+		true
+	}
+
+	//Append the value of a column of the current row. Numbers stored with the type of the column are read as they are,
+	//the other values are converted by <i>push_value</i>.
+	fn push_cell(&mut self, row : &Row, index : i32) -> bool {
+		match (self, row.column_type(index)) {
+			(&mut ColumnData::Integer(ref mut v, ref mut n), Some(ColumnType::Integer)) => { v.push(row.get_long(index)); n.push(false); true }
+			(&mut ColumnData::Real(ref mut v, ref mut n), Some(ColumnType::Real)) |
+			(&mut ColumnData::Real(ref mut v, ref mut n), Some(ColumnType::Integer)) => { v.push(row.get_double(index)); n.push(false); true }
+			(c, _) => c.push_value(row.get_value_ref(index))
+		}
+	}
+
+	///Return the number of values.
+	pub fn len(&self) -> usize {
+		match *self {
+			ColumnData::Integer(ref v, _) => v.len(),
+			ColumnData::Real(ref v, _) => v.len(),
+			ColumnData::Text(ref v) => v.len(),
+			ColumnData::Blob(ref v) => v.len()
+		}
+	}
+
+	///Return true if there is no value.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	///Return true if the value with index <i>index</i> is NULL.
+	pub fn is_null(&self, index : usize) -> bool {
+		match *self {
+			ColumnData::Integer(_, ref n) | ColumnData::Real(_, ref n) => n.is_null(index),
+			ColumnData::Text(ref v) => v.get(index).is_none_or(|x| x.is_none()),
+			ColumnData::Blob(ref v) => v.get(index).is_none_or(|x| x.is_none())
+		}
+	}
+
+	///Return the integer values, None if the column is not an Integer column.
+	pub fn as_integers(&self) -> Option<&[i64]> {
+		match *self { ColumnData::Integer(ref v, _) => Some(v), _ => None }
+	}
+
+	///Return the real values, None if the column is not a Real column.
+	pub fn as_reals(&self) -> Option<&[f64]> {
+		match *self { ColumnData::Real(ref v, _) => Some(v), _ => None }
+	}
+
+	///Return the text values, None if the column is not a Text column.
+	pub fn as_texts(&self) -> Option<&[Option<String>]> {
+		match *self { ColumnData::Text(ref v) => Some(v), _ => None }
+	}
+
+	///Return the blob values, None if the column is not a Blob column.
+	pub fn as_blobs(&self) -> Option<&[Option<Vec<u8>>]> {
+		match *self { ColumnData::Blob(ref v) => Some(v), _ => None }
+	}
+}
+
+///A batch of rows of a query result, stored column by column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnBatch {
+	schema : Arc<Schema>,
+	columns : Vec<ColumnData>,
+	len : usize
+}
+
+impl ColumnBatch {
+	///Return the description of the columns, shared by all the batches of a result.
+	pub fn schema(&self) -> &Arc<Schema> {
+		&self.schema
+	}
+
+	///Return the number of rows in the batch.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	///Return true if the batch has no row.
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	///Return the columns of the batch.
+	pub fn columns(&self) -> &[ColumnData] {
+		&self.columns
+	}
+
+	///Return the column with index <i>index</i>, the first column is 0. Returns None if out of range.
+	pub fn column(&self, index : usize) -> Option<&ColumnData> {
+		self.columns.get(index)
+	}
+
+	///Return the first column named <i>name</i>, compared case-insensitively. Returns None if not found.
+	pub fn column_by_name(&self, name : &str) -> Option<&ColumnData> {
+		self.schema.index_of(name).and_then(|i| self.columns.get(i))
+	}
+
+	///Consume the batch and return its columns.
+	pub fn into_columns(self) -> Vec<ColumnData> {
+		self.columns
+	}
+}
+
+///Iterator reading a query result in ColumnBatch of a fixed number of rows, the last batch may be shorter.
+///
+///The type of each column is chosen before the first batch, from the declared type of the column or, when the declared
+///type does not tell, from the first non NULL value of the result, whatever the size of the batches. Rows are read ahead
+///until each such column has a value, so a column NULL in every row makes the first batch read the whole result.
+///Later values are converted to the type of their column: any value to text or bytes, integers to reals, reals and
+///numeric texts to integers when they have no fractional part. A value that cannot be converted, such as a text that
+///does not parse in an Integer column, ends the iteration with an <i>InvalidData</i> IoError.
+pub struct ColumnBatches<'a: 'b, 'b> {
+	rows : Rows<'a, 'b>,
+	batch_size : usize,
+	schema : Option<Arc<Schema>>,
+	//Rows read ahead to choose the types of the columns, not yet returned in a batch.
+	pending : VecDeque<Vec<Value>>,
+	//Number of rows put in a batch so far, to tell which row holds a value that cannot be converted.
+	count : usize,
+	done : bool
+}
+
+impl<'a, 'b> ColumnBatches<'a, 'b> {
+	pub(crate) fn new(rows : Rows<'a, 'b>, batch_size : usize) -> ColumnBatches<'a, 'b> {
+		ColumnBatches { rows, batch_size : if batch_size == 0 { 1 } else { batch_size }, schema : None, pending : VecDeque::new(), count : 0,
+						done : false }
+	}
+
+	//Choose the types of the columns, reading rows ahead as values while a column the declared types leave undecided
+	//has only NULL values.
+	fn read_schema(&mut self) -> Result<Arc<Schema>> {
+		let n = self.rows.column_count();
+		let decls : Vec<Option<String>> = (0..n).map(|i| self.rows.column_decltype(i)).collect();
+		let mut types : Vec<Option<ColumnType>> = decls.iter().map(|d| d.as_ref().and_then(|d| affinity(d))).collect();
+		while types.iter().any(|t| t.is_none()) {
+			let values : Vec<Value> = match self.rows.next() {
+				Some(row) => { let row = row?; (0..n).map(|i| row.get_value_ref(i).to_value()).collect() }
+				None => { self.done = true; break; }
+			};
+			for (t, v) in types.iter_mut().zip(values.iter()) {
+				if t.is_none() {
+					*t = match *v {
+						Value::Null => None,
+						Value::Integer(_) => Some(ColumnType::Integer),
+						Value::Real(_) => Some(ColumnType::Real),
+						Value::Text(_) => Some(ColumnType::Text),
+						Value::Blob(_) => Some(ColumnType::Blob)
+					};
+				}
+			}
+			self.pending.push_back(values);
+		}
+		let fields = (0..n).map(|i| Field { name : self.rows.column_name(i), column_type : types[i as usize].unwrap_or(ColumnType::Text),
+											decl_type : decls[i as usize].clone() }).collect();
+		Ok(Arc::new(Schema { fields }))
+	}
+
+	//Fill a batch with the rows read ahead, then with the next rows of the result.
+	fn next_batch(&mut self, schema : Arc<Schema>) -> Result<ColumnBatch> {
+		let mut columns : Vec<ColumnData> = schema.fields.iter().map(|f| ColumnData::with_capacity(f.column_type, self.batch_size)).collect();
+		let mut len = 0;
+		while len < self.batch_size {
+			if let Some(values) = self.pending.pop_front() {
+				for (i, c) in columns.iter_mut().enumerate() {
+					if !c.push_value(values[i].as_value_ref()) { return Err(unconvertible(&schema.fields[i], self.count, values[i].as_value_ref())); }
+				}
+			} else if self.done {
+				break;
+			} else {
+				match self.rows.next() {
+					Some(row) => {
+						let row = row?;
+						for (i, c) in columns.iter_mut().enumerate() {
+							if !c.push_cell(&row, i as i32) { return Err(unconvertible(&schema.fields[i], self.count, row.get_value_ref(i as i32))); }
+						}
+					}
+					None => { self.done = true; break; }
+				}
+			}
+			len += 1;
+			self.count += 1;
+		}
+		Ok(ColumnBatch { schema, columns, len })
+	}
+}
+
+impl<'a, 'b> Iterator for ColumnBatches<'a, 'b> {
+	type Item = Result<ColumnBatch>;
+	///Returns the next batch, or an Io Error if reading a row fails or if a value cannot be converted to the type of its
+	///column. Returns None once all the rows have been read.
+	fn next(&mut self) -> Option<Result<ColumnBatch>> {
+		if self.done && self.pending.is_empty() { return None; }
+		let schema = match self.schema.clone() {
+			Some(schema) => Ok(schema),
+			None => self.read_schema()
+		};
+		let batch = schema.and_then(|schema| { self.schema = Some(schema.clone()); self.next_batch(schema) });
+		match batch {
+			Ok(ref b) if b.is_empty() => None,
+			Err(e) => { self.done = true; self.pending.clear(); Some(Err(e)) }
+			b => Some(b)
+		}
+	}
+}
+
+//The error telling that the value of the row with index <i>row</i> cannot be stored in the column <i>field</i>.
+fn unconvertible(field : &Field, row : usize, value : ValueRef) -> Error {
+	Error::new(ErrorKind::InvalidData, format!("row {} of column {}: {:?} cannot be converted to {:?}", row + 1, field.name, value.to_value(),
+											   field.column_type))
+}
+
+//Column type given by the affinity rules of SQLite, None for the NUMERIC affinity which does not tell. A column declared
+//BLOB keeps its values as they are given, they are read as bytes.
+pub fn affinity(decl_type : &str) -> Option<ColumnType> {
+	let d = decl_type.to_ascii_uppercase();
+	if d.contains("INT") { Some(ColumnType::Integer) }
+	else if d.contains("CHAR") || d.contains("CLOB") || d.contains("TEXT") { Some(ColumnType::Text) }
+	else if d.contains("BLOB") { Some(ColumnType::Blob) }
+	else if d.contains("REAL") || d.contains("FLOA") || d.contains("DOUB") { Some(ColumnType::Real) }
+	else { None }
+}
+
+fn to_integer(value : ValueRef) -> Option<i64> {
+	match value {
+		ValueRef::Integer(n) => Some(n),
+		ValueRef::Real(f) => integral(f),
+		ValueRef::Text(t) => {
+			let t = ::std::str::from_utf8(t).ok()?.trim();
+			t.parse().ok().or_else(|| t.parse().ok().and_then(integral))
+		}
+		_ => None
+	}
+}
+
+//The integer equal to a real, None if the real has a fractional part or is out of range.
+fn integral(f : f64) -> Option<i64> {
+	if f.fract() == 0.0 && (-9223372036854775808.0..9223372036854775808.0).contains(&f) { Some(f as i64) } else { None }
+}
+
+fn to_real(value : ValueRef) -> Option<f64> {
+	match value {
+		ValueRef::Integer(n) => Some(n as f64),
+		ValueRef::Real(f) => Some(f),
+		ValueRef::Text(t) => ::std::str::from_utf8(t).ok()?.trim().parse().ok(),
+		_ => None
+	}
+}
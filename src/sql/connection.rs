@@ -1,262 +1,586 @@
 ﻿use std::str::from_utf8;
-use std::ffi::{CString,CStr};
+use std::borrow::Cow;
 use std::vec::Vec;
-use std::io::{Error, ErrorKind, Result};
-use std::ptr::null;
+use std::io::{BufReader, Error, ErrorKind, Read, Result, Write};
+use std::time::Duration;
 use sql::DbType;
-use std::slice;
-
-#[link(name = "sqlite3")]
-extern {
-    fn sqlite3_open(filename : *const i8, ppDb : *const*const i8) -> i32;
-	fn sqlite3_close_v2(pDb : *const i8) -> i32;
-	fn sqlite3_errmsg(pDb : *const i8) -> *const i8;
-	fn sqlite3_errstr(erno : i32) -> *const i8;
-	fn sqlite3_prepare_v2(pDb : *const i8, sql : *const i8, nByte : i32, ppStmt : *const*const i8, pzTail : *const*const i8) -> i32;
-	fn sqlite3_step(pStmt : *const i8) -> i32;
-	fn sqlite3_changes(pStmt : *const i8) -> i32;
-	fn sqlite3_column_int(pStmt : *const i8, iCol : i32) -> i32;
-	fn sqlite3_column_int64(pStmt : *const i8, iCol : i32) -> i64;
-	fn sqlite3_column_double(pStmt : *const i8, iCol : i32) -> f64;
-	fn sqlite3_column_text(pStmt : *const i8, iCol : i32) -> *const u8;
-	fn sqlite3_column_blob(pStmt : *const i8, iCol : i32) -> *const u8;
-	fn sqlite3_column_bytes(pStmt : *const i8, iCol : i32) -> i32;
-	fn sqlite3_column_type(pStmt : *const i8, iCol : i32) -> i32;
-	fn sqlite3_bind_int(pStmt : *const i8, iCol : i32, value : i32) -> i32;
-	fn sqlite3_bind_int64(pStmt : *const i8, iCol : i32, value : i64) -> i32;
-	fn sqlite3_bind_double(pStmt : *const i8, iCol : i32, value : f64) -> i32;
-	fn sqlite3_bind_text(pStmt : *const i8, iCol : i32, value : *const i8, n : i32, f: *const extern fn(*const i8)) -> i32;
-	fn sqlite3_bind_null(pStmt : *const i8, iCol : i32) -> i32;
-	fn sqlite3_bind_blob(pStmt : *const i8, iCol : i32, value : *const i8, n : i32, f: *const extern fn(*const i8)) -> i32;
-	fn sqlite3_reset(pStmt : *const i8) -> i32;
-	//fn sqlite3_finalize(pStmt : *i8) -> i32;
-}
+use sql::driver::{get_driver, RawConnection, RawStatement};
+use sql::value::{OwnedRow, Value, ValueRef};
+use sql::resultset::ResultSet;
+use sql::columnar::{ColumnBatches, ColumnType};
+use sql::catalog::{Introspector, SqliteIntrospector};
+use sql::script::{parameters, split_statements};
+use sql::expand::{Expansion, Param};
+use sql::types::{Date, Decimal, DecimalFormat, TimeFormat, TimeUnit, Timestamp, Uuid, UuidFormat};
+use sql::types::{duration_from_value, duration_to_value, i128_from_value, i128_to_value, u64_from_value, u64_to_value};
+use sql::dump::{dump, restore, DumpOptions};
+use sql::csv::{import_csv, write_csv, CsvOptions, CsvReport};
+use sql::json::{write_json, JsonOptions};
+use sql::pragma::{self, ConnectionConfig, JournalMode, Synchronous, TempStore};
+use sql::attach::{self, AttachTarget, Database, OpenMode};
+use sql::maintenance::{self, ForeignKeyViolation, PageStats};
+use sql::sqlite3::{CheckpointMode, Sqlite3Connection, WalCheckpoint};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "serde")]
+use sql::serde::{bind, from_row, insert, QueryAs};
 
 ///Connection permits to connect to supported databases.
 pub struct Connection {
 	db_type : DbType,
-	p_db : *const i8
+	raw : Box<dyn RawConnection>
 }
 
 ///Statement is used for executing SQL instructions and returning results.
 ///
-///In the SQL Statement, ? character is replaced by a parameter using a set_* method.
+///In the SQL Statement, ? character is replaced by a parameter using a set_* method, or by a list of values using
+///<i>set_list</i>.
 pub struct Statement<'a> {
 	p_con  : &'a Connection,
-	p_stmt : *const i8,
-	exec  : bool
+	raw : Box<dyn RawStatement + 'a>,
+	exec  : bool,
+	params : Vec<Param<'a>>,
+	expansion : Option<Box<Expansion<'a>>>
 }
 
 ///Cursor is used for browsing a database query result.
+///
+///Each row is returned as an OwnedRow, a copy detached from the Statement, so rows can be kept or collected. The copy
+///has a cost on every row: a Vec of values is allocated, and each text or blob column is copied into its own String or
+///Vec. Use <i>Statement::query</i> to read the rows in place without copying them, and <i>Row::to_owned</i> to keep
+///only some of them.
 pub struct Cursor<'a: 'b, 'b> {
-	p_stmt : &'b Statement<'a>,
-	error : bool
+	rows : Rows<'a, 'b>
+}
+
+///Rows is used for browsing a database query result without copying it.
+///
+///Rows is a streaming iterator: each Row borrows the Rows it comes from, so it must be dropped before the next row is read.
+pub struct Rows<'a: 'b, 'b> {
+	p_stmt : &'b mut Statement<'a>,
+	done : bool
+}
+
+///Row gives access to the columns of the current row of a Rows.
+pub struct Row<'r> {
+	p_stmt : &'r Statement<'r>
 }
 
 impl<'a> Statement<'a> {
 
-	///Execute the SQL query and returns the result in an iterable Cursor.
+	///Execute the SQL query and returns the result in an iterable Cursor, which copies each row into an OwnedRow.
 	pub fn execute_query<'b>(&'b mut self) -> Cursor<'a, 'b> {
-		match self.p_con.db_type {
-		DbType::SQLite3 => {
-		if self.exec { unsafe { sqlite3_reset(self.p_stmt) }; } else { self.exec=true; }
-		Cursor { p_stmt : self, error : false }
-		}
-		}
+		Cursor { rows : self.query() }
+	}
+
+	///Execute the SQL query and returns the result in a Rows, which reads each row in place.
+	pub fn query<'b>(&'b mut self) -> Rows<'a, 'b> {
+		let done = self.start().is_err();
+		Rows { p_stmt : self, done }
+	}
+
+	///Execute the SQL query and returns the whole result in a ResultSet, which does not depend on the Statement anymore.
+	///
+	///Returns an Io Error if reading a row fails.
+	pub fn fetch_all(&mut self) -> Result<ResultSet> {
+		self.start()?;
+		ResultSet::read(Rows { p_stmt : self, done : false })
+	}
+
+	///Execute the SQL query and returns an iterator reading the result column by column, in batches of <i>batch_size</i> rows.
+	///
+	///Each ColumnBatch holds one typed vector per column, ready to be handed to numeric code. Rows are not copied one by
+	///one as with <i>execute_query</i>, but the storage class of each value is read to check it fits its column, which
+	///costs about as much as testing each value for NULL with <i>query</i>.
+	pub fn fetch_columns<'b>(&'b mut self, batch_size : usize) -> ColumnBatches<'a, 'b> {
+		ColumnBatches::new(self.query(), batch_size)
+	}
+
+	///Execute the SQL query and returns an iterator deserializing each row into a T, with serde.
+	///
+	///A struct or a map is filled by column name, an Option is None for NULL, bytes and Vec&lt;u8&gt; are read from blobs;
+	///a tuple is filled by column position, and any other type from the only column of the row.
+	///Each item is an <i>InvalidData</i> IoError if the row cannot be deserialized.
+	#[cfg(feature = "serde")]
+	pub fn query_as<'b, T : DeserializeOwned>(&'b mut self) -> QueryAs<'a, 'b, T> {
+		QueryAs::new(self.query())
+	}
+
+	///Return the number of columns in the result of the statement, 0 for a statement that returns no data.
+	pub fn column_count(&self) -> i32 {
+		self.raw.column_count()
+	}
+
+	///Return the name of the column with index <i>column_index</i> in the result, the first column is 0.
+	///Returns an empty String if the index is out of range.
+	pub fn column_name(&self, column_index : i32) -> String {
+		self.raw.column_name(column_index)
+	}
+
+	///Return the declared type of the column with index <i>column_index</i> in the result, the first column is 0.
+	///Returns None if the column is an expression or a subquery, or if the index is out of range.
+	pub fn column_decltype(&self, column_index : i32) -> Option<String> {
+		self.raw.column_decltype(column_index)
+	}
+
+	///Execute the SQL query and write its result as CSV, with a header of the column names if <i>options</i> ask for it.
+	///
+	///Returns the number of rows written, or the Io Error of the query or of the writer that failed.
+	pub fn write_csv<W : Write>(&mut self, mut writer : W, options : &CsvOptions) -> Result<u64> {
+		write_csv(self, &mut writer, options)
+	}
+
+	///Execute the SQL query and write its result as JSON, a JSON array of objects or JSON Lines as <i>options</i> ask.
+	///
+	///Rows are written as they are read, the result is never held in memory.
+	///Returns the number of rows written, or the Io Error of the query or of the writer that failed.
+	pub fn write_json<W : Write>(&mut self, mut writer : W, options : &JsonOptions) -> Result<u64> {
+		write_json(self, &mut writer, options)
 	}
 
 	///Execute the SQL statement and returns None if succeeds or an Io Error.
 	pub fn execute(&mut self) -> Option<Error> {
-		match self.p_con.db_type {
-		DbType::SQLite3 => {
-		if self.exec { unsafe { sqlite3_reset(self.p_stmt) }; } else { self.exec=true; }
-		match unsafe { sqlite3_step(self.p_stmt) } {
-			100 | 101 => None,
-			err => Some (Error::new(ErrorKind::Other, get_error(self.p_con.p_db, err).as_ref())) }
-		}
-		}
+		self.start().and_then(|_| self.raw.step()).err()
 	}
 
 	///Execute the SQL INSERT, UPDATE or DELETE statement and returns the number of affected rows.
 	///Returns 0 for SQL statement that returns nothing. Returns an Io Error if fails.
 	pub fn execute_update(&mut self) -> Result<i32> {
-		match self.p_con.db_type {
-		DbType::SQLite3 => { 
-		if self.exec { unsafe { sqlite3_reset(self.p_stmt) }; } else { self.exec=true; }
-		match unsafe { sqlite3_step(self.p_stmt) } {
-			100 | 101 => Ok(unsafe { sqlite3_changes(self.p_stmt) }),
-			err => Err(Error::new(ErrorKind::Other, get_error(self.p_con.p_db, err).as_ref())) }
-		}
+		self.start()?;
+		self.raw.step()?;
+		Ok(self.p_con.raw.changes() as i32)
+	}
+
+	//Reset the statement if it has already been executed, before executing it again.
+	fn start(&mut self) -> Result<()> {
+		if self.exec { self.raw.reset() } else { self.exec=true; Ok(()) }
+	}
+
+	//Reset the statement if it has already been executed, before binding a new parameter value.
+	fn rebind(&mut self) -> Result<()> {
+		if self.exec { self.exec=false; self.raw.reset() } else { Ok(()) }
+	}
+
+	//Bind a value to a parameter, after preparing the statement again if the number of placeholders it takes changes.
+	//The value is kept to bind it again on the next preparation.
+	fn bind_param(&mut self, param_index : i32, param : Param<'a>) -> Option<Error> {
+		if let Err(e) = self.rebind().and_then(|_| self.arrange(param_index, param.len())) { return Some(e); }
+		let position = self.expansion.as_ref().map_or(param_index, |x| x.position(param_index));
+		if let Err(e) = bind_raw(&mut *self.raw, position, &param) { return Some(e); }
+		//The binding succeeded, so the index is one of a parameter of the statement.
+		if param_index >= 1 {
+			let i = param_index as usize - 1;
+			if self.params.len() <= i { self.params.resize(i + 1, Param::Unset); }
+			self.params[i] = param;
+		}
+		None
+	}
+
+	//Switch to the statement prepared for <i>len</i> placeholders in place of the parameter <i>param_index</i>, and
+	//bind again the values of the other parameters.
+	fn arrange(&mut self, param_index : i32, len : usize) -> Result<()> {
+		if self.expansion.is_none() {
+			if len == 1 { return Ok(()); }
+			self.expansion = Some(Box::new(Expansion::new(&self.raw.sql())));
+		}
+		let db_type = self.p_con.db_type;
+		let expansion = self.expansion.as_mut().unwrap();
+		if param_index < 1 || param_index as usize > expansion.count() {
+			if len == 1 { return Ok(()); }
+			return Err(Error::new(ErrorKind::InvalidInput, format!("no parameter {} to bind a list to", param_index)));
+		}
+		if expansion.lengths()[param_index as usize - 1] == len { return Ok(()); }
+		if len == 0 && db_type == DbType::PostgreSQL {
+			return Err(Error::new(ErrorKind::InvalidInput, format!("no empty list for parameter {} with PostgreSQL", param_index)));
+		}
+		let mut lengths = expansion.lengths().to_vec();
+		lengths[param_index as usize - 1] = len;
+		let raw = match expansion.take(&lengths) {
+			Some(raw) => raw,
+			None => self.p_con.raw.prepare(&expansion.rewrite(db_type, &lengths))?
+		};
+		expansion.switch(::std::mem::replace(&mut self.raw, raw), lengths);
+		for (i, p) in self.params.iter().enumerate() {
+			if i + 1 != param_index as usize && i < expansion.count() {
+				bind_raw(&mut *self.raw, expansion.position(i as i32 + 1), p)?;
+			}
 		}
+		Ok(())
 	}
-	
+
 	///Replace in the SQL Statement the '?' parameter by an int. The leftmost parameter has an index of 1.
 	pub fn set_int(&mut self, param_index : i32, value : i32) -> Option<Error> {
-		match self.p_con.db_type {
-		DbType::SQLite3 => {
-			if self.exec { unsafe { sqlite3_reset(self.p_stmt) }; self.exec=false; }
-			match unsafe { sqlite3_bind_int(self.p_stmt, param_index, value) } {
-				0 => None,
-				n => Some(Error::new(ErrorKind::Other, get_error(self.p_con.p_db, n).as_ref())) }
-			}
-		}
+		self.bind_param(param_index, Param::Value(Value::Integer(value as i64)))
 	}
-	
+
 	///Replace in the SQL Statement the '?' parameter by an i64. The leftmost parameter has an index of 1.
 	pub fn set_long(&mut self, param_index : i32, value : i64) -> Option<Error> {
-		match self.p_con.db_type {
-		DbType::SQLite3 => {
-			if self.exec { unsafe { sqlite3_reset(self.p_stmt) }; self.exec=false; }
-			match unsafe { sqlite3_bind_int64(self.p_stmt, param_index as i32, value) } {
-				0 => None,
-				n => Some(Error::new(ErrorKind::Other, get_error(self.p_con.p_db, n).as_ref())) }
-		}
-		}
+		self.bind_param(param_index, Param::Value(Value::Integer(value)))
 	}
 
 	///Replace in the SQL Statement the '?' parameter by an f32. The leftmost parameter has an index of 1.
 	pub fn set_float(&mut self, param_index : i32, value : f32) -> Option<Error> {
-		match self.p_con.db_type {
-		DbType::SQLite3 => {
-			if self.exec { unsafe { sqlite3_reset(self.p_stmt) }; self.exec=false; }
-			match unsafe { sqlite3_bind_double(self.p_stmt, param_index as i32, value as f64) } {
-				0 => None,
-				n => Some(Error::new(ErrorKind::Other, get_error(self.p_con.p_db, n).as_ref())) }
-		}
-		}
+		self.bind_param(param_index, Param::Value(Value::Real(value as f64)))
 	}
 
 	///Replace in the SQL Statement the '?' parameter by a double. The leftmost parameter has an index of 1.
 	pub fn set_double(&mut self, param_index : i32, value : f64) -> Option<Error> {
-		match self.p_con.db_type {
-		DbType::SQLite3 => {
-			if self.exec { unsafe { sqlite3_reset(self.p_stmt) }; self.exec=false; }
-			match unsafe { sqlite3_bind_double(self.p_stmt, param_index as i32, value) } {
-				0 => None,
-				n => Some(Error::new(ErrorKind::Other, get_error(self.p_con.p_db, n).as_ref())) }
-		}
-		}
+		self.bind_param(param_index, Param::Value(Value::Real(value)))
 	}
 
 	///Replace in the SQL Statement the '?' parameter by an &str. The leftmost parameter has an index of 1.
+	///
+	///The string is bound with its length, so it may contain NUL characters. The database makes its own copy of the value.
 	pub fn set_string(&mut self, param_index : i32, value : &str) -> Option<Error> {
-		match self.p_con.db_type {
-		DbType::SQLite3 => {
-			if self.exec { unsafe { sqlite3_reset(self.p_stmt) }; self.exec=false; }
-			match CString::new(value) {
-				Ok(s)	=> match unsafe { sqlite3_bind_text(self.p_stmt, param_index as i32, s.as_ptr(), -1i32, !0 as *const extern fn(*const i8)) } {
-								0 => None,
-								n => Some(Error::new(ErrorKind::Other, get_error(self.p_con.p_db, n as i32).as_ref())) },
-				Err(e)	=> Some(Error::new(ErrorKind::Other, e))
-			}
-		}
-		}
+		self.bind_param(param_index, Param::Value(Value::Text(value.to_string())))
 	}
-	
+
+	///Replace in the SQL Statement the '?' parameter by an &str, without copying it.
+	///The leftmost parameter has an index of 1.
+	///
+	///The value must outlive the Statement, the database reads it in place each time the statement is executed.
+	pub fn set_string_ref(&mut self, param_index : i32, value : &'a str) -> Option<Error> {
+		self.bind_param(param_index, Param::Text(value))
+	}
+
 	///Replace in the SQL Statement the '?' parameter by an &[u8]. The leftmost parameter has an index of 1.
+	///
+	///The database makes its own copy of the value.
 	pub fn set_blob(&mut self, param_index : i32, value : &[u8]) -> Option<Error> {
-		match self.p_con.db_type {
-		DbType::SQLite3 => {
-			if self.exec { unsafe { sqlite3_reset(self.p_stmt) }; self.exec=false; }
-			match unsafe { sqlite3_bind_blob(self.p_stmt, param_index as i32, value.as_ptr() as *const i8,
-												value.len() as i32, !0 as *const extern fn(*const i8)) } {
-				0 => None,
-				n => Some(Error::new(ErrorKind::Other, get_error(self.p_con.p_db, n).as_ref())) }
-		}
-		}
+		self.bind_param(param_index, Param::Value(Value::Blob(value.to_vec())))
+	}
+
+	///Replace in the SQL Statement the '?' parameter by an &[u8], without copying it.
+	///The leftmost parameter has an index of 1.
+	///
+	///The value must outlive the Statement, the database reads it in place each time the statement is executed.
+	pub fn set_blob_ref(&mut self, param_index : i32, value : &'a [u8]) -> Option<Error> {
+		self.bind_param(param_index, Param::Blob(value))
 	}
-	
+
 	///Replace in the SQL Statement the '?' parameter by an SQL NULL. The leftmost parameter has an index of 1.
 	pub fn set_null(&mut self, param_index : i32) -> Option<Error> {
-		match self.p_con.db_type {
-		DbType::SQLite3 => {
-			if self.exec { unsafe { sqlite3_reset(self.p_stmt) }; self.exec=false; }
-			match unsafe { sqlite3_bind_null(self.p_stmt, param_index as i32) } {
-				0 => None,
-				n => Some(Error::new(ErrorKind::Other, get_error(self.p_con.p_db, n).as_ref())) }
+		self.bind_param(param_index, Param::Value(Value::Null))
+	}
+
+	///Replace in the SQL Statement the '?' parameter by a Value. The leftmost parameter has an index of 1.
+	pub fn set_value(&mut self, param_index : i32, value : &Value) -> Option<Error> {
+		self.bind_param(param_index, Param::Value(value.clone()))
+	}
+
+	///Replace in the SQL Statement the '?' parameter by a u64. The leftmost parameter has an index of 1.
+	///
+	///Returns an <i>InvalidInput</i> IoError if the value exceeds the range of an integer column, 2<sup>63</sup> - 1.
+	pub fn set_u64(&mut self, param_index : i32, value : u64) -> Option<Error> {
+		u64_to_value(value).map(|v| self.set_value(param_index, &v)).unwrap_or_else(Some)
+	}
+
+	///Replace in the SQL Statement the '?' parameter by an i128. The leftmost parameter has an index of 1.
+	///
+	///Returns an <i>InvalidInput</i> IoError if the value exceeds the range of an integer column, that of an i64.
+	pub fn set_i128(&mut self, param_index : i32, value : i128) -> Option<Error> {
+		i128_to_value(value).map(|v| self.set_value(param_index, &v)).unwrap_or_else(Some)
+	}
+
+	///Replace in the SQL Statement the '?' parameter by a UTC instant, stored in <i>format</i>.
+	///The leftmost parameter has an index of 1.
+	pub fn set_timestamp(&mut self, param_index : i32, value : &Timestamp, format : TimeFormat) -> Option<Error> {
+		self.set_value(param_index, &value.to_value(format))
+	}
+
+	///Replace in the SQL Statement the '?' parameter by a date, stored in <i>format</i> (at midnight UTC for numbers).
+	///The leftmost parameter has an index of 1.
+	pub fn set_date(&mut self, param_index : i32, value : &Date, format : TimeFormat) -> Option<Error> {
+		self.set_value(param_index, &value.to_value(format))
+	}
+
+	///Replace in the SQL Statement the '?' parameter by a duration, stored as an integer number of <i>unit</i>, the
+	///remainder being dropped. The leftmost parameter has an index of 1.
+	///
+	///Returns an <i>InvalidInput</i> IoError if the number exceeds the range of an integer column.
+	pub fn set_duration(&mut self, param_index : i32, value : Duration, unit : TimeUnit) -> Option<Error> {
+		duration_to_value(value, unit).map(|v| self.set_value(param_index, &v)).unwrap_or_else(Some)
+	}
+
+	///Replace in the SQL Statement the '?' parameter by a UUID, stored in <i>format</i>.
+	///The leftmost parameter has an index of 1.
+	pub fn set_uuid(&mut self, param_index : i32, value : &Uuid, format : UuidFormat) -> Option<Error> {
+		self.set_value(param_index, &value.to_value(format))
+	}
+
+	///Replace in the SQL Statement the '?' parameter by a decimal, stored in <i>format</i>.
+	///The leftmost parameter has an index of 1.
+	///
+	///Returns an <i>InvalidInput</i> IoError if the decimal has more digits after the decimal point than a scaled format
+	///keeps, or if the scaled integer exceeds the range of an integer column.
+	pub fn set_decimal(&mut self, param_index : i32, value : &Decimal, format : DecimalFormat) -> Option<Error> {
+		value.to_value(format).map(|v| self.set_value(param_index, &v)).unwrap_or_else(Some)
+	}
+
+	///Replace in the SQL Statement the '?' parameter by a list of values separated by commas, such as the ids of
+	///<i>WHERE id IN (?)</i>. The leftmost parameter has an index of 1.
+	///
+	///The statement is prepared again with one placeholder per value, and the statements prepared for each length of
+	///the lists are kept to be reused. The indexes of the other parameters do not change, and their values are kept.
+	///An empty list makes <i>x IN (?)</i> false with SQLite and MySQL.
+	///Returns an <i>InvalidInput</i> IoError if there is no parameter <i>param_index</i> or if the list is empty with
+	///PostgreSQL, or the Io Error of the preparation.
+	pub fn set_list<I>(&mut self, param_index : i32, values : I) -> Option<Error> where I : IntoIterator, I::Item : Into<Value> {
+		self.bind_param(param_index, Param::List(values.into_iter().map(Into::into).collect()))
+	}
+
+	///Bind the fields of <i>value</i>, a struct or a map serialized with serde, to the parameters named after them,
+	///written :name, @name or $name in the SQL Statement. Fields without parameter are ignored.
+	///
+	///None is bound as NULL, unit enum variants as their name, bytes and Vec&lt;u8&gt; as blobs. Returns an <i>InvalidData</i>
+	///IoError if a field cannot be stored in a column, such as a nested struct or an integer beyond the i64 range.
+	#[cfg(feature = "serde")]
+	pub fn bind<T : Serialize + ?Sized>(&mut self, value : &T) -> Option<Error> {
+		bind(self, value)
+	}
+
+	///Return the SQL text used to prepare the statement, with the placeholders of the lists bound by set_list.
+	pub fn sql(&self) -> String {
+		self.raw.sql()
+	}
+
+	///Return the index of the parameter named <i>name</i> in the SQL Statement, to use with the set_* methods.
+	///The name includes its prefix, such as ":id", "@id" or "$id". Returns None if there is no such parameter.
+	pub fn parameter_index(&self, name : &str) -> Option<i32> {
+		match self.expansion {
+			Some(ref x) => parameters(x.sql()).iter().position(|p| !p.is_empty() && p == name).map(|i| i as i32 + 1),
+			None => self.raw.parameter_index(name)
 		}
+	}
+
+	///Return the SQL text of the statement with the currently bound parameters expanded in place of the '?'.
+	///Returns None if the expanded text could not be built (out of memory or text too long), or if the database does not
+	///support it.
+	pub fn expanded_sql(&self) -> Option<String> {
+		self.raw.expanded_sql()
+	}
+
+	///Return true if the statement makes no direct change to the content of the database.
+	pub fn is_readonly(&self) -> bool {
+		self.raw.is_readonly()
+	}
+
+	///Return true if the statement is an EXPLAIN or an EXPLAIN QUERY PLAN statement.
+	pub fn is_explain(&self) -> bool {
+		self.raw.is_explain()
+	}
+
+	///Return true if the statement has been stepped at least once but has neither run to completion nor been reset.
+	pub fn is_busy(&self) -> bool {
+		self.raw.is_busy()
+	}
+
+	///Return the Connection the statement has been prepared on.
+	pub fn connection(&self) -> &'a Connection {
+		self.p_con
+	}
+
+	///Give access to the backend side of the statement.
+	pub fn raw(&self) -> &(dyn RawStatement + 'a) {
+		&*self.raw
+	}
+}
+
+impl<'a, 'b> Rows<'a, 'b> {
+	///Read the next row of the result. Returns None when all the rows have been read, and from then on.
+	///
+	///Returns a Row if ok, or a <i>OtherIoError</i> IoError with (if available from the underlying database)
+	///in the <i>detail</i> field text that describes the error, result code, and text that describes the result code.
+	#[allow(clippy::should_implement_trait)]
+	pub fn next(&mut self) -> Option<Result<Row<'_>>> {
+		if self.done { return None; }
+		match self.p_stmt.raw.step() {
+			Ok(true) => Some(Ok(Row { p_stmt : self.p_stmt })),
+			//Stepping a finished statement would run it again.
+			Ok(false) => { self.done = true; None },
+			Err(e) => { self.done = true; Some(Err(e)) }
 		}
 	}
+
+	///Return the number of columns in the result.
+	pub fn column_count(&self) -> i32 {
+		self.p_stmt.column_count()
+	}
+
+	///Return the name of the column with index <i>column_index</i> in the result, the first column is 0.
+	pub fn column_name(&self, column_index : i32) -> String {
+		self.p_stmt.column_name(column_index)
+	}
+
+	///Return the declared type of the column with index <i>column_index</i> in the result, the first column is 0.
+	pub fn column_decltype(&self, column_index : i32) -> Option<String> {
+		self.p_stmt.column_decltype(column_index)
+	}
 }
 
+impl<'r> Row<'r> {
+	///Return the number of columns in the row.
+	pub fn column_count(&self) -> i32 {
+		self.p_stmt.column_count()
+	}
+
+	///Return the name of the column with index <i>column_index</i>, the first column is 0.
+	///Returns an empty String if the index is out of range.
+	pub fn column_name(&self, column_index : i32) -> String {
+		self.p_stmt.column_name(column_index)
+	}
 
-impl<'a, 'b> Cursor<'a, 'b> {
 	///Retrieve the column value as int with index <i>column_index</i>from the current row, the first column is 0.
 	pub fn get_int(&self, column_index : i32) -> i32 {
-		match self.p_stmt.p_con.db_type {
-		DbType::SQLite3 => {
-		unsafe { sqlite3_column_int(self.p_stmt.p_stmt, column_index as i32) as i32} 
-		}
-		}
+		self.p_stmt.raw.column_int64(column_index) as i32
 	}
 	///Retrieve the column value as i64 with index <i>column_index</i>from the current row, the first column is 0.
 	pub fn get_long(&self, column_index : i32) -> i64 {
-		match self.p_stmt.p_con.db_type {
-		DbType::SQLite3 => {
-		unsafe { sqlite3_column_int64(self.p_stmt.p_stmt, column_index as i32) } 
-		}
-		}
+		self.p_stmt.raw.column_int64(column_index)
 	}
 	///Retrieve the column value as float with index <i>column_index</i>from the current row, the first column is 0.
 	pub fn get_float(&self, column_index : i32) -> f32 {
-		match self.p_stmt.p_con.db_type {
-		DbType::SQLite3 => {
-		unsafe { sqlite3_column_double(self.p_stmt.p_stmt, column_index as i32) as f32} 
-		}
-		}
+		self.p_stmt.raw.column_double(column_index) as f32
 	}
 	///Retrieve the column value as double with index <i>column_index</i>from the current row, the first column is 0.
 	pub fn get_double(&self, column_index : i32) -> f64 {
-		match self.p_stmt.p_con.db_type {
-		DbType::SQLite3 => {
-		unsafe { sqlite3_column_double(self.p_stmt.p_stmt, column_index as i32) } 
-		}
-		}
+		self.p_stmt.raw.column_double(column_index)
 	}
 
 	///Retrieve the column value as String with index <i>column_index</i>from the current row, the first column is 0.
 	///If column value is NULL, returns "NULL"
+	///
+	///Invalid UTF-8 sequences are replaced by U+FFFD, use <i>get_str</i> to reject them instead.
 	pub fn get_string(&self, column_index : i32) -> String {
-		match self.p_stmt.p_con.db_type {
-		DbType::SQLite3 => {
-			if unsafe { sqlite3_column_type(self.p_stmt.p_stmt, column_index as i32) } == 5 { return "NULL".to_string(); }
-			match from_utf8(unsafe{CStr::from_ptr(sqlite3_column_text(self.p_stmt.p_stmt, column_index) as *const i8)}.to_bytes())
-			{ Err(_) => String::new(), Ok(s) => s.to_string() }
-		}
-		}
+		if self.is_null(column_index) { return "NULL".to_string(); }
+		self.get_str_lossy(column_index).into_owned()
 	}
 
 	///Retrieve the column value as an array of bytes <i>column_index</i>from the current row, the first column is 0.
 	///If column value is NULL, returns ['N', 'U', 'L', 'L']
 	pub fn get_blob(&self, column_index : i32) -> Vec<u8> {
-		match self.p_stmt.p_con.db_type {
-		DbType::SQLite3 => {
-			if unsafe { sqlite3_column_type(self.p_stmt.p_stmt, column_index as i32) } == 5 { return vec![78, 85, 76, 76]; }
-			let p = unsafe { sqlite3_column_blob(self.p_stmt.p_stmt, column_index as i32) };
-			let n = unsafe { sqlite3_column_bytes(self.p_stmt.p_stmt, column_index as i32) };
-			Vec::from(unsafe {slice::from_raw_parts(p, n as usize)})
-		}
-		}
-	}	
+		if self.is_null(column_index) { return vec![78, 85, 76, 76]; }
+		Vec::from(self.get_bytes(column_index))
+	}
+
+	///Return true if the column value with index <i>column_index</i> from the current row is NULL, the first column is 0.
+	pub fn is_null(&self, column_index : i32) -> bool {
+		self.p_stmt.raw.column_is_null(column_index)
+	}
+
+	//Return the storage class of the column value with index <i>column_index</i>, None if it is NULL.
+	pub(crate) fn column_type(&self, column_index : i32) -> Option<ColumnType> {
+		self.p_stmt.raw.column_type(column_index)
+	}
+
+	///Borrow the column value as &str with index <i>column_index</i> from the current row, the first column is 0.
+	///The text is read with its length, so it may contain NUL characters. If column value is NULL, returns "".
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not valid UTF-8.
+	pub fn get_str(&self, column_index : i32) -> Result<&str> {
+		from_utf8(self.p_stmt.raw.column_text(column_index)).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+	}
+
+	///Borrow the column value as text with index <i>column_index</i> from the current row, the first column is 0.
+	///Invalid UTF-8 sequences are replaced by U+FFFD, in which case the text is copied. If column value is NULL, returns "".
+	pub fn get_str_lossy(&self, column_index : i32) -> Cow<'_, str> {
+		String::from_utf8_lossy(self.p_stmt.raw.column_text(column_index))
+	}
+
+	///Borrow the column value as &[u8] with index <i>column_index</i> from the current row, the first column is 0.
+	///If column value is NULL, returns an empty slice.
+	pub fn get_bytes(&self, column_index : i32) -> &[u8] {
+		self.p_stmt.raw.column_blob(column_index)
+	}
+
+	///Borrow the column value with index <i>column_index</i> from the current row, tagged with its storage class.
+	///The first column is 0.
+	pub fn get_value_ref(&self, column_index : i32) -> ValueRef<'_> {
+		self.p_stmt.raw.column_value(column_index)
+	}
+
+	///Retrieve the column value as u64 with index <i>column_index</i> from the current row, the first column is 0.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not an integer, or text holding one, within the u64 range.
+	pub fn get_u64(&self, column_index : i32) -> Result<u64> {
+		u64_from_value(self.get_value_ref(column_index))
+	}
+
+	///Retrieve the column value as i128 with index <i>column_index</i> from the current row, the first column is 0.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not an integer, or text holding one, within the i128 range.
+	pub fn get_i128(&self, column_index : i32) -> Result<i128> {
+		i128_from_value(self.get_value_ref(column_index))
+	}
+
+	///Retrieve the column value as a UTC instant with index <i>column_index</i> from the current row, the first column
+	///is 0. Text is read as ISO-8601, integers as Unix seconds and reals as julian days.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not an instant.
+	pub fn get_timestamp(&self, column_index : i32) -> Result<Timestamp> {
+		Timestamp::from_value(self.get_value_ref(column_index))
+	}
+
+	///Retrieve the column value as a date with index <i>column_index</i> from the current row, the first column is 0.
+	///The value is read as by get_timestamp, and its time dropped.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not a date.
+	pub fn get_date(&self, column_index : i32) -> Result<Date> {
+		Date::from_value(self.get_value_ref(column_index))
+	}
+
+	///Retrieve the column value as a duration with index <i>column_index</i> from the current row, the first column is
+	///0. The value is a number of <i>unit</i>.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not a positive number.
+	pub fn get_duration(&self, column_index : i32, unit : TimeUnit) -> Result<Duration> {
+		duration_from_value(self.get_value_ref(column_index), unit)
+	}
+
+	///Retrieve the column value as a UUID with index <i>column_index</i> from the current row, the first column is 0.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is neither a 16-byte blob nor the text of a UUID.
+	pub fn get_uuid(&self, column_index : i32) -> Result<Uuid> {
+		Uuid::from_value(self.get_value_ref(column_index))
+	}
+
+	///Retrieve the column value as a decimal with index <i>column_index</i> from the current row, the first column is 0.
+	///Integers are read with the scale of <i>format</i>, text and reals as written.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not a decimal.
+	pub fn get_decimal(&self, column_index : i32, format : DecimalFormat) -> Result<Decimal> {
+		Decimal::from_value(self.get_value_ref(column_index), format)
+	}
+
+	///Copy the row into an OwnedRow, which remains valid after the next row is read.
+	pub fn to_owned(&self) -> OwnedRow {
+		OwnedRow::new((0..self.column_count()).map(|i| self.get_value_ref(i).to_value()).collect())
+	}
+
+	///Deserialize the row into a T with serde: a struct or a map by column name, a tuple by column position, any other
+	///type from the only column of the row. Text and blobs may be borrowed from the row.
+	///
+	///Returns an <i>InvalidData</i> IoError if the row cannot be deserialized.
+	#[cfg(feature = "serde")]
+	pub fn deserialize<'de, T : Deserialize<'de>>(&'de self) -> Result<T> {
+		from_row(self)
+	}
 }
 
 /// Allow to iterate Cursor.
 impl<'a, 'b> Iterator for Cursor<'a, 'b> {
-	type Item = Result<Cursor<'a, 'b>>;
+	type Item = Result<OwnedRow>;
 	/// Returns the next row of the Cursor.
 	///
-	///Returns a Cursor if ok, or a <i>OtherIoError</i> IoError with (if available from the underlying database)
+	///Returns an OwnedRow if ok, or a <i>OtherIoError</i> IoError with (if available from the underlying database)
 	///in the <i>detail</i> field text that describes the error, result code, and text that describes the result code.
-	fn next(&mut self) -> Option<Result<Cursor<'a, 'b>>> {
-		match self.p_stmt.p_con.db_type {
-		DbType::SQLite3 => {
-		if self.error { return None; }
-		match unsafe { sqlite3_step(self.p_stmt.p_stmt) } {
-			100 => Some(Ok(Cursor { p_stmt : self.p_stmt, error : false })),
-			101 => None,
-			err => {	self.error = true;
-					Some (Err(Error::new(ErrorKind::Other, get_error(self.p_stmt.p_con.p_db, err).as_ref()))) } }
-		}
+	fn next(&mut self) -> Option<Result<OwnedRow>> {
+		match self.rows.next() {
+			Some(Ok(row)) => Some(Ok(row.to_owned())),
+			Some(Err(e)) => Some(Err(e)),
+			None => None
 		}
 	}
 }
@@ -264,72 +588,396 @@ impl<'a, 'b> Iterator for Cursor<'a, 'b> {
 impl Connection {
 	///Open a new connection to the a database.
 	///
-	///Returns a Connection if ok, or a <i>ConnectionFailed</i> IoError with (if available from the underlying database)
+	///The connection is opened by the Driver registered under <i>db_type</i>, <i>filename</i> is the file name or connection
+	///string expected by that driver.
+	///
+	///Returns a Connection if ok, a <i>NotFound</i> IoError if no driver is registered under <i>db_type</i>,
+	///or a <i>ConnectionFailed</i> IoError with (if available from the underlying database)
 	///in the <i>detail</i> field text that describes the error, result code, and text that describes the result code.
 	pub fn new(db_type : DbType, filename : &str) -> Result<Connection> {
-		match db_type {
-			DbType::SQLite3 => {
-				let p_db : *const i8 = null();
-				match CString::new(filename) {
-					Ok(s)	=> match unsafe{sqlite3_open(s.as_ptr(), &p_db)} {
-								0 => Ok( Connection { 	db_type : DbType::SQLite3, p_db : p_db } ),
-								i => Err(Error::new(ErrorKind::ConnectionRefused, get_error(p_db, i).as_ref())) },
-					Err(e)	=> Err(Error::new(ErrorKind::Other, e))
-				}
-			}
-		}
+		let raw = get_driver(db_type)?.connect(filename)?;
+		Ok(Connection::from_raw(db_type, raw))
+	}
+
+	///Open a new connection to a database, as <i>new</i> does, and apply <i>config</i> to it.
+	///
+	///Returns the Io Error of the connection, or of the first setting that fails or that the database did not take,
+	///see <i>configure</i>.
+	pub fn open(db_type : DbType, filename : &str, config : &ConnectionConfig) -> Result<Connection> {
+		let con = Connection::new(db_type, filename)?;
+		con.configure(config)?;
+		Ok(con)
+	}
+
+	///Wrap a connection already opened by a backend.
+	pub fn from_raw(db_type : DbType, raw : Box<dyn RawConnection>) -> Connection {
+		Connection { db_type, raw }
 	}
 
 	///Prepare a statement for executing SQL instructions.
 	///
 	///Returns a Statement if ok, or an <i>InvalidInput</i> IoError with (if available from the underlying database)
-	///in the <i>detail</i> field text that describes the error, result code, and text that describes the result code.	
+	///in the <i>detail</i> field text that describes the error, result code, and text that describes the result code.
 	pub fn prepare_statement<'a>(&'a self, sql :&str) -> Result<Statement<'a>> {
+		Ok(Statement { p_con : self, raw : self.raw.prepare(sql)?, exec : false, params : Vec::new(), expansion : None })
+	}
+
+	///Execute a script of SQL statements separated by ';', in order, stopping at the first error.
+	///
+	///The ';' of the body of a CREATE TRIGGER do not separate statements. Results of queries are discarded.
+	///Returns the Io Error of the statement that failed.
+	pub fn execute_batch(&self, sql : &str) -> Result<()> {
+		for s in split_statements(sql) {
+			if let Some(e) = self.prepare_statement(&s)?.execute() { return Err(e); }
+		}
+		Ok(())
+	}
+
+	///Write the database as an SQL script re-creating it: its tables with their rows as INSERT statements, then its
+	///indexes, triggers and views, in one transaction.
+	///
+	///<i>options</i> select the tables to dump and whether to dump the schema, the data or both. Text, blobs and NULL are
+	///written as SQL literals so that restore reproduces the values and their storage classes.
+	///Returns an <i>Unsupported</i> IoError if the database is not SQLite3, a <i>NotFound</i> IoError if a named table does
+	///not exist, or the IoError of the query or of the writer that failed.
+	pub fn dump<W : Write>(&self, mut writer : W, options : &DumpOptions) -> Result<()> {
+		dump(self, &mut writer, options)
+	}
+
+	///Read an SQL script, such as one written by dump, and execute it with execute_batch.
+	///
+	///If a statement fails the open transaction is rolled back; foreign key enforcement is left as it was before the call.
+	///Returns an <i>InvalidData</i> IoError if the script is not UTF-8, or the IoError of the statement that failed.
+	pub fn restore<R : Read>(&self, mut reader : R) -> Result<()> {
+		let mut sql = String::new();
+		reader.read_to_string(&mut sql)?;
+		restore(self, &sql)
+	}
+
+	///Insert <i>entity</i>, a struct serialized with serde, as a row of the table named after the struct, which can be
+	///changed with #[serde(rename)]. Each field fills the column of the same name, see <i>Statement::bind</i>.
+	///
+	///Returns the rowid of the row, the AUTO_INCREMENT value with MySQL (0 if the table has none), None with PostgreSQL,
+	///which has no rowid: use an Insert query with RETURNING to read generated keys. Returns the Io Error of the
+	///serialization or of the statement that failed.
+	#[cfg(feature = "serde")]
+	pub fn insert<T : Serialize + ?Sized>(&self, entity : &T) -> Result<Option<i64>> {
+		insert(self, None, entity)
+	}
+
+	///Insert <i>entity</i>, a struct or a map serialized with serde, as a row of the table <i>table</i>.
+	///
+	///Returns the rowid of the row as <i>insert</i> does, or the Io Error of the serialization or of the statement that failed.
+	#[cfg(feature = "serde")]
+	pub fn insert_into<T : Serialize + ?Sized>(&self, table : &str, entity : &T) -> Result<Option<i64>> {
+		insert(self, Some(table), entity)
+	}
+
+	///Read a CSV text and insert its records into the table <i>table</i>, as described by <i>options</i>.
+	///
+	///The fields of a record are converted according to the declared types of their columns. A record that is malformed,
+	///cannot be converted or is refused by the database is rejected; the import stops when more records than
+	///<i>options.max_errors</i> are rejected, rolling back the current batch of rows.
+	///Returns a CsvReport with the number of rows inserted and the rejected records, an <i>Unsupported</i> IoError if the
+	///database cannot be introspected, a <i>NotFound</i> IoError if there is no such table, an <i>InvalidInput</i> IoError
+	///if a field of the header matches no column, or an <i>InvalidData</i> IoError giving the line of the record that
+	///stopped the import.
+	pub fn import_csv<R : Read>(&self, reader : R, table : &str, options : &CsvOptions) -> Result<CsvReport> {
+		import_csv(self, &mut BufReader::new(reader), table, options)
+	}
+
+	///Return the rowid of the most recent successful INSERT into a rowid table on this connection, or 0 if none.
+	pub fn last_insert_rowid(&self) -> i64 {
+		self.raw.last_insert_rowid()
+	}
+
+	///Return the number of rows inserted, modified or deleted by the most recently completed INSERT, UPDATE or DELETE statement.
+	pub fn changes64(&self) -> i64 {
+		self.raw.changes()
+	}
+
+	///Return the total number of rows inserted, modified or deleted by all INSERT, UPDATE or DELETE statements
+	///completed since the connection was opened.
+	pub fn total_changes(&self) -> i64 {
+		self.raw.total_changes()
+	}
+
+	///Return true if the connection is in autocommit mode, that is to say outside of an explicit transaction.
+	pub fn is_autocommit(&self) -> bool {
+		self.raw.is_autocommit()
+	}
+
+	///Return the type of database the connection is connected to.
+	pub fn db_type(&self) -> DbType {
+		self.db_type
+	}
+
+	///Apply the settings of <i>config</i> to the connection, in order, stopping at the first that fails.
+	///
+	///Returns an <i>Unsupported</i> IoError if the database is not SQLite3, an <i>Other</i> IoError if the database did
+	///not take a setting (WAL on an in-memory database, foreign keys within a transaction...), or the Io Error of the
+	///PRAGMA that failed.
+	pub fn configure(&self, config : &ConnectionConfig) -> Result<()> {
+		config.apply(self)
+	}
+
+	///Return the journal mode of the main database.
+	///
+	///Each PRAGMA getter and setter returns an <i>Unsupported</i> IoError if the database is not SQLite3, and each
+	///setter an <i>Other</i> IoError if the value read back is not the value set.
+	pub fn journal_mode(&self) -> Result<JournalMode> {
+		pragma::journal_mode(self)
+	}
+
+	///Change the journal mode of the main database. Returns an <i>Other</i> IoError if the mode could not be changed,
+	///such as WAL for an in-memory database, or any mode but WAL while another connection uses the WAL.
+	pub fn set_journal_mode(&self, mode : JournalMode) -> Result<()> {
+		pragma::set_journal_mode(self, mode)
+	}
+
+	///Return the synchronous setting of the main database.
+	pub fn synchronous(&self) -> Result<Synchronous> {
+		pragma::synchronous(self)
+	}
+
+	///Change the synchronous setting of the main database.
+	pub fn set_synchronous(&self, synchronous : Synchronous) -> Result<()> {
+		pragma::set_synchronous(self, synchronous)
+	}
+
+	///Return true if foreign key constraints are enforced.
+	pub fn foreign_keys(&self) -> Result<bool> {
+		pragma::foreign_keys(self)
+	}
+
+	///Enforce foreign key constraints or not. Returns an <i>Other</i> IoError within a transaction, where SQLite ignores
+	///the change.
+	pub fn set_foreign_keys(&self, enabled : bool) -> Result<()> {
+		pragma::set_foreign_keys(self, enabled)
+	}
+
+	///Return how long a statement waits for a lock held by another connection before failing with SQLITE_BUSY.
+	pub fn busy_timeout(&self) -> Result<Duration> {
+		pragma::busy_timeout(self)
+	}
+
+	///Change how long a statement waits for a lock held by another connection, to the millisecond.
+	pub fn set_busy_timeout(&self, timeout : Duration) -> Result<()> {
+		pragma::set_busy_timeout(self, timeout)
+	}
+
+	///Return the size of the page cache: a number of pages if positive, of kibibytes if negative.
+	pub fn cache_size(&self) -> Result<i64> {
+		pragma::cache_size(self)
+	}
+
+	///Change the size of the page cache: a number of pages if positive, of kibibytes if negative.
+	pub fn set_cache_size(&self, size : i64) -> Result<()> {
+		pragma::set_cache_size(self, size)
+	}
+
+	///Return the number of bytes of the database file mapped in memory, 0 if memory-mapped I/O is disabled.
+	pub fn mmap_size(&self) -> Result<u64> {
+		pragma::mmap_size(self)
+	}
+
+	///Change the number of bytes of the database file mapped in memory, 0 to disable memory-mapped I/O.
+	///Returns the size in effect, which SQLite caps at the maximum it was compiled with.
+	pub fn set_mmap_size(&self, size : u64) -> Result<u64> {
+		pragma::set_mmap_size(self, size)
+	}
+
+	///Return where temporary tables and indexes are stored.
+	pub fn temp_store(&self) -> Result<TempStore> {
+		pragma::temp_store(self)
+	}
+
+	///Change where temporary tables and indexes are stored.
+	pub fn set_temp_store(&self, store : TempStore) -> Result<()> {
+		pragma::set_temp_store(self, store)
+	}
+
+	///Check the whole structure of a database, main if <i>schema</i> is None, with PRAGMA integrity_check.
+	///Returns the problems found, an empty list if the database is sound.
+	///
+	///Each maintenance method returns an <i>Unsupported</i> IoError if the database is not SQLite3, or the Io Error of
+	///the statement that failed.
+	pub fn integrity_check(&self, schema : Option<&str>) -> Result<Vec<String>> {
+		maintenance::integrity_check(self, schema)
+	}
+
+	///Check the structure of a database as <i>integrity_check</i>, faster, without verifying that the indexes match
+	///their tables.
+	pub fn quick_check(&self, schema : Option<&str>) -> Result<Vec<String>> {
+		maintenance::quick_check(self, schema)
+	}
+
+	///Find the rows that break a foreign key, in <i>table</i> or in every table of the schema.
+	pub fn foreign_key_check(&self, schema : Option<&str>, table : Option<&str>) -> Result<Vec<ForeignKeyViolation>> {
+		maintenance::foreign_key_check(self, schema, table)
+	}
+
+	///Write a compacted copy of a database to <i>path</i>, which must not exist or be empty.
+	pub fn vacuum_into(&self, schema : Option<&str>, path : &str) -> Result<()> {
+		maintenance::vacuum_into(self, schema, path)
+	}
+
+	///Give back up to <i>pages</i> free pages of a database to the file system, all of them if None, and return the
+	///number of pages given back. Nothing is given back unless the database was created with auto_vacuum INCREMENTAL.
+	pub fn incremental_vacuum(&self, schema : Option<&str>, pages : Option<u32>) -> Result<u64> {
+		maintenance::incremental_vacuum(self, schema, pages)
+	}
+
+	///Gather statistics for the query planner with ANALYZE, for a table, a schema, or every schema if both are None.
+	pub fn analyze(&self, schema : Option<&str>, table : Option<&str>) -> Result<()> {
+		maintenance::analyze(self, schema, table)
+	}
+
+	///Gather the statistics that SQLite deems useful with PRAGMA optimize, best run before closing a long-lived
+	///connection.
+	pub fn optimize(&self) -> Result<()> {
+		maintenance::optimize(self)
+	}
+
+	///Return the page size, page count and free pages of a database, with the space used by each table and index when
+	///SQLite has the dbstat virtual table.
+	pub fn page_stats(&self, schema : Option<&str>) -> Result<PageStats> {
+		maintenance::page_stats(self, schema)
+	}
+
+	///Copy the frames of the write-ahead log back into the database file, see <i>Sqlite3Connection::wal_checkpoint</i>.
+	///
+	///Each WAL method returns an <i>Unsupported</i> IoError if the database is not SQLite3.
+	pub fn wal_checkpoint(&self, schema : Option<&str>, mode : CheckpointMode) -> Result<WalCheckpoint> {
+		self.sqlite()?.wal_checkpoint(schema, mode)
+	}
+
+	///Register a closure called after each commit to the write-ahead log, given the connection, the name of the
+	///database and the number of frames in its WAL. See <i>Sqlite3Connection::set_wal_hook</i>.
+	pub fn set_wal_hook<F>(&self, hook : F) -> Result<()> where F : FnMut(&Sqlite3Connection, &str, i32) + 'static {
+		self.sqlite()?.set_wal_hook(hook)
+	}
+
+	///Remove the closure registered by <i>set_wal_hook</i>.
+	pub fn clear_wal_hook(&self) -> Result<()> {
+		self.sqlite()?.clear_wal_hook()
+	}
+
+	///Checkpoint automatically once the write-ahead log holds <i>frames</i> frames or more, 0 or less to disable
+	///automatic checkpoints. This replaces the closure registered by <i>set_wal_hook</i>.
+	pub fn set_wal_autocheckpoint(&self, frames : i32) -> Result<()> {
+		self.sqlite()?.set_wal_autocheckpoint(frames)
+	}
+
+	///Attach a database to the connection under the schema name <i>name</i>, which prefixes its tables in SQL
+	///statements and names it for the introspector. A file is opened in <i>mode</i>, as a URI filename.
+	///
+	///Returns an <i>Unsupported</i> IoError if the database is not SQLite3, an <i>AlreadyExists</i> IoError if the name
+	///is main, temp or already attached, a <i>QuotaExceeded</i> IoError if as many databases as SQLite allows are
+	///attached, or an <i>Other</i> IoError if the database could not be opened.
+	pub fn attach(&self, target : AttachTarget, name : &str, mode : OpenMode) -> Result<()> {
+		attach::attach(self, target, name, mode)
+	}
+
+	///Detach the database attached under <i>name</i>.
+	///
+	///Returns an <i>Unsupported</i> IoError if the database is not SQLite3, an <i>InvalidInput</i> IoError for main or
+	///temp, a <i>NotFound</i> IoError if no database is attached under that name, or an <i>Other</i> IoError if it is in
+	///use by a pending statement or a transaction.
+	pub fn detach(&self, name : &str) -> Result<()> {
+		attach::detach(self, name)
+	}
+
+	///List the databases of the connection: main, temp once used, then the attached databases in the order they were
+	///attached.
+	///
+	///Returns an <i>Unsupported</i> IoError if the database is not SQLite3.
+	pub fn databases(&self) -> Result<Vec<Database>> {
+		attach::databases(self)
+	}
+
+	///Copy a database, main if <i>schema</i> is None, into a byte vector: the image of its file, which
+	///<i>from_bytes</i> or <i>deserialize</i> read back.
+	///
+	///Returns an <i>Unsupported</i> IoError if the database is not SQLite3, or a <i>NotFound</i> IoError if there is
+	///no such database.
+	pub fn serialize(&self, schema : Option<&str>) -> Result<Vec<u8>> {
+		self.sqlite()?.serialize(schema)
+	}
+
+	///Replace a database, main if <i>schema</i> is None, with a copy of <i>bytes</i>, the image of a database file.
+	///The database is then kept in memory, read-only or growing as it is written.
+	///
+	///Returns an <i>Unsupported</i> IoError if the database is not SQLite3, a <i>NotFound</i> IoError if there is no
+	///such database, or an <i>Other</i> IoError if it is in use by a pending statement or a transaction.
+	pub fn deserialize(&self, schema : Option<&str>, bytes : &[u8], read_only : bool) -> Result<()> {
+		self.sqlite()?.deserialize(schema, bytes, read_only)
+	}
+
+	///Open an in-memory SQLite3 database holding a copy of <i>bytes</i>, the image of a database file such as
+	///<i>serialize</i> returns, read-only or growing as it is written.
+	///
+	///Returns the Io Error of the connection, or an <i>Other</i> IoError if the bytes are not a database, which may
+	///only show at the first statement.
+	pub fn from_bytes(bytes : &[u8], read_only : bool) -> Result<Connection> {
+		let con = Connection::new(DbType::SQLite3, ":memory:")?;
+		con.deserialize(None, bytes, read_only)?;
+		Ok(con)
+	}
+
+	///Load the SQLite extension at <i>path</i>, calling its <i>entry_point</i> function, or the entry point SQLite
+	///derives from the file name if None. Extensions can be loaded this way only: loading stays disabled on the
+	///connection but for the duration of the call, and the SQL function load_extension() is never enabled.
+	///
+	///Returns an <i>Unsupported</i> IoError if the database is not SQLite3, or an <i>Other</i> IoError with the message
+	///of SQLite if the extension could not be loaded.
+	pub fn load_extension(&self, path : &str, entry_point : Option<&str>) -> Result<()> {
+		self.sqlite()?.load_extension(path, entry_point)
+	}
+
+	//Return the SQLite3 side of the connection.
+	fn sqlite(&self) -> Result<&Sqlite3Connection> {
+		self.raw.as_any().downcast_ref::<Sqlite3Connection>()
+			.ok_or_else(|| Error::new(ErrorKind::Unsupported, format!("{:?} is not SQLite3", self.db_type)))
+	}
+
+	///Return the Introspector reading the structure of the database.
+	///
+	///Returns an <i>Unsupported</i> IoError if there is no Introspector for the type of database.
+	pub fn introspector(&self) -> Result<Box<dyn Introspector + '_>> {
 		match self.db_type {
-			DbType::SQLite3 => {
-				let p_stmt  : *const i8 = null();
-				let pz_tail : *const i8 = null();
-		
-				match CString::new(sql) {
-					Ok(s)	=> match unsafe { sqlite3_prepare_v2(self.p_db, s.as_ptr(), -1, &p_stmt, &pz_tail) } {
-								0 => Ok(Statement { p_con : self, p_stmt : p_stmt, exec : false }),
-								e => Err(Error::new(ErrorKind::InvalidInput, get_error(self.p_db, e).as_ref())) },
-					Err(e)	=> Err(Error::new(ErrorKind::Other, e))
-				}
-			}
+			DbType::SQLite3 => Ok(Box::new(SqliteIntrospector::new(self))),
+			t => Err(Error::new(ErrorKind::Unsupported, format!("no introspection for {:?}", t)))
 		}
 	}
 
+	///Give access to the backend side of the connection, for backend specific features.
+	pub fn raw(&self) -> &dyn RawConnection {
+		&*self.raw
+	}
 }
 
-impl Drop for Connection {
-	///The drop method is called when Connection goes out of scope, and therefore close properly the connection.
-	fn drop(&mut self) {
-		match self.db_type {
-			DbType::SQLite3 => { if !self.p_db.is_null() { unsafe { sqlite3_close_v2(self.p_db); } } }
+//Bind a parameter value to the placeholders starting at <i>position</i>.
+fn bind_raw<'a>(raw : &mut (dyn RawStatement + 'a), position : i32, param : &Param<'a>) -> Result<()> {
+	match *param {
+		Param::Unset => Ok(()),
+		Param::Value(ref v) => bind_value(raw, position, v),
+		//value outlives the raw statement, which lives no longer than 'a
+		Param::Text(t) => unsafe { raw.bind_text_in_place(position, t) },
+		Param::Blob(b) => unsafe { raw.bind_blob_in_place(position, b) },
+		Param::List(ref values) => {
+			for (i, v) in values.iter().enumerate() { bind_value(raw, position + i as i32, v)?; }
+			Ok(())
 		}
 	}
 }
 
-/*
-//https://github.com/mozilla/rust/issues/13853
-impl<'a> Drop for Statement<'a> {
-	fn drop(&mut self) {}
+fn bind_value(raw : &mut dyn RawStatement, position : i32, value : &Value) -> Result<()> {
+	match *value {
+		Value::Null => raw.bind_null(position),
+		Value::Integer(i) => raw.bind_int64(position, i),
+		Value::Real(f) => raw.bind_double(position, f),
+		Value::Text(ref s) => raw.bind_text(position, s),
+		Value::Blob(ref b) => raw.bind_blob(position, b)
+	}
 }
-*/
-
-fn get_error<'a>(p_db : *const i8, errno : i32) -> String {
-	let mut buf=String::new();	
-	match from_utf8( unsafe{CStr::from_ptr(sqlite3_errmsg(p_db))}.to_bytes() ) {
-		Ok(s) => buf.push_str(s),
-		Err(_) => ()
-	}
-	if !buf.is_empty() { buf.push(' '); }
-	buf.push('('); buf.push_str(errno.to_string().as_ref());
-	match from_utf8( unsafe{CStr::from_ptr(sqlite3_errstr(errno))}.to_bytes() ) {
-		Ok(s) => { buf.push(':');  buf.push_str(s); }
-		Err(_) => ()
-	}
-	buf.push(')');
-	buf
-}
\ No newline at end of file
@@ -0,0 +1,345 @@
+//Hash functions and encodings needed by the authentication exchanges of the network backends.
+//They are only used on small inputs (passwords, nonces), simplicity is preferred over speed.
+//
+//The backends speak their protocols without TLS: a cleartext password, and over TCP the RSA exchange of MySQL with
+//the public key the server sends, travel unprotected on the network, and are only as safe as the network itself.
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Result};
+
+//MD5 digest (RFC 1321).
+pub fn md5(data : &[u8]) -> [u8; 16] {
+	const S : [u32; 64] = [7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22,
+						   5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20,
+						   4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
+						   6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21];
+	let k : Vec<u32> = (0..64).map(|i| ((i as f64 + 1.0).sin().abs() * 4294967296.0) as u32).collect();
+	let mut h : [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+	for chunk in pad(data, false).chunks(64) {
+		let m : Vec<u32> = chunk.chunks(4).map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]])).collect();
+		let (mut a, mut b, mut c, mut d) = (h[0], h[1], h[2], h[3]);
+		for i in 0..64 {
+			let (f, g) = match i / 16 {
+				0 => ((b & c) | (!b & d), i),
+				1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+				2 => (b ^ c ^ d, (3 * i + 5) % 16),
+				_ => (c ^ (b | !d), (7 * i) % 16)
+			};
+			let f = f.wrapping_add(a).wrapping_add(k[i]).wrapping_add(m[g]);
+			a = d; d = c; c = b;
+			b = b.wrapping_add(f.rotate_left(S[i]));
+		}
+		h[0] = h[0].wrapping_add(a); h[1] = h[1].wrapping_add(b);
+		h[2] = h[2].wrapping_add(c); h[3] = h[3].wrapping_add(d);
+	}
+	let mut out = [0u8; 16];
+	for (i, v) in h.iter().enumerate() { out[i * 4..i * 4 + 4].copy_from_slice(&v.to_le_bytes()); }
+	out
+}
+
+//SHA-256 digest (FIPS 180-4).
+pub fn sha256(data : &[u8]) -> [u8; 32] {
+	const K : [u32; 64] = [
+		0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+		0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+		0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+		0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+		0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+		0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+		0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+		0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2];
+	let mut h : [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+	for chunk in pad(data, true).chunks(64) {
+		let mut w = [0u32; 64];
+		for i in 0..16 { w[i] = u32::from_be_bytes([chunk[i * 4], chunk[i * 4 + 1], chunk[i * 4 + 2], chunk[i * 4 + 3]]); }
+		for i in 16..64 {
+			let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+			let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+			w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+		}
+		let mut v = h;
+		for i in 0..64 {
+			let s1 = v[4].rotate_right(6) ^ v[4].rotate_right(11) ^ v[4].rotate_right(25);
+			let ch = (v[4] & v[5]) ^ (!v[4] & v[6]);
+			let t1 = v[7].wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+			let s0 = v[0].rotate_right(2) ^ v[0].rotate_right(13) ^ v[0].rotate_right(22);
+			let maj = (v[0] & v[1]) ^ (v[0] & v[2]) ^ (v[1] & v[2]);
+			let t2 = s0.wrapping_add(maj);
+			v = [t1.wrapping_add(t2), v[0], v[1], v[2], v[3].wrapping_add(t1), v[4], v[5], v[6]];
+		}
+		for i in 0..8 { h[i] = h[i].wrapping_add(v[i]); }
+	}
+	let mut out = [0u8; 32];
+	for (i, v) in h.iter().enumerate() { out[i * 4..i * 4 + 4].copy_from_slice(&v.to_be_bytes()); }
+	out
+}
+
+//SHA-1 digest (FIPS 180-4).
+pub fn sha1(data : &[u8]) -> [u8; 20] {
+	let mut h : [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+	for chunk in pad(data, true).chunks(64) {
+		let mut w = [0u32; 80];
+		for i in 0..16 { w[i] = u32::from_be_bytes([chunk[i * 4], chunk[i * 4 + 1], chunk[i * 4 + 2], chunk[i * 4 + 3]]); }
+		for i in 16..80 { w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1); }
+		let (mut a, mut b, mut c, mut d, mut e) = (h[0], h[1], h[2], h[3], h[4]);
+		for (i, wi) in w.iter().enumerate() {
+			let (f, k) = match i / 20 {
+				0 => ((b & c) | (!b & d), 0x5a827999),
+				1 => (b ^ c ^ d, 0x6ed9eba1),
+				2 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+				_ => (b ^ c ^ d, 0xca62c1d6)
+			};
+			let t = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*wi);
+			e = d; d = c; c = b.rotate_left(30); b = a; a = t;
+		}
+		h[0] = h[0].wrapping_add(a); h[1] = h[1].wrapping_add(b); h[2] = h[2].wrapping_add(c);
+		h[3] = h[3].wrapping_add(d); h[4] = h[4].wrapping_add(e);
+	}
+	let mut out = [0u8; 20];
+	for (i, v) in h.iter().enumerate() { out[i * 4..i * 4 + 4].copy_from_slice(&v.to_be_bytes()); }
+	out
+}
+
+//Merkle-Damgard padding shared by MD5 (little endian length) and the SHA family (big endian length).
+fn pad(data : &[u8], big_endian : bool) -> Vec<u8> {
+	let mut m = data.to_vec();
+	m.push(0x80);
+	while m.len() % 64 != 56 { m.push(0); }
+	let bits = (data.len() as u64).wrapping_mul(8);
+	m.extend_from_slice(&if big_endian { bits.to_be_bytes() } else { bits.to_le_bytes() });
+	m
+}
+
+//HMAC-SHA-256 (RFC 2104).
+pub fn hmac_sha256(key : &[u8], data : &[u8]) -> [u8; 32] {
+	let mut k = [0u8; 64];
+	if key.len() > 64 { k[..32].copy_from_slice(&sha256(key)); } else { k[..key.len()].copy_from_slice(key); }
+	let mut inner : Vec<u8> = k.iter().map(|b| b ^ 0x36).collect();
+	inner.extend_from_slice(data);
+	let mut outer : Vec<u8> = k.iter().map(|b| b ^ 0x5c).collect();
+	outer.extend_from_slice(&sha256(&inner));
+	sha256(&outer)
+}
+
+//PBKDF2 with HMAC-SHA-256, for a single 32 bytes block (the Hi function of SCRAM, RFC 5802).
+pub fn pbkdf2_sha256(password : &[u8], salt : &[u8], iterations : u32) -> [u8; 32] {
+	let mut s = salt.to_vec();
+	s.extend_from_slice(&1u32.to_be_bytes());
+	let mut u = hmac_sha256(password, &s);
+	let mut out = u;
+	for _ in 1..iterations {
+		u = hmac_sha256(password, &u);
+		for (o, b) in out.iter_mut().zip(u.iter()) { *o ^= b; }
+	}
+	out
+}
+
+//Lower case hexadecimal encoding.
+pub fn to_hex(data : &[u8]) -> String {
+	const DIGITS : &[u8; 16] = b"0123456789abcdef";
+	let mut s = String::with_capacity(data.len() * 2);
+	for b in data { s.push(DIGITS[(b >> 4) as usize] as char); s.push(DIGITS[(b & 15) as usize] as char); }
+	s
+}
+
+//Hexadecimal decoding, either case. None if the text has an odd length or a character that is not a hexadecimal digit.
+pub fn from_hex(text : &str) -> Option<Vec<u8>> {
+	let t = text.as_bytes();
+	if !t.len().is_multiple_of(2) { return None; }
+	let digit = |c : u8| (c as char).to_digit(16).map(|d| d as u8);
+	t.chunks(2).map(|c| Some(digit(c[0])? << 4 | digit(c[1])?)).collect()
+}
+
+const BASE64 : &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+//Standard base64 encoding, with padding.
+pub fn base64_encode(data : &[u8]) -> String {
+	let mut s = String::with_capacity(data.len().div_ceil(3) * 4);
+	for c in data.chunks(3) {
+		let n = (c[0] as u32) << 16 | (*c.get(1).unwrap_or(&0) as u32) << 8 | *c.get(2).unwrap_or(&0) as u32;
+		for i in 0..4 {
+			if i <= c.len() { s.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char); } else { s.push('='); }
+		}
+	}
+	s
+}
+
+//Standard base64 decoding, padding optional. None if the text is not valid base64.
+pub fn base64_decode(text : &str) -> Option<Vec<u8>> {
+	let t = text.trim_end_matches('=').as_bytes();
+	let mut out = Vec::with_capacity(t.len() * 3 / 4);
+	let (mut acc, mut bits) = (0u32, 0);
+	for c in t {
+		acc = acc << 6 | BASE64.iter().position(|b| b == c)? as u32;
+		bits += 6;
+		if bits >= 8 { bits -= 8; out.push((acc >> bits) as u8); }
+	}
+	Some(out)
+}
+
+//Fill a buffer with unpredictable bytes from the system random source. There is no fallback: the bytes make nonces
+//and padding seeds, an error is better than guessable ones.
+pub fn random_bytes(buf : &mut [u8]) -> Result<()> {
+	File::open("/dev/urandom").and_then(|mut f| f.read_exact(buf))
+		.map_err(|e| Error::new(ErrorKind::Unsupported, format!("no system random source: {}", e)))
+}
+
+//RSA encryption with OAEP padding, SHA-1 and MGF1-SHA-1 (RFC 8017), under a public key in PEM format:
+//SubjectPublicKeyInfo (BEGIN PUBLIC KEY) or PKCS#1 (BEGIN RSA PUBLIC KEY). None if the key cannot be read
+//or the message is too long for it, an error if no random seed can be drawn.
+pub fn rsa_oaep_sha1_encrypt(pem : &str, message : &[u8]) -> Result<Option<Vec<u8>>> {
+	let mut seed = [0u8; 20];
+	random_bytes(&mut seed)?;
+	Ok(rsa_oaep_sha1_encrypt_seeded(pem, message, seed))
+}
+
+//RSA-OAEP encryption with a given seed, which makes it deterministic.
+pub fn rsa_oaep_sha1_encrypt_seeded(pem : &str, message : &[u8], mut seed : [u8; 20]) -> Option<Vec<u8>> {
+	let body : String = pem.lines().filter(|l| !l.starts_with("-----")).map(|l| l.trim()).collect();
+	let der = base64_decode(&body)?;
+	let (n, e) = rsa_public_key(&der)?;
+	let k = n.len();
+	if k < 2 * 20 + 2 || message.len() > k - 2 * 20 - 2 { return None; }
+	let mut db = sha1(b"").to_vec();
+	db.resize(k - message.len() - 20 - 2, 0);
+	db.push(1);
+	db.extend_from_slice(message);
+	for (d, m) in db.iter_mut().zip(mgf1_sha1(&seed, k - 20 - 1)) { *d ^= m; }
+	for (s, m) in seed.iter_mut().zip(mgf1_sha1(&db, 20)) { *s ^= m; }
+	let mut em = vec![0u8];
+	em.extend_from_slice(&seed);
+	em.extend_from_slice(&db);
+	let c = Big::from_bytes(&em).pow_mod(&Big::from_bytes(e), &Big::from_bytes(n));
+	Some(c.to_bytes(k))
+}
+
+fn mgf1_sha1(seed : &[u8], len : usize) -> Vec<u8> {
+	let mut out = Vec::with_capacity(len + 20);
+	let mut counter = 0u32;
+	while out.len() < len {
+		let mut block = seed.to_vec();
+		block.extend_from_slice(&counter.to_be_bytes());
+		out.extend_from_slice(&sha1(&block));
+		counter += 1;
+	}
+	out.truncate(len);
+	out
+}
+
+//Read the modulus and the public exponent, without leading zeros, from a DER encoded RSA public key.
+fn rsa_public_key(der : &[u8]) -> Option<(&[u8], &[u8])> {
+	let (tag, seq, _) = der_read(der)?;
+	if tag != 0x30 { return None; }
+	let (tag, first, rest) = der_read(seq)?;
+	match tag {
+		//SubjectPublicKeyInfo: the algorithm, then the PKCS#1 key in a bit string with no unused bits.
+		0x30 => {
+			let (tag, bits, _) = der_read(rest)?;
+			if tag != 0x03 || bits.first() != Some(&0) { return None; }
+			rsa_public_key(&bits[1..])
+		}
+		0x02 => {
+			let (tag, e, _) = der_read(rest)?;
+			if tag != 0x02 { return None; }
+			Some((strip_zeros(first), strip_zeros(e)))
+		}
+		_ => None
+	}
+}
+
+fn strip_zeros(v : &[u8]) -> &[u8] {
+	&v[v.iter().position(|&b| b != 0).unwrap_or(v.len())..]
+}
+
+//Read a DER element: its tag, its content and what follows it.
+fn der_read(der : &[u8]) -> Option<(u8, &[u8], &[u8])> {
+	let tag = *der.first()?;
+	let first = *der.get(1)? as usize;
+	let (len, start) = if first < 0x80 { (first, 2) } else {
+		let n = first & 0x7f;
+		if n == 0 || n > 4 { return None; }
+		(der.get(2..2 + n)?.iter().fold(0usize, |a, &b| a << 8 | b as usize), 2 + n)
+	};
+	let content = der.get(start..start + len)?;
+	Some((tag, content, &der[start + len..]))
+}
+
+//Unsigned big integer, little endian 32 bits limbs. Only what a public key operation needs: the exponent is small,
+//so a bitwise modular reduction is fast enough.
+struct Big(Vec<u32>);
+
+impl Big {
+	fn from_bytes(b : &[u8]) -> Big {
+		let mut v : Vec<u32> = b.rchunks(4).map(|c| c.iter().fold(0u32, |a, &x| a << 8 | x as u32)).collect();
+		while v.last() == Some(&0) { v.pop(); }
+		Big(v)
+	}
+
+	fn to_bytes(&self, len : usize) -> Vec<u8> {
+		let mut out = vec![0u8; len];
+		for (i, limb) in self.0.iter().enumerate() {
+			for (j, b) in limb.to_le_bytes().iter().enumerate() {
+				if i * 4 + j < len { out[len - 1 - i * 4 - j] = *b; }
+			}
+		}
+		out
+	}
+
+	fn bits(&self) -> usize {
+		match self.0.last() { Some(l) => self.0.len() * 32 - l.leading_zeros() as usize, None => 0 }
+	}
+
+	fn bit(&self, i : usize) -> bool {
+		self.0.get(i / 32).is_some_and(|l| l >> (i % 32) & 1 == 1)
+	}
+
+	fn mul(&self, o : &Big) -> Big {
+		let mut r = vec![0u32; self.0.len() + o.0.len()];
+		for (i, &a) in self.0.iter().enumerate() {
+			let mut carry = 0u64;
+			for (j, &b) in o.0.iter().enumerate() {
+				let t = a as u64 * b as u64 + r[i + j] as u64 + carry;
+				r[i + j] = t as u32;
+				carry = t >> 32;
+			}
+			r[i + o.0.len()] = carry as u32;
+		}
+		while r.last() == Some(&0) { r.pop(); }
+		Big(r)
+	}
+
+	fn rem(&self, m : &Big) -> Big {
+		let mut r = Big(Vec::with_capacity(m.0.len() + 1));
+		for i in (0..self.bits()).rev() {
+			//r = r * 2 + bit
+			let mut carry = self.bit(i) as u32;
+			for l in r.0.iter_mut() { let c = *l >> 31; *l = *l << 1 | carry; carry = c; }
+			if carry != 0 { r.0.push(carry); }
+			if r.cmp(m) != ::std::cmp::Ordering::Less { r.sub(m); }
+		}
+		r
+	}
+
+	fn cmp(&self, o : &Big) -> ::std::cmp::Ordering {
+		self.0.len().cmp(&o.0.len()).then_with(|| self.0.iter().rev().cmp(o.0.iter().rev()))
+	}
+
+	fn sub(&mut self, o : &Big) {
+		let mut borrow = 0i64;
+		for i in 0..self.0.len() {
+			let t = self.0[i] as i64 - borrow - *o.0.get(i).unwrap_or(&0) as i64;
+			self.0[i] = t as u32;
+			borrow = (t < 0) as i64;
+		}
+		while self.0.last() == Some(&0) { self.0.pop(); }
+	}
+
+	fn pow_mod(&self, e : &Big, m : &Big) -> Big {
+		let mut r = Big(vec![1]).rem(m);
+		let base = self.rem(m);
+		for i in (0..e.bits()).rev() {
+			r = r.mul(&r).rem(m);
+			if e.bit(i) { r = r.mul(&base).rem(m); }
+		}
+		r
+	}
+}
@@ -0,0 +1,387 @@
+use std::fmt;
+use std::io::{BufRead, Error, ErrorKind, Result, Write};
+use sql::{Connection, Statement};
+use sql::catalog::quote;
+use sql::columnar::{affinity, ColumnType};
+use sql::value::{BlobEncoding, Value, ValueRef};
+
+///Format of a CSV text, and how it is imported into a table.
+///
+///The default format is the one of RFC 4180: fields separated by ',', records terminated by CRLF (LF is accepted on
+///import), fields holding the delimiter, a quote or a line break enclosed in '"' with inner quotes doubled. The first
+///record is a header naming the columns. NULL is written as an empty unquoted field, so an empty text is written "";
+///blobs are written in hexadecimal.
+///
+///On import each field is converted according to the affinity of the declared type of its column: INTEGER and REAL
+///columns require a number, BLOB columns a blob in the chosen encoding, columns without affinity take a number when the
+///field is one and text otherwise. Rows are inserted in transactions of <i>batch_size</i> rows.
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+	delimiter : u8,
+	null : String,
+	blobs : BlobEncoding,
+	header : bool,
+	mapping : Vec<(String, Option<String>)>,
+	batch_size : usize,
+	max_errors : usize
+}
+
+///Outcome of a CSV import: the number of rows inserted and the rows rejected.
+#[derive(Clone, Debug, Default)]
+pub struct CsvReport {
+	rows : u64,
+	errors : Vec<CsvError>
+}
+
+///A record of a CSV text that could not be imported, with the line where it starts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvError {
+	line : u64,
+	message : String
+}
+
+impl CsvOptions {
+	///Create the options of the RFC 4180 format, with a header, importing in batches of 1000 rows and stopping at the
+	///first rejected row.
+	pub fn new() -> CsvOptions {
+		CsvOptions { delimiter : b',', null : String::new(), blobs : BlobEncoding::Hex, header : true, mapping : Vec::new(),
+					 batch_size : 1000, max_errors : 0 }
+	}
+
+	///Set the character separating fields, such as b';' or b'\t'.
+	pub fn delimiter(mut self, delimiter : u8) -> CsvOptions {
+		self.delimiter = delimiter;
+		self
+	}
+
+	///Set the text of a NULL field, empty by default. On import only unquoted fields are read as NULL.
+	pub fn null(mut self, null : &str) -> CsvOptions {
+		self.null = null.to_string();
+		self
+	}
+
+	///Set the encoding of blobs.
+	pub fn blobs(mut self, blobs : BlobEncoding) -> CsvOptions {
+		self.blobs = blobs;
+		self
+	}
+
+	///Set whether the first record is a header naming the columns. Without header, the fields of an imported record
+	///fill the columns of the table in order.
+	pub fn header(mut self, header : bool) -> CsvOptions {
+		self.header = header;
+		self
+	}
+
+	///On import, fill the column <i>column</i> of the table with the field named <i>field</i> in the header.
+	///Fields are compared case-insensitively; by default a field fills the column of the same name.
+	pub fn map(mut self, field : &str, column : &str) -> CsvOptions {
+		self.mapping.push((field.to_string(), Some(column.to_string())));
+		self
+	}
+
+	///On import, skip the field named <i>field</i> in the header.
+	pub fn ignore(mut self, field : &str) -> CsvOptions {
+		self.mapping.push((field.to_string(), None));
+		self
+	}
+
+	///Set the number of rows inserted per transaction on import, at least 1.
+	pub fn batch_size(mut self, batch_size : usize) -> CsvOptions {
+		self.batch_size = batch_size.max(1);
+		self
+	}
+
+	///Set the number of rows that may be rejected on import before it stops; rejected rows are listed in the CsvReport.
+	pub fn max_errors(mut self, max_errors : usize) -> CsvOptions {
+		self.max_errors = max_errors;
+		self
+	}
+
+	fn target<'s>(&'s self, field : &'s str) -> Option<&'s str> {
+		match self.mapping.iter().rev().find(|m| m.0.eq_ignore_ascii_case(field)) {
+			Some(m) => m.1.as_deref(),
+			None => Some(field)
+		}
+	}
+}
+
+impl Default for CsvOptions {
+	fn default() -> CsvOptions {
+		CsvOptions::new()
+	}
+}
+
+impl CsvReport {
+	///Return the number of rows inserted.
+	pub fn rows(&self) -> u64 {
+		self.rows
+	}
+
+	///Return the rejected records, in the order of the text.
+	pub fn errors(&self) -> &[CsvError] {
+		&self.errors
+	}
+}
+
+impl CsvError {
+	///Return the line of the text where the record starts, the first line is 1.
+	pub fn line(&self) -> u64 {
+		self.line
+	}
+
+	///Return the reason the record was rejected.
+	pub fn message(&self) -> &str {
+		&self.message
+	}
+}
+
+impl fmt::Display for CsvError {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "line {}: {}", self.line, self.message)
+	}
+}
+
+//Write the result of a statement as CSV, the header first if requested. Reals are written in their shortest form that
+//reads back exactly; text and blobs whose text is the one of NULL are quoted. Returns the number of rows written.
+pub fn write_csv(st : &mut Statement, w : &mut dyn Write, options : &CsvOptions) -> Result<u64> {
+	let mut rows = st.query();
+	if options.header {
+		let names : Vec<String> = (0..rows.column_count()).map(|i| rows.column_name(i)).collect();
+		let fields : Vec<String> = names.iter().map(|n| field(n, options, false)).collect();
+		write!(w, "{}\r\n", fields.join(&(options.delimiter as char).to_string()))?;
+	}
+	let mut count = 0;
+	while let Some(row) = rows.next() {
+		let row = row?;
+		let mut line = Vec::new();
+		for i in 0..row.column_count() {
+			if i > 0 { line.push(options.delimiter); }
+			let text = match row.get_value_ref(i) {
+				ValueRef::Null => options.null.clone(),
+				ValueRef::Integer(i) => i.to_string(),
+				ValueRef::Real(f) => format!("{:?}", f),
+				ValueRef::Blob(b) => { let b = options.blobs.encode(b); field(&b, options, b == options.null) },
+				ValueRef::Text(t) => { let t = String::from_utf8_lossy(t); field(&t, options, t == options.null) }
+			};
+			line.extend_from_slice(text.as_bytes());
+		}
+		line.extend_from_slice(b"\r\n");
+		w.write_all(&line)?;
+		count += 1;
+	}
+	w.flush()?;
+	Ok(count)
+}
+
+//Quote a field if it holds the delimiter, a quote or a line break, or if asked to.
+fn field(text : &str, options : &CsvOptions, force : bool) -> String {
+	if force || text.bytes().any(|c| c == options.delimiter || c == b'"' || c == b'\r' || c == b'\n') {
+		format!("\"{}\"", text.replace('"', "\"\""))
+	} else {
+		text.to_string()
+	}
+}
+
+//A field of a record, and whether it was quoted.
+struct Field {
+	text : String,
+	quoted : bool
+}
+
+//The fields of a record, or the reason it is malformed.
+type Record = ::std::result::Result<Vec<Field>, String>;
+
+//Reads the records of a CSV text, keeping track of line numbers.
+struct Records<'r> {
+	input : &'r mut dyn BufRead,
+	delimiter : u8,
+	line : u64
+}
+
+impl<'r> Records<'r> {
+	//Read the next record: the line where it starts and its fields, or the reason it is malformed. Blank lines are
+	//skipped. None at the end of the text.
+	fn next(&mut self) -> Result<Option<(u64, Record)>> {
+		loop {
+			match self.read()? {
+				Some((_, Ok(ref f))) if f.len() == 1 && f[0].text.is_empty() && !f[0].quoted => continue,
+				r => return Ok(r)
+			}
+		}
+	}
+
+	fn read(&mut self) -> Result<Option<(u64, Record)>> {
+		let mut buf = Vec::new();
+		let start = self.line + 1;
+		let (mut fields, mut field, mut quoted, mut in_quotes, mut closed) = (Vec::new(), Vec::new(), false, false, false);
+		let mut error = None;
+		loop {
+			buf.clear();
+			if self.input.read_until(b'\n', &mut buf)? == 0 {
+				if self.line < start { return Ok(None); }
+				if in_quotes { error = Some("unterminated quoted field".to_string()); }
+				break;
+			}
+			if self.line == 0 && buf.starts_with(b"\xEF\xBB\xBF") { buf.drain(..3); }
+			self.line += 1;
+			let mut i = 0;
+			let mut end = false;
+			while i < buf.len() {
+				let c = buf[i];
+				if in_quotes {
+					if c == b'"' {
+						if buf.get(i + 1) == Some(&b'"') { field.push(c); i += 1; } else { in_quotes = false; closed = true; }
+					} else {
+						field.push(c);
+					}
+				} else if c == b'\n' || (c == b'\r' && buf.get(i + 1) == Some(&b'\n')) {
+					end = true;
+					break;
+				} else if c == self.delimiter {
+					fields.push((field, quoted));
+					field = Vec::new();
+					quoted = false;
+					closed = false;
+				} else if closed {
+					if error.is_none() { error = Some("unexpected character after a quoted field".to_string()); }
+				} else if c == b'"' && field.is_empty() && !quoted {
+					in_quotes = true;
+					quoted = true;
+				} else {
+					field.push(c);
+				}
+				i += 1;
+			}
+			if !in_quotes && (end || i == buf.len()) { break; }
+		}
+		if let Some(e) = error { return Ok(Some((start, Err(e)))); }
+		fields.push((field, quoted));
+		let mut record = Vec::with_capacity(fields.len());
+		for (k, (f, quoted)) in fields.into_iter().enumerate() {
+			match String::from_utf8(f) {
+				Ok(text) => record.push(Field { text, quoted }),
+				Err(_) => return Ok(Some((start, Err(format!("field {} is not valid UTF-8", k + 1)))))
+			}
+		}
+		Ok(Some((start, Ok(record))))
+	}
+}
+
+//A column filled by the import, with the type its fields are converted to.
+struct Target {
+	name : String,
+	affinity : Option<ColumnType>
+}
+
+//Import a CSV text into a table. Rows are inserted in transactions of batch_size rows when the connection is in
+//autocommit mode, otherwise in the transaction of the caller.
+pub fn import_csv(con : &Connection, input : &mut dyn BufRead, table : &str, options : &CsvOptions) -> Result<CsvReport> {
+	let desc = match con.introspector()?.table(None, table)? {
+		Some(t) => t,
+		None => return Err(Error::new(ErrorKind::NotFound, format!("unknown table {}", table)))
+	};
+	let insertable = |name : &str| desc.column(name).filter(|c| !c.is_hidden() && c.generated().is_none());
+	let mut records = Records { input, delimiter : options.delimiter, line : 0 };
+	//For each field of a record, the index of its column in targets, None if it is skipped.
+	let mut slots : Vec<Option<usize>> = Vec::new();
+	let mut targets : Vec<Target> = Vec::new();
+	if options.header {
+		let header = match records.next()? {
+			Some((_, Ok(h))) => h,
+			Some((line, Err(e))) => return Err(Error::new(ErrorKind::InvalidData, CsvError { line, message : e }.to_string())),
+			None => return Ok(CsvReport::default())
+		};
+		for f in &header {
+			let column = match options.target(&f.text) {
+				Some(c) => c,
+				None => { slots.push(None); continue; }
+			};
+			let c = match insertable(column) {
+				Some(c) => c,
+				None => return Err(Error::new(ErrorKind::InvalidInput, format!("no column {} to import field {} into table {}", column, f.text, table)))
+			};
+			if targets.iter().any(|t| t.name == c.name()) {
+				return Err(Error::new(ErrorKind::InvalidInput, format!("column {} of table {} is imported twice", c.name(), table)));
+			}
+			slots.push(Some(targets.len()));
+			targets.push(Target { name : c.name().to_string(), affinity : c.decl_type().and_then(affinity) });
+		}
+	} else {
+		for c in desc.columns().iter().filter(|c| insertable(c.name()).is_some()) {
+			slots.push(Some(targets.len()));
+			targets.push(Target { name : c.name().to_string(), affinity : c.decl_type().and_then(affinity) });
+		}
+	}
+	let columns : Vec<String> = targets.iter().map(|t| quote(&t.name)).collect();
+	let params : Vec<String> = (1..=targets.len()).map(|i| format!("?{}", i)).collect();
+	let mut st = con.prepare_statement(&format!("INSERT INTO {}({}) VALUES({})", quote(table), columns.join(","), params.join(",")))?;
+	let own = con.is_autocommit();
+	let mut report = CsvReport::default();
+	let (mut pending, mut open) = (0, false);
+	let r = (|| -> Result<()> {
+		while let Some((line, record)) = records.next()? {
+			if own && !open { con.execute_batch("BEGIN")?; open = true; }
+			match record.and_then(|fields| insert(&mut st, &fields, &slots, &targets, options)) {
+				Ok(()) => {
+					report.rows += 1;
+					pending += 1;
+				},
+				Err(message) => {
+					let e = CsvError { line, message };
+					if report.errors.len() >= options.max_errors { return Err(Error::new(ErrorKind::InvalidData, e.to_string())); }
+					report.errors.push(e);
+				}
+			}
+			if open && pending >= options.batch_size { con.execute_batch("COMMIT")?; open = false; pending = 0; }
+		}
+		if open { con.execute_batch("COMMIT")?; open = false; }
+		Ok(())
+	})();
+	if let Err(e) = r {
+		if open && !con.is_autocommit() { let _ = con.execute_batch("ROLLBACK"); }
+		return Err(e);
+	}
+	Ok(report)
+}
+
+//Convert the fields of a record and insert them. Returns the reason the record is rejected.
+fn insert(st : &mut Statement, fields : &[Field], slots : &[Option<usize>], targets : &[Target], options : &CsvOptions)
+		  -> ::std::result::Result<(), String> {
+	if fields.len() != slots.len() { return Err(format!("expected {} fields, found {}", slots.len(), fields.len())); }
+	for (f, slot) in fields.iter().zip(slots) {
+		let k = match *slot { Some(k) => k, None => continue };
+		let value = convert(f, targets[k].affinity, options).map_err(|e| format!("column {}: {}", targets[k].name, e))?;
+		let index = k as i32 + 1;
+		if let Some(e) = st.set_value(index, &value) { return Err(e.to_string()); }
+	}
+	st.execute_update().map(|_| ()).map_err(|e| e.to_string())
+}
+
+//Convert a field to the value stored in a column of the given affinity.
+fn convert(f : &Field, affinity : Option<ColumnType>, options : &CsvOptions) -> ::std::result::Result<Value, String> {
+	if !f.quoted && f.text == options.null { return Ok(Value::Null); }
+	let text = &f.text;
+	let number = || match text.trim().parse::<i64>() {
+		Ok(i) => Some(Value::Integer(i)),
+		Err(_) => text.trim().parse::<f64>().ok().filter(|r| !r.is_nan()).map(Value::Real)
+	};
+	match affinity {
+		Some(ColumnType::Integer) => match number() {
+			Some(Value::Real(r)) if r.fract() == 0.0 && r.abs() < 9.2e18 => Ok(Value::Integer(r as i64)),
+			Some(Value::Integer(i)) => Ok(Value::Integer(i)),
+			_ => Err(format!("invalid integer '{}'", text))
+		},
+		Some(ColumnType::Real) => match number() {
+			Some(Value::Integer(i)) => Ok(Value::Real(i as f64)),
+			Some(r) => Ok(r),
+			None => Err(format!("invalid real '{}'", text))
+		},
+		Some(ColumnType::Text) => Ok(Value::Text(text.clone())),
+		Some(ColumnType::Blob) => match options.blobs.decode(text) {
+			Some(b) => Ok(Value::Blob(b)),
+			None => Err(format!("invalid {:?} blob '{}'", options.blobs, text))
+		},
+		None => Ok(number().unwrap_or_else(|| Value::Text(text.clone())))
+	}
+}
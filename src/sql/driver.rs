@@ -0,0 +1,193 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind, Result};
+use std::sync::{Arc, OnceLock, RwLock};
+use sql::DbType;
+use sql::sqlite3::Sqlite3Driver;
+use sql::postgres::PgDriver;
+use sql::mysql::MySqlDriver;
+use sql::value::ValueRef;
+use sql::columnar::ColumnType;
+use sql::script::parameters;
+
+///A Driver opens connections to one kind of database.
+///
+///Drivers are registered under a DbType with <i>register_driver</i>; <i>Connection::new</i> looks the driver up by its DbType.
+///A backend is added, from inside or outside this crate, by implementing Driver, RawConnection and RawStatement.
+pub trait Driver : Send + Sync {
+	///Open a new connection. The meaning of <i>target</i> depends on the backend: file name, URL, connection string...
+	fn connect(&self, target : &str) -> Result<Box<dyn RawConnection>>;
+}
+
+///RawConnection is the backend side of a Connection.
+pub trait RawConnection {
+	///Prepare a statement. The statement may borrow the connection.
+	///
+	///Returns an <i>InvalidInput</i> IoError if the SQL text is rejected.
+	fn prepare<'c>(&'c self, sql : &str) -> Result<Box<dyn RawStatement + 'c>>;
+
+	///Return the rowid, or generated key, of the most recent successful INSERT, or 0 if none.
+	fn last_insert_rowid(&self) -> i64 { 0 }
+
+	///Return the number of rows inserted, modified or deleted by the most recently completed statement.
+	fn changes(&self) -> i64;
+
+	///Return the total number of rows inserted, modified or deleted since the connection was opened.
+	fn total_changes(&self) -> i64;
+
+	///Return true if the connection is outside of an explicit transaction.
+	fn is_autocommit(&self) -> bool;
+
+	///Give access to the concrete backend type, for backend specific features.
+	fn as_any(&self) -> &dyn Any;
+}
+
+///RawStatement is the backend side of a Statement.
+///
+///Parameter indexes start at 1 and column indexes start at 0. Values returned by the column_* methods are those of the
+///current row, they are valid until the statement is stepped, reset or rebound.
+pub trait RawStatement {
+	///Reset the statement so that it can be executed again. Bound parameters are kept.
+	fn reset(&mut self) -> Result<()>;
+
+	///Execute the statement or read its next row. Returns true if a row is available, false once the statement is done.
+	fn step(&mut self) -> Result<bool>;
+
+	///Bind an SQL NULL to the parameter with index <i>index</i>.
+	fn bind_null(&mut self, index : i32) -> Result<()>;
+
+	///Bind an i64 to the parameter with index <i>index</i>.
+	fn bind_int64(&mut self, index : i32, value : i64) -> Result<()>;
+
+	///Bind an f64 to the parameter with index <i>index</i>.
+	fn bind_double(&mut self, index : i32, value : f64) -> Result<()>;
+
+	///Bind a copy of a text to the parameter with index <i>index</i>.
+	fn bind_text(&mut self, index : i32, value : &str) -> Result<()>;
+
+	///Bind a copy of a blob to the parameter with index <i>index</i>.
+	fn bind_blob(&mut self, index : i32, value : &[u8]) -> Result<()>;
+
+	///Bind a text to the parameter with index <i>index</i>, without copying it. By default the text is copied.
+	///
+	///# Safety
+	///The caller must keep <i>value</i> alive and unchanged until the parameter is rebound or the statement is dropped.
+	unsafe fn bind_text_in_place(&mut self, index : i32, value : &str) -> Result<()> {
+		self.bind_text(index, value)
+	}
+
+	///Bind a blob to the parameter with index <i>index</i>, without copying it. By default the blob is copied.
+	///
+	///# Safety
+	///The caller must keep <i>value</i> alive and unchanged until the parameter is rebound or the statement is dropped.
+	unsafe fn bind_blob_in_place(&mut self, index : i32, value : &[u8]) -> Result<()> {
+		self.bind_blob(index, value)
+	}
+
+	///Return the number of columns in the result, 0 for a statement that returns no data.
+	fn column_count(&self) -> i32;
+
+	///Return the name of a column of the result, an empty String if the index is out of range.
+	fn column_name(&self, index : i32) -> String;
+
+	///Return the declared type of a column of the result, None if unknown.
+	fn column_decltype(&self, _index : i32) -> Option<String> { None }
+
+	///Return a column value of the current row, tagged with its storage class. Out of range columns are NULL.
+	fn column_value(&self, index : i32) -> ValueRef<'_>;
+
+	///Return a column value of the current row converted to i64.
+	fn column_int64(&self, index : i32) -> i64 {
+		self.column_value(index).as_long()
+	}
+
+	///Return a column value of the current row converted to f64.
+	fn column_double(&self, index : i32) -> f64 {
+		self.column_value(index).as_double()
+	}
+
+	///Return a column value of the current row converted to text, numbers rendered as the database renders them.
+	///NULL is empty.
+	fn column_text(&self, index : i32) -> &[u8];
+
+	///Return a column value of the current row as bytes, numbers rendered as text. NULL is empty.
+	fn column_blob(&self, index : i32) -> &[u8] {
+		match self.column_value(index) {
+			ValueRef::Null => &[],
+			ValueRef::Text(b) | ValueRef::Blob(b) => b,
+			_ => self.column_text(index)
+		}
+	}
+
+	///Return true if a column value of the current row is NULL.
+	fn column_is_null(&self, index : i32) -> bool {
+		self.column_value(index).is_null()
+	}
+
+	///Return the storage class of a column value of the current row, None if it is NULL.
+	fn column_type(&self, index : i32) -> Option<ColumnType> {
+		match self.column_value(index) {
+			ValueRef::Null => None,
+			ValueRef::Integer(_) => Some(ColumnType::Integer),
+			ValueRef::Real(_) => Some(ColumnType::Real),
+			ValueRef::Text(_) => Some(ColumnType::Text),
+			ValueRef::Blob(_) => Some(ColumnType::Blob)
+		}
+	}
+
+	///Return the SQL text used to prepare the statement.
+	fn sql(&self) -> String;
+
+	///Return the index of the parameter named <i>name</i>, prefix included such as ":id", None if there is none.
+	///The default implementation numbers the parameters of the SQL text the way SQLite does.
+	fn parameter_index(&self, name : &str) -> Option<i32> {
+		parameters(&self.sql()).iter().position(|p| !p.is_empty() && p == name).map(|i| i as i32 + 1)
+	}
+
+	///Return the SQL text with the bound parameters expanded, None if not supported.
+	fn expanded_sql(&self) -> Option<String> { None }
+
+	///Return true if the statement makes no direct change to the database.
+	fn is_readonly(&self) -> bool { false }
+
+	///Return true if the statement is an EXPLAIN statement.
+	fn is_explain(&self) -> bool { false }
+
+	///Return true if the statement has been stepped but has neither run to completion nor been reset.
+	fn is_busy(&self) -> bool { false }
+}
+
+type Registry = RwLock<HashMap<DbType, Arc<dyn Driver>>>;
+
+fn registry() -> &'static Registry {
+	static REGISTRY : OnceLock<Registry> = OnceLock::new();
+	REGISTRY.get_or_init(|| {
+		let mut drivers : HashMap<DbType, Arc<dyn Driver>> = HashMap::new();
+		drivers.insert(DbType::SQLite3, Arc::new(Sqlite3Driver));
+		drivers.insert(DbType::PostgreSQL, Arc::new(PgDriver));
+		drivers.insert(DbType::MySQL, Arc::new(MySqlDriver));
+		RwLock::new(drivers)
+	})
+}
+
+///Register a Driver under a DbType, replacing and returning the driver previously registered under it, if any.
+///
+///Built-in backends are registered under their own DbType; backends from other crates use <i>DbType::Custom</i>.
+pub fn register_driver(db_type : DbType, driver : Arc<dyn Driver>) -> Option<Arc<dyn Driver>> {
+	registry().write().unwrap_or_else(|e| e.into_inner()).insert(db_type, driver)
+}
+
+///Remove and return the Driver registered under a DbType.
+pub fn unregister_driver(db_type : DbType) -> Option<Arc<dyn Driver>> {
+	registry().write().unwrap_or_else(|e| e.into_inner()).remove(&db_type)
+}
+
+///Return the Driver registered under a DbType.
+///
+///Returns a <i>NotFound</i> IoError if no driver is registered under it.
+pub fn get_driver(db_type : DbType) -> Result<Arc<dyn Driver>> {
+	match registry().read().unwrap_or_else(|e| e.into_inner()).get(&db_type) {
+		Some(d) => Ok(d.clone()),
+		None => Err(Error::new(ErrorKind::NotFound, format!("no driver registered for {:?}", db_type)))
+	}
+}
@@ -0,0 +1,160 @@
+use std::io::{Error, ErrorKind, Result, Write};
+use std::str::from_utf8;
+use sql::{Connection, DbType, ValueRef};
+use sql::catalog::quote;
+use sql::crypto::to_hex;
+
+///Options of a dump of a database: the tables to dump, and whether to dump their schema, their data or both.
+///
+///By default every table is dumped with its schema and its data. The indexes and triggers of a table are dumped with
+///its schema; a view is dumped when its name is among the tables, or when no table is named.
+#[derive(Clone, Debug)]
+pub struct DumpOptions {
+	tables : Vec<String>,
+	content : DumpContent
+}
+
+///What a dump contains, see <i>DumpOptions::content</i>.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DumpContent {
+	///The CREATE statements and the INSERT statements.
+	All,
+	///Only the CREATE statements of the tables, indexes, triggers and views.
+	Schema,
+	///Only the INSERT statements of the rows, and of the sequences of the AUTOINCREMENT tables.
+	Data
+}
+
+impl DumpOptions {
+	///Create options dumping the schema and the data of every table.
+	pub fn new() -> DumpOptions {
+		DumpOptions { tables : Vec::new(), content : DumpContent::All }
+	}
+
+	///Add a table (or a view) to the tables to dump. Once a table is named, only the named tables are dumped.
+	pub fn table(mut self, name : &str) -> DumpOptions {
+		self.tables.push(name.to_string());
+		self
+	}
+
+	///Set what to dump: the schema and the data, only the schema or only the data.
+	pub fn content(mut self, content : DumpContent) -> DumpOptions {
+		self.content = content;
+		self
+	}
+
+	///Return the names of the tables to dump, empty to dump them all.
+	pub fn tables(&self) -> &[String] {
+		&self.tables
+	}
+
+	///Return true if the CREATE statements are dumped.
+	pub fn is_schema(&self) -> bool {
+		self.content != DumpContent::Data
+	}
+
+	///Return true if the INSERT statements are dumped.
+	pub fn is_data(&self) -> bool {
+		self.content != DumpContent::Schema
+	}
+
+	fn includes(&self, name : &str) -> bool {
+		self.tables.is_empty() || self.tables.iter().any(|t| t == name)
+	}
+}
+
+impl Default for DumpOptions {
+	fn default() -> DumpOptions {
+		DumpOptions::new()
+	}
+}
+
+//Write the main schema of an SQLite database as an SQL script, in the order of creation of its objects: the tables
+//with their rows, the sequences of the AUTOINCREMENT tables, then the indexes, triggers and views.
+pub fn dump(con : &Connection, w : &mut dyn Write, options : &DumpOptions) -> Result<()> {
+	if con.db_type() != DbType::SQLite3 {
+		return Err(Error::new(ErrorKind::Unsupported, format!("no dump for {:?}", con.db_type())));
+	}
+	let objects = con.prepare_statement("SELECT name FROM sqlite_schema WHERE type IN ('table', 'view')")?.fetch_all()?;
+	for t in &options.tables {
+		if !objects.iter().any(|r| r.get_string(0) == *t) {
+			return Err(Error::new(ErrorKind::NotFound, format!("unknown table {}", t)));
+		}
+	}
+	//The shadow tables of virtual tables are filled through their virtual table.
+	let tables = con.prepare_statement("SELECT s.name, s.sql FROM sqlite_schema s, pragma_table_list l \
+										WHERE l.schema = 'main' AND l.name = s.name AND s.type = 'table' AND s.sql NOT NULL \
+										AND s.name NOT LIKE 'sqlite\\_%' ESCAPE '\\' AND l.type <> 'shadow' ORDER BY s.rowid")?.fetch_all()?;
+	writeln!(w, "PRAGMA foreign_keys=OFF;")?;
+	writeln!(w, "BEGIN TRANSACTION;")?;
+	for t in &tables {
+		let name = t.get_string(0);
+		if !options.includes(&name) { continue; }
+		if options.is_schema() { writeln!(w, "{};", t.get_string(1))?; }
+		if options.is_data() { dump_rows(con, w, &name)?; }
+	}
+	if options.is_data() && objects.iter().any(|r| r.get_string(0) == "sqlite_sequence") {
+		let rs = con.prepare_statement("SELECT name, seq FROM sqlite_sequence ORDER BY rowid")?.fetch_all()?;
+		for r in &rs {
+			let name = r.get_value_ref(0);
+			if !options.includes(&name.as_text()) { continue; }
+			writeln!(w, "DELETE FROM sqlite_sequence WHERE name = {};", literal(name))?;
+			writeln!(w, "INSERT INTO sqlite_sequence VALUES({},{});", literal(name), literal(r.get_value_ref(1)))?;
+		}
+	}
+	if options.is_schema() {
+		let rs = con.prepare_statement("SELECT tbl_name, sql FROM sqlite_schema WHERE type IN ('index', 'trigger', 'view') \
+										AND sql NOT NULL ORDER BY rowid")?.fetch_all()?;
+		for r in &rs {
+			if options.includes(&r.get_string(0)) { writeln!(w, "{};", r.get_string(1))?; }
+		}
+	}
+	writeln!(w, "COMMIT;")?;
+	w.flush()
+}
+
+//Write one INSERT statement per row of a table. Generated and hidden columns are left out of the statement, which
+//then names the columns it sets.
+fn dump_rows(con : &Connection, w : &mut dyn Write, table : &str) -> Result<()> {
+	let mut st = con.prepare_statement("SELECT name, hidden FROM pragma_table_xinfo(?1)")?;
+	if let Some(e) = st.set_string(1, table) { return Err(e); }
+	let info = st.fetch_all()?;
+	let columns : Vec<String> = info.iter().filter(|r| r.get_long(1) == 0).map(|r| quote(&r.get_string(0))).collect();
+	if columns.is_empty() { return Ok(()); }
+	let target = if columns.len() == info.row_count() { quote(table) } else { format!("{}({})", quote(table), columns.join(",")) };
+	let mut st = con.prepare_statement(&format!("SELECT {} FROM {}", columns.join(","), quote(table)))?;
+	let mut rows = st.query();
+	while let Some(row) = rows.next() {
+		let row = row?;
+		let values : Vec<String> = (0..row.column_count()).map(|i| literal(row.get_value_ref(i))).collect();
+		writeln!(w, "INSERT INTO {} VALUES({});", target, values.join(","))?;
+	}
+	Ok(())
+}
+
+//Return the SQL literal of a value. Reals keep a decimal point or an exponent so they are read back as reals, text
+//that is not valid UTF-8 or holds a NUL character is written as a cast blob.
+fn literal(v : ValueRef) -> String {
+	match v {
+		ValueRef::Null => "NULL".to_string(),
+		ValueRef::Integer(i) => i.to_string(),
+		ValueRef::Real(f) if f.is_infinite() => if f > 0.0 { "1e999".to_string() } else { "-1e999".to_string() },
+		ValueRef::Real(f) => format!("{:?}", f),
+		ValueRef::Text(t) => match from_utf8(t) {
+			Ok(s) if !s.contains('\0') => format!("'{}'", s.replace('\'', "''")),
+			_ => format!("CAST(X'{}' AS TEXT)", to_hex(t))
+		},
+		ValueRef::Blob(b) => format!("X'{}'", to_hex(b))
+	}
+}
+
+//Run a script written by dump. The foreign key enforcement the script turns off is restored afterwards, and the
+//transaction is rolled back if a statement fails.
+pub fn restore(con : &Connection, sql : &str) -> Result<()> {
+	let foreign_keys = con.db_type() == DbType::SQLite3
+					   && con.prepare_statement("PRAGMA foreign_keys")?.fetch_all()?.row(0).is_some_and(|r| r.get_long(0) != 0);
+	let r = con.execute_batch(sql);
+	if r.is_err() && !con.is_autocommit() { let _ = con.execute_batch("ROLLBACK"); }
+	if foreign_keys { con.execute_batch("PRAGMA foreign_keys = ON")?; }
+	r
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use sql::DbType;
+use sql::driver::RawStatement;
+use sql::script::placeholders;
+use sql::value::Value;
+
+//Number of statements kept for the list lengths of one SQL text, beyond which the cache is emptied.
+const CACHE_SIZE : usize = 16;
+
+//The value bound to a parameter of a Statement, kept to bind it again when the statement is prepared for other list
+//lengths. Text and blobs bound in place are kept as references.
+#[derive(Clone, Debug)]
+pub enum Param<'a> {
+	Unset,
+	Value(Value),
+	Text(&'a str),
+	Blob(&'a [u8]),
+	List(Vec<Value>)
+}
+
+impl<'a> Param<'a> {
+	//Return the number of placeholders the parameter takes once expanded.
+	pub fn len(&self) -> usize {
+		match *self {
+			Param::List(ref v) => v.len(),
+			_ => 1
+		}
+	}
+}
+
+//The SQL text of a statement with lists bound to some of its parameters, and the statements prepared for the lengths
+//of the lists, keyed by the number of placeholders of each parameter.
+//
+//A parameter bound to a list of n values is rewritten as n placeholders, separated by commas; the parameters after it
+//are numbered accordingly. An empty list leaves the parentheses of IN (?) empty, which SQLite accepts; it is rewritten
+//as an empty subquery for MySQL. PostgreSQL would need the type of the subquery, so it takes no empty list.
+pub struct Expansion<'a> {
+	sql : String,
+	marks : Vec<(usize, usize, usize)>,
+	lengths : Vec<usize>,
+	cache : HashMap<Vec<usize>, Box<dyn RawStatement + 'a>>
+}
+
+impl<'a> Expansion<'a> {
+	pub fn new(sql : &str) -> Expansion<'a> {
+		let marks = placeholders(sql);
+		let count = marks.iter().map(|m| m.2).max().unwrap_or(0);
+		Expansion { sql : sql.to_string(), marks, lengths : vec![1; count], cache : HashMap::new() }
+	}
+
+	//Return the SQL text, as written by the caller.
+	pub fn sql(&self) -> &str {
+		&self.sql
+	}
+
+	//Return the number of parameters of the SQL text.
+	pub fn count(&self) -> usize {
+		self.lengths.len()
+	}
+
+	//Return the placeholder numbers of each parameter.
+	pub fn lengths(&self) -> &[usize] {
+		&self.lengths
+	}
+
+	//Return the index of the first placeholder of the parameter <i>index</i> in the rewritten SQL text.
+	pub fn position(&self, index : i32) -> i32 {
+		let before = (index.max(1) as usize - 1).min(self.lengths.len());
+		index - before as i32 + self.lengths[..before].iter().sum::<usize>() as i32
+	}
+
+	//Take the statement prepared for the lengths, if kept.
+	pub fn take(&mut self, lengths : &[usize]) -> Option<Box<dyn RawStatement + 'a>> {
+		self.cache.remove(lengths)
+	}
+
+	//Keep the statement prepared for the current lengths and switch to new ones.
+	pub fn switch(&mut self, raw : Box<dyn RawStatement + 'a>, lengths : Vec<usize>) {
+		if self.cache.len() >= CACHE_SIZE { self.cache.clear(); }
+		let old = ::std::mem::replace(&mut self.lengths, lengths);
+		self.cache.insert(old, raw);
+	}
+
+	//Rewrite the SQL text for the lengths, with the placeholders of the database.
+	pub fn rewrite(&self, db_type : DbType, lengths : &[usize]) -> String {
+		let mut sql = String::with_capacity(self.sql.len());
+		let mut last = 0;
+		for &(start, end, n) in &self.marks {
+			sql.push_str(&self.sql[last..start]);
+			last = end;
+			let first : usize = lengths[..n - 1].iter().sum();
+			if lengths[n - 1] == 0 && db_type == DbType::MySQL { sql.push_str("SELECT NULL FROM DUAL WHERE FALSE"); }
+			for k in 0..lengths[n - 1] {
+				if k > 0 { sql.push_str(", "); }
+				match db_type {
+					DbType::PostgreSQL => sql.push_str(&format!("${}", first + k + 1)),
+					//MySQL placeholders are numbered by position, each ? being its own parameter.
+					DbType::MySQL => sql.push('?'),
+					_ => sql.push_str(&format!("?{}", first + k + 1))
+				}
+			}
+		}
+		sql.push_str(&self.sql[last..]);
+		sql
+	}
+}
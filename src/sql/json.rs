@@ -0,0 +1,109 @@
+use std::fmt::Write as FmtWrite;
+use std::io::{Result, Write};
+use sql::Statement;
+use sql::value::{BlobEncoding, ValueRef};
+
+///Layout of the rows written by Statement::write_json.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JsonFormat {
+	///A JSON array holding one object per row.
+	Array,
+	///JSON Lines: one object per line, without enclosing array.
+	Lines
+}
+
+///Options of the JSON export of a query result.
+///
+///Each row is written as an object whose keys are the column names. NULL is written as null, integers and reals as
+///numbers (reals that are not finite as null), text as strings, and blobs as strings in the chosen encoding, base64
+///by default.
+#[derive(Clone, Debug)]
+pub struct JsonOptions {
+	format : JsonFormat,
+	blobs : BlobEncoding
+}
+
+impl JsonOptions {
+	///Create options writing a JSON array, with blobs in base64.
+	pub fn new() -> JsonOptions {
+		JsonOptions { format : JsonFormat::Array, blobs : BlobEncoding::Base64 }
+	}
+
+	///Set the layout of the rows.
+	pub fn format(mut self, format : JsonFormat) -> JsonOptions {
+		self.format = format;
+		self
+	}
+
+	///Set the encoding of blobs.
+	pub fn blobs(mut self, blobs : BlobEncoding) -> JsonOptions {
+		self.blobs = blobs;
+		self
+	}
+}
+
+impl Default for JsonOptions {
+	fn default() -> JsonOptions {
+		JsonOptions::new()
+	}
+}
+
+//Write the result of a statement as JSON, row by row as they are read. Returns the number of rows written.
+pub fn write_json(st : &mut Statement, w : &mut dyn Write, options : &JsonOptions) -> Result<u64> {
+	let mut rows = st.query();
+	let keys : Vec<String> = (0..rows.column_count()).map(|i| { let mut k = String::new(); string(&mut k, &rows.column_name(i)); k }).collect();
+	let (open, separator, close) = match options.format {
+		JsonFormat::Array => ("[\n", ",\n", "\n]\n"),
+		JsonFormat::Lines => ("", "\n", "\n")
+	};
+	let mut line = String::new();
+	let mut count = 0;
+	while let Some(row) = rows.next() {
+		let row = row?;
+		line.clear();
+		line.push_str(if count == 0 { open } else { separator });
+		line.push('{');
+		for (i, k) in keys.iter().enumerate() {
+			if i > 0 { line.push(','); }
+			line.push_str(k);
+			line.push(':');
+			match row.get_value_ref(i as i32) {
+				ValueRef::Null => line.push_str("null"),
+				ValueRef::Integer(n) => { let _ = write!(line, "{}", n); },
+				ValueRef::Real(f) if f.is_finite() => { let _ = write!(line, "{:?}", f); },
+				ValueRef::Real(_) => line.push_str("null"),
+				ValueRef::Text(t) => string(&mut line, &String::from_utf8_lossy(t)),
+				ValueRef::Blob(b) => string(&mut line, &options.blobs.encode(b))
+			}
+		}
+		line.push('}');
+		w.write_all(line.as_bytes())?;
+		count += 1;
+	}
+	match (count, options.format) {
+		(0, JsonFormat::Array) => w.write_all(b"[]\n")?,
+		(0, JsonFormat::Lines) => (),
+		_ => w.write_all(close.as_bytes())?
+	}
+	w.flush()?;
+	Ok(count)
+}
+
+//Append a JSON string literal, escaping quotes, backslashes and control characters.
+fn string(out : &mut String, s : &str) {
+	out.push('"');
+	for c in s.chars() {
+		match c {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'\r' => out.push_str("\\r"),
+			'\t' => out.push_str("\\t"),
+			'\u{8}' => out.push_str("\\b"),
+			'\u{c}' => out.push_str("\\f"),
+			c if (c as u32) < 0x20 => { let _ = write!(out, "\\u{:04x}", c as u32); },
+			c => out.push(c)
+		}
+	}
+	out.push('"');
+}
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind, Result};
+use sql::{Connection, DbType};
+use sql::catalog::quote;
+use sql::resultset::ResultSet;
+
+///A row that breaks a foreign key constraint, found by <i>Connection::foreign_key_check</i>.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignKeyViolation {
+	table : String,
+	rowid : Option<i64>,
+	parent : String,
+	columns : Vec<String>
+}
+
+///Space used by a database, from <i>Connection::page_stats</i>.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageStats {
+	page_size : u64,
+	page_count : u64,
+	freelist_count : u64,
+	objects : Option<Vec<ObjectStats>>
+}
+
+///Space used by a table or an index, read from the dbstat virtual table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectStats {
+	name : String,
+	pages : u64,
+	bytes : u64,
+	payload : u64,
+	unused : u64
+}
+
+impl ForeignKeyViolation {
+	///Return the name of the table of the row.
+	pub fn table(&self) -> &str {
+		&self.table
+	}
+
+	///Return the rowid of the row, None for a WITHOUT ROWID table.
+	pub fn rowid(&self) -> Option<i64> {
+		self.rowid
+	}
+
+	///Return the name of the table the foreign key refers to.
+	pub fn parent(&self) -> &str {
+		&self.parent
+	}
+
+	///Return the columns of the foreign key, in the table of the row.
+	pub fn columns(&self) -> &[String] {
+		&self.columns
+	}
+}
+
+impl PageStats {
+	///Return the size of a page, in bytes.
+	pub fn page_size(&self) -> u64 {
+		self.page_size
+	}
+
+	///Return the number of pages of the database file.
+	pub fn page_count(&self) -> u64 {
+		self.page_count
+	}
+
+	///Return the number of unused pages, which VACUUM or an incremental vacuum gives back.
+	pub fn freelist_count(&self) -> u64 {
+		self.freelist_count
+	}
+
+	///Return the size of the database file, in bytes.
+	pub fn size(&self) -> u64 {
+		self.page_size * self.page_count
+	}
+
+	///Return the space used by each table and index, by name, None if SQLite was compiled without the dbstat virtual
+	///table.
+	pub fn objects(&self) -> Option<&[ObjectStats]> {
+		self.objects.as_deref()
+	}
+}
+
+impl ObjectStats {
+	///Return the name of the table or index, sqlite_schema for the schema itself.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	///Return the number of pages of the object, overflow pages included.
+	pub fn pages(&self) -> u64 {
+		self.pages
+	}
+
+	///Return the size of the pages of the object, in bytes.
+	pub fn bytes(&self) -> u64 {
+		self.bytes
+	}
+
+	///Return the number of bytes of data stored in the pages.
+	pub fn payload(&self) -> u64 {
+		self.payload
+	}
+
+	///Return the number of unused bytes in the pages.
+	pub fn unused(&self) -> u64 {
+		self.unused
+	}
+}
+
+//Run a PRAGMA of a schema and return the first column of each row as text.
+fn messages(con : &Connection, schema : Option<&str>, name : &str) -> Result<Vec<String>> {
+	let rs = query(con, &format!("PRAGMA {}.{}", quote(schema.unwrap_or("main")), name), &[])?;
+	let messages : Vec<String> = rs.iter().map(|r| r.get_string(0)).collect();
+	if messages.len() == 1 && messages[0] == "ok" { Ok(Vec::new()) } else { Ok(messages) }
+}
+
+//Run a query of an SQLite database with text parameters and return its whole result.
+fn query(con : &Connection, sql : &str, params : &[&str]) -> Result<ResultSet> {
+	sqlite_only(con)?;
+	let mut st = con.prepare_statement(sql)?;
+	for (i, p) in params.iter().enumerate() {
+		if let Some(e) = st.set_string(i as i32 + 1, p) { return Err(e); }
+	}
+	st.fetch_all()
+}
+
+fn sqlite_only(con : &Connection) -> Result<()> {
+	match con.db_type() {
+		DbType::SQLite3 => Ok(()),
+		t => Err(Error::new(ErrorKind::Unsupported, format!("no maintenance commands for {:?}", t)))
+	}
+}
+
+fn count(con : &Connection, schema : &str, name : &str) -> Result<u64> {
+	let rs = query(con, &format!("PRAGMA {}.{}", quote(schema), name), &[])?;
+	match rs.row(0) {
+		Some(r) => Ok(r.get_long(0).max(0) as u64),
+		None => Err(Error::new(ErrorKind::InvalidData, format!("no value for {}", name)))
+	}
+}
+
+//Check the whole structure of the database, returning the problems found, none if it is sound.
+pub fn integrity_check(con : &Connection, schema : Option<&str>) -> Result<Vec<String>> {
+	messages(con, schema, "integrity_check")
+}
+
+//Check the structure of the database as integrity_check, without verifying that indexes match their tables.
+pub fn quick_check(con : &Connection, schema : Option<&str>) -> Result<Vec<String>> {
+	messages(con, schema, "quick_check")
+}
+
+//Find the rows breaking a foreign key, of a table or of every table. The columns of each foreign key are read from
+//foreign_key_list, by the id foreign_key_check gives.
+pub fn foreign_key_check(con : &Connection, schema : Option<&str>, table : Option<&str>) -> Result<Vec<ForeignKeyViolation>> {
+	let schema = schema.unwrap_or("main");
+	let sql = match table {
+		Some(t) => format!("PRAGMA {}.foreign_key_check({})", quote(schema), quote(t)),
+		None => format!("PRAGMA {}.foreign_key_check", quote(schema))
+	};
+	let mut keys : HashMap<(String, i64), Vec<String>> = HashMap::new();
+	let mut violations = Vec::new();
+	for r in &query(con, &sql, &[])? {
+		let table = r.get_string(0);
+		let key = (table.clone(), r.get_long(3));
+		if !keys.contains_key(&key) {
+			let columns = query(con, &format!("SELECT \"from\" FROM pragma_foreign_key_list(?1, ?2) WHERE id = {} ORDER BY seq", key.1),
+								&[&table, schema])?;
+			keys.insert(key.clone(), columns.iter().map(|c| c.get_string(0)).collect());
+		}
+		violations.push(ForeignKeyViolation {
+			rowid : if r.is_null(1) { None } else { Some(r.get_long(1)) },
+			parent : r.get_string(2),
+			columns : keys[&key].clone(),
+			table
+		});
+	}
+	Ok(violations)
+}
+
+//Write a compacted copy of the database to a new file.
+pub fn vacuum_into(con : &Connection, schema : Option<&str>, path : &str) -> Result<()> {
+	query(con, &format!("VACUUM {} INTO ?1", quote(schema.unwrap_or("main"))), &[path]).map(|_| ())
+}
+
+//Give back up to <i>pages</i> free pages to the file system, all of them if None, and return the number of pages given
+//back. Nothing is done unless auto_vacuum is INCREMENTAL.
+pub fn incremental_vacuum(con : &Connection, schema : Option<&str>, pages : Option<u32>) -> Result<u64> {
+	let schema = schema.unwrap_or("main");
+	//PRAGMA incremental_vacuum(0) frees every page.
+	if pages == Some(0) { return Ok(0); }
+	let before = count(con, schema, "freelist_count")?;
+	query(con, &format!("PRAGMA {}.incremental_vacuum({})", quote(schema), pages.unwrap_or(0)), &[])?;
+	Ok(before.saturating_sub(count(con, schema, "freelist_count")?))
+}
+
+//Gather statistics for the query planner, of a table, a schema or of every schema.
+pub fn analyze(con : &Connection, schema : Option<&str>, table : Option<&str>) -> Result<()> {
+	let sql = match (schema, table) {
+		(Some(s), Some(t)) => format!("ANALYZE {}.{}", quote(s), quote(t)),
+		(None, Some(t)) => format!("ANALYZE {}", quote(t)),
+		(Some(s), None) => format!("ANALYZE {}", quote(s)),
+		(None, None) => "ANALYZE".to_string()
+	};
+	query(con, &sql, &[]).map(|_| ())
+}
+
+//Let SQLite gather the statistics it deems useful, as recommended before closing long-lived connections.
+pub fn optimize(con : &Connection) -> Result<()> {
+	query(con, "PRAGMA optimize", &[]).map(|_| ())
+}
+
+//Read the page counts of a schema, and the space used by each object from dbstat, which may not be compiled in.
+pub fn page_stats(con : &Connection, schema : Option<&str>) -> Result<PageStats> {
+	let schema = schema.unwrap_or("main");
+	let objects = match query(con, "SELECT name, count(*), sum(pgsize), sum(payload), sum(unused) FROM dbstat(?1) GROUP BY name ORDER BY name",
+							  &[schema]) {
+		Ok(rs) => Some(rs.iter().map(|r| ObjectStats {
+			name : r.get_string(0),
+			pages : r.get_long(1) as u64,
+			bytes : r.get_long(2) as u64,
+			payload : r.get_long(3) as u64,
+			unused : r.get_long(4) as u64
+		}).collect()),
+		Err(ref e) if e.to_string().contains("no such table") => None,
+		Err(e) => return Err(e)
+	};
+	Ok(PageStats {
+		page_size : count(con, schema, "page_size")?,
+		page_count : count(con, schema, "page_count")?,
+		freelist_count : count(con, schema, "freelist_count")?,
+		objects
+	})
+}
@@ -0,0 +1,455 @@
+use std::fmt;
+use std::io::{Error, ErrorKind, Result};
+use sql::{Connection, DbType};
+use sql::catalog::{Column, Generated, Index, IndexOrigin, Introspector, SqliteIntrospector, Table, Trigger, View, quote};
+use sql::script::{first_word, normalize, split_statements, table_definitions, tokens, unquote};
+
+///Brings the schema of an SQLite database to a desired schema.
+///
+///The desired schema is a script of CREATE TABLE, CREATE INDEX, CREATE VIEW and CREATE TRIGGER statements. It is
+///created in a scratch in-memory database and compared, through introspection, with the main schema of the database
+///to migrate. The comparison produces a Plan, an ordered list of steps: views, triggers and indexes that changed are
+///dropped, new tables are created, columns are added with ALTER TABLE when SQLite allows it, other changed tables are
+///rebuilt, removed tables are dropped, then indexes, views and triggers are created.
+///
+///A table is rebuilt with the procedure of the SQLite documentation: the new table is created under a temporary
+///name, the columns it has in common with the old one are copied, the old table is dropped and the new one renamed.
+///The plan runs in one transaction. When it rebuilds tables the foreign key constraints are checked before commit, and
+///disabled while it runs if they are enabled.
+///
+///Dropping a table and rebuilding a table without some of its columns lose data: these steps are destructive and are
+///refused unless <i>allow_destructive</i> is set. In a dry run the plan is computed and returned but not applied; print it
+///to see its SQL.
+pub struct Migration {
+	ddl : String,
+	allow_destructive : bool,
+	dry_run : bool
+}
+
+///Ordered steps bringing a database to a desired schema, computed by a Migration.
+///
+///Printing a plan shows its SQL script, each step preceded by a comment.
+#[derive(Clone, Debug)]
+pub struct Plan {
+	steps : Vec<Step>,
+	allow_destructive : bool,
+	foreign_keys : bool
+}
+
+///A step of a Plan: the statements changing one object of the schema.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+	kind : StepKind,
+	object : String,
+	statements : Vec<String>,
+	destructive : bool
+}
+
+///What a Step does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepKind {
+	CreateTable,
+	AddColumn,
+	RebuildTable,
+	DropTable,
+	CreateIndex,
+	DropIndex,
+	CreateView,
+	DropView,
+	CreateTrigger,
+	DropTrigger
+}
+
+//The parts of a CREATE TABLE statement, normalized for comparison.
+#[derive(PartialEq)]
+struct Shape {
+	columns : Vec<String>,
+	constraints : Vec<String>,
+	options : String
+}
+
+//Both schemas, read once.
+struct Schemas {
+	tables : Vec<Table>,
+	views : Vec<View>,
+	triggers : Vec<Trigger>
+}
+
+impl Migration {
+	///Create a migration to the schema created by the script <i>ddl</i>. Destructive steps are refused and the plan is
+	///applied by default.
+	pub fn new(ddl : &str) -> Migration {
+		Migration { ddl : ddl.to_string(), allow_destructive : false, dry_run : false }
+	}
+
+	///Allow, or refuse, the steps that lose data.
+	pub fn allow_destructive(mut self, allow : bool) -> Migration {
+		self.allow_destructive = allow;
+		self
+	}
+
+	///Only compute the plan, without applying it.
+	pub fn dry_run(mut self, dry_run : bool) -> Migration {
+		self.dry_run = dry_run;
+		self
+	}
+
+	///Compare the main schema of <i>con</i> with the desired schema and return the plan that migrates it.
+	///
+	///Returns an <i>Unsupported</i> IoError if <i>con</i> is not an SQLite database, an <i>InvalidInput</i> IoError if the
+	///desired schema cannot be created.
+	pub fn plan(&self, con : &Connection) -> Result<Plan> {
+		if con.db_type() != DbType::SQLite3 {
+			return Err(Error::new(ErrorKind::Unsupported, format!("no migration for {:?}", con.db_type())));
+		}
+		let scratch = Connection::new(DbType::SQLite3, ":memory:")?;
+		scratch.execute_batch(&self.ddl).map_err(|e| Error::new(ErrorKind::InvalidInput, format!("invalid desired schema: {}", e)))?;
+		let live = Schemas::read(&SqliteIntrospector::new(con))?;
+		let desired = Schemas::read(&SqliteIntrospector::new(&scratch))?;
+		let order : Vec<String> = split_statements(&self.ddl).iter().map(|s| normalize(s)).collect();
+		Ok(Plan { steps : steps(&live, &desired, &order), allow_destructive : self.allow_destructive, foreign_keys : foreign_keys(con)? })
+	}
+
+	///Compute the plan migrating the main schema of <i>con</i> and apply it, unless this is a dry run. Returns the plan.
+	///
+	///Returns a <i>PermissionDenied</i> IoError if the plan has destructive steps and they are not allowed, or the
+	///IoError of the statement that failed, in which case the database is left unchanged.
+	pub fn run(&self, con : &Connection) -> Result<Plan> {
+		let plan = self.plan(con)?;
+		if !self.dry_run { plan.apply(con)?; }
+		Ok(plan)
+	}
+}
+
+impl Schemas {
+	fn read(i : &dyn Introspector) -> Result<Schemas> {
+		Ok(Schemas { tables : i.tables(None)?, views : i.views(None)?, triggers : i.triggers(None)? })
+	}
+
+	fn table(&self, name : &str) -> Option<&Table> {
+		self.tables.iter().find(|t| t.name().eq_ignore_ascii_case(name))
+	}
+}
+
+//Compute the steps migrating <i>live</i> to <i>desired</i>. <i>order</i> holds the normalized statements of the
+//desired script, views and triggers are created in that order since they may depend on each other.
+fn steps(live : &Schemas, desired : &Schemas, order : &[String]) -> Vec<Step> {
+	let mut creates = Vec::new();
+	let mut changes = Vec::new();
+	let mut drops = Vec::new();
+	let mut rebuilt : Vec<&str> = Vec::new();
+	for d in &desired.tables {
+		match live.table(d.name()) {
+			None => {
+				creates.push(step(StepKind::CreateTable, d.name(), vec![sql_of(d.sql())], false));
+				rebuilt.push(d.name());
+			},
+			Some(l) => match compare(l, d) {
+				Change::None => (),
+				Change::AddColumns(defs) => {
+					for (name, def) in defs {
+						changes.push(step(StepKind::AddColumn, &format!("{}.{}", d.name(), name),
+										  vec![format!("ALTER TABLE {} ADD COLUMN {}", quote(d.name()), def)], false));
+					}
+				},
+				Change::Rebuild => {
+					changes.push(rebuild(l, d, &[live, desired]));
+					rebuilt.push(d.name());
+				}
+			}
+		}
+	}
+	for l in &live.tables {
+		if desired.table(l.name()).is_none() {
+			drops.push(step(StepKind::DropTable, l.name(), vec![format!("DROP TABLE {}", quote(l.name()))], true));
+		}
+	}
+	//Rebuilding or dropping tables breaks the views and triggers that refer to them: recreate them all.
+	let recreate_all = !drops.is_empty() || changes.iter().any(|s| s.kind == StepKind::RebuildTable);
+	let mut plan = Vec::new();
+	let mut later = Vec::new();
+	for l in &live.views {
+		let same = desired.views.iter().find(|d| d.name().eq_ignore_ascii_case(l.name())).is_some_and(|d| same_sql(l.sql(), d.sql()));
+		if recreate_all || !same { plan.push(step(StepKind::DropView, l.name(), vec![format!("DROP VIEW {}", quote(l.name()))], false)); }
+	}
+	for l in &live.triggers {
+		let same = desired.triggers.iter().find(|d| d.name().eq_ignore_ascii_case(l.name())).is_some_and(|d| same_sql(l.sql(), d.sql()));
+		if recreate_all || !same { plan.push(step(StepKind::DropTrigger, l.name(), vec![format!("DROP TRIGGER {}", quote(l.name()))], false)); }
+	}
+	for d in &desired.tables {
+		let indexes = explicit(d.indexes());
+		let fresh = rebuilt.iter().any(|r| r.eq_ignore_ascii_case(d.name()));
+		let old = if fresh { Vec::new() } else { live.table(d.name()).map(|l| explicit(l.indexes())).unwrap_or_default() };
+		for o in &old {
+			if !indexes.iter().any(|i| i.name().eq_ignore_ascii_case(o.name()) && same_sql(i.sql(), o.sql())) {
+				plan.push(step(StepKind::DropIndex, o.name(), vec![format!("DROP INDEX {}", quote(o.name()))], false));
+			}
+		}
+		for i in &indexes {
+			if !old.iter().any(|o| o.name().eq_ignore_ascii_case(i.name()) && same_sql(i.sql(), o.sql())) {
+				later.push(step(StepKind::CreateIndex, i.name(), vec![sql_of(i.sql())], false));
+			}
+		}
+	}
+	let mut objects : Vec<(usize, Step)> = Vec::new();
+	for d in &desired.views {
+		let same = live.views.iter().find(|l| l.name().eq_ignore_ascii_case(d.name())).is_some_and(|l| same_sql(l.sql(), d.sql()));
+		if recreate_all || !same { objects.push((position(order, d.sql()), step(StepKind::CreateView, d.name(), vec![sql_of(d.sql())], false))); }
+	}
+	for d in &desired.triggers {
+		let same = live.triggers.iter().find(|l| l.name().eq_ignore_ascii_case(d.name())).is_some_and(|l| same_sql(l.sql(), d.sql()));
+		if recreate_all || !same { objects.push((position(order, d.sql()), step(StepKind::CreateTrigger, d.name(), vec![sql_of(d.sql())], false))); }
+	}
+	objects.sort_by_key(|o| o.0);
+	plan.extend(creates);
+	plan.extend(changes);
+	plan.extend(drops);
+	plan.extend(later);
+	plan.extend(objects.into_iter().map(|o| o.1));
+	plan
+}
+
+enum Change<'d> {
+	None,
+	AddColumns(Vec<(&'d str, &'d str)>),
+	Rebuild
+}
+
+//Compare a live table with its desired definition.
+fn compare<'d>(live : &Table, desired : &'d Table) -> Change<'d> {
+	let (l, (d, defs)) = match (live.sql().and_then(shape), desired.sql().and_then(shape)) {
+		(Some(l), Some(d)) => (l.0, d),
+		_ => return Change::Rebuild
+	};
+	if l == d { return Change::None; }
+	let n = l.columns.len();
+	if l.constraints != d.constraints || l.options != d.options || n >= d.columns.len() || l.columns[..] != d.columns[..n] {
+		return Change::Rebuild;
+	}
+	let mut added = Vec::new();
+	for &(ref name, def) in &defs[n..] {
+		match desired.column(name) {
+			Some(c) if can_add(c, def) => added.push((c.name(), def)),
+			_ => return Change::Rebuild
+		}
+	}
+	Change::AddColumns(added)
+}
+
+//The restrictions of ALTER TABLE ADD COLUMN.
+fn can_add(c : &Column, def : &str) -> bool {
+	let words : Vec<String> = tokens(def).iter().map(|&(s, e)| def[s..e].to_ascii_uppercase()).collect();
+	let default = c.default().map(|d| d.to_ascii_uppercase());
+	let null_default = default.as_deref().is_none_or(|d| d == "NULL");
+	c.primary_key() == 0 && c.generated() != Some(Generated::Stored)
+		&& !words.iter().any(|w| w == "UNIQUE")
+		&& !(c.is_not_null() && null_default && c.generated().is_none())
+		&& (null_default || !words.iter().any(|w| w == "REFERENCES"))
+		&& !default.as_deref().is_some_and(|d| d.starts_with('(') || d.starts_with("CURRENT_"))
+}
+
+//Split a CREATE TABLE statement into its normalized shape and the original texts of its column definitions, which
+//ALTER TABLE ADD COLUMN reuses.
+fn shape(sql : &str) -> Option<(Shape, Vec<(String, &str)>)> {
+	let (defs, _, close) = table_definitions(sql)?;
+	let mut shape = Shape { columns : Vec::new(), constraints : Vec::new(), options : normalize(&sql[close + 1..]) };
+	let mut columns = Vec::new();
+	for def in defs {
+		match &*first_word(def) {
+			"CONSTRAINT" | "PRIMARY" | "UNIQUE" | "CHECK" | "FOREIGN" => shape.constraints.push(normalize(def)),
+			_ => {
+				shape.columns.push(normalize(def));
+				columns.push((tokens(def).first().map(|&(s, e)| unquote(&def[s..e])).unwrap_or_default(), def));
+			}
+		}
+	}
+	Some((shape, columns))
+}
+
+//The statements rebuilding a table with its desired definition, <i>schemas</i> are searched for a free temporary name.
+fn rebuild(live : &Table, desired : &Table, schemas : &[&Schemas]) -> Step {
+	let temp = temporary_name(desired.name(), schemas);
+	let sql = sql_of(desired.sql());
+	let open = table_definitions(&sql).map(|d| d.1).unwrap_or(0);
+	let kept : Vec<String> = desired.columns().iter()
+		.filter(|c| c.generated().is_none() && live.column(c.name()).is_some_and(|l| l.generated().is_none()))
+		.map(|c| quote(c.name())).collect();
+	let lost = live.columns().iter().any(|l| l.generated().is_none() && desired.column(l.name()).is_none_or(|c| c.generated().is_some()));
+	let mut statements = vec![format!("CREATE TABLE {}{}", quote(&temp), &sql[open..])];
+	if !kept.is_empty() {
+		statements.push(format!("INSERT INTO {} ({}) SELECT {} FROM {}", quote(&temp), kept.join(", "), kept.join(", "), quote(live.name())));
+	}
+	statements.push(format!("DROP TABLE {}", quote(live.name())));
+	statements.push(format!("ALTER TABLE {} RENAME TO {}", quote(&temp), quote(desired.name())));
+	step(StepKind::RebuildTable, desired.name(), statements, lost)
+}
+
+//Return new_<i>table</i>, followed by _2, _3 ... if a table, view or index of one of the schemas has that name.
+fn temporary_name(table : &str, schemas : &[&Schemas]) -> String {
+	let taken = |name : &str| schemas.iter().any(|s| s.table(name).is_some()
+		|| s.views.iter().any(|v| v.name().eq_ignore_ascii_case(name))
+		|| s.tables.iter().any(|t| t.indexes().iter().any(|i| i.name().eq_ignore_ascii_case(name))));
+	let mut name = format!("new_{}", table);
+	let mut n = 1;
+	while taken(&name) {
+		n += 1;
+		name = format!("new_{}_{}", table, n);
+	}
+	name
+}
+
+fn step(kind : StepKind, object : &str, statements : Vec<String>, destructive : bool) -> Step {
+	Step { kind, object : object.to_string(), statements, destructive }
+}
+
+fn explicit(indexes : &[Index]) -> Vec<&Index> {
+	indexes.iter().filter(|i| i.origin() == IndexOrigin::CreateIndex).collect()
+}
+
+fn same_sql(a : Option<&str>, b : Option<&str>) -> bool {
+	a.map(normalize) == b.map(normalize)
+}
+
+fn sql_of(sql : Option<&str>) -> String {
+	sql.unwrap_or("").to_string()
+}
+
+//Precedes the foreign key check in the script, which only lists the broken constraints.
+const CHECK_COMMENT : &str = "-- The foreign key check must return no rows: if it does, ROLLBACK instead of COMMIT.";
+
+//Return true if the foreign key constraints are enforced on the connection.
+fn foreign_keys(con : &Connection) -> Result<bool> {
+	Ok(con.prepare_statement("PRAGMA foreign_keys")?.fetch_all()?.row(0).is_some_and(|r| r.get_long(0) != 0))
+}
+
+fn position(order : &[String], sql : Option<&str>) -> usize {
+	let n = normalize(sql.unwrap_or(""));
+	order.iter().position(|s| *s == n).unwrap_or(order.len())
+}
+
+impl Plan {
+	///Return the steps of the plan, in execution order.
+	pub fn steps(&self) -> &[Step] {
+		&self.steps
+	}
+
+	///Return true if the database already has the desired schema.
+	pub fn is_empty(&self) -> bool {
+		self.steps.is_empty()
+	}
+
+	///Return true if the plan has steps that lose data.
+	pub fn is_destructive(&self) -> bool {
+		self.steps.iter().any(|s| s.destructive)
+	}
+
+	///Return the SQL script of the plan: the statements of its steps inside a transaction. When tables are rebuilt the
+	///foreign key constraints are checked before commit, and disabled during the script if they were enabled when the
+	///plan was computed. Empty if the plan is empty.
+	///
+	///The check is a PRAGMA foreign_key_check listing the broken constraints, it fails nothing by itself: apply rolls
+	///back when it returns rows, whoever runs the script must do the same.
+	pub fn statements(&self) -> Vec<String> {
+		if self.steps.is_empty() { return Vec::new(); }
+		let rebuild = self.rebuilds();
+		let toggle = rebuild && self.foreign_keys;
+		let mut all = Vec::new();
+		if toggle { all.push("PRAGMA foreign_keys = OFF".to_string()); }
+		all.push("BEGIN".to_string());
+		for s in &self.steps { all.extend(s.statements.iter().cloned()); }
+		if rebuild { all.push(format!("{}\nPRAGMA foreign_key_check", CHECK_COMMENT)); }
+		all.push("COMMIT".to_string());
+		if toggle { all.push("PRAGMA foreign_keys = ON".to_string()); }
+		all
+	}
+
+	///Apply the plan to <i>con</i>, in one transaction.
+	///
+	///Returns a <i>PermissionDenied</i> IoError if the plan has destructive steps and they are not allowed, an
+	///<i>InvalidData</i> IoError if rebuilding tables breaks foreign key constraints, or the IoError of the statement that
+	///failed. The transaction is rolled back on error.
+	pub fn apply(&self, con : &Connection) -> Result<()> {
+		if self.steps.is_empty() { return Ok(()); }
+		if !self.allow_destructive && self.is_destructive() {
+			let list : Vec<String> = self.steps.iter().filter(|s| s.destructive).map(|s| s.to_string()).collect();
+			return Err(Error::new(ErrorKind::PermissionDenied, format!("destructive steps not allowed: {}", list.join(", "))));
+		}
+		let rebuild = self.rebuilds();
+		let foreign_keys = rebuild && foreign_keys(con)?;
+		if foreign_keys { con.execute_batch("PRAGMA foreign_keys = OFF")?; }
+		let r = self.apply_steps(con, rebuild);
+		if r.is_err() && !con.is_autocommit() { let _ = con.execute_batch("ROLLBACK"); }
+		//Restoring the constraints must not hide the error of the migration.
+		if foreign_keys {
+			let restored = con.execute_batch("PRAGMA foreign_keys = ON");
+			if r.is_ok() { restored?; }
+		}
+		r
+	}
+
+	fn apply_steps(&self, con : &Connection, rebuild : bool) -> Result<()> {
+		con.execute_batch("BEGIN")?;
+		for s in &self.steps {
+			for sql in &s.statements { con.execute_batch(sql)?; }
+		}
+		if rebuild {
+			let violations = con.prepare_statement("PRAGMA foreign_key_check")?.fetch_all()?;
+			if let Some(r) = violations.row(0) {
+				return Err(Error::new(ErrorKind::InvalidData, format!("foreign key constraint broken: {} row(s), first in table {} (rowid {}) referencing {}",
+												  violations.row_count(), r.get_string(0), r.get_string(1), r.get_string(2))));
+			}
+		}
+		con.execute_batch("COMMIT")
+	}
+
+	fn rebuilds(&self) -> bool {
+		self.steps.iter().any(|s| s.kind == StepKind::RebuildTable)
+	}
+}
+
+impl fmt::Display for Plan {
+	///Write the SQL script of the plan, each step preceded by a comment.
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		if self.steps.is_empty() { return writeln!(f, "-- nothing to do"); }
+		let rebuild = self.rebuilds();
+		let toggle = rebuild && self.foreign_keys;
+		if toggle { writeln!(f, "PRAGMA foreign_keys = OFF;")?; }
+		writeln!(f, "BEGIN;")?;
+		for s in &self.steps {
+			writeln!(f, "-- {}", s)?;
+			for sql in &s.statements { writeln!(f, "{};", sql)?; }
+		}
+		if rebuild { writeln!(f, "{}\nPRAGMA foreign_key_check;", CHECK_COMMENT)?; }
+		writeln!(f, "COMMIT;")?;
+		if toggle { writeln!(f, "PRAGMA foreign_keys = ON;")?; }
+		Ok(())
+	}
+}
+
+impl Step {
+	///Return what the step does.
+	pub fn kind(&self) -> StepKind {
+		self.kind
+	}
+
+	///Return the name of the object the step changes: a table, index, view or trigger, or table.column for an added column.
+	pub fn object(&self) -> &str {
+		&self.object
+	}
+
+	///Return the SQL statements of the step.
+	pub fn statements(&self) -> &[String] {
+		&self.statements
+	}
+
+	///Return true if the step loses data: it drops a table, or rebuilds a table without some of its columns.
+	pub fn is_destructive(&self) -> bool {
+		self.destructive
+	}
+}
+
+impl fmt::Display for Step {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:?} {}{}", self.kind, self.object, if self.destructive { " (destructive)" } else { "" })
+	}
+}
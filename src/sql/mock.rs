@@ -0,0 +1,497 @@
+use std::any::Any;
+use std::cell::Cell;
+use std::fmt;
+use std::io::{Error, ErrorKind, Result};
+use std::sync::{Arc, Mutex, MutexGuard};
+use sql::{Connection, DbType};
+use sql::driver::{Driver, RawConnection, RawStatement};
+use sql::regex::Regex;
+use sql::script::parameters;
+use sql::value::{Value, ValueRef, format_real};
+
+///Scripted in-memory backend, for testing data-access code without a database.
+///
+///Expected statements are registered with <i>expect</i>, each with the rows or the error it produces. When a statement
+///is executed, the first expectation that matches its SQL text (and its parameters, if given) and has not been used up
+///provides the result; a statement matching no expectation fails with an <i>Other</i> IoError. Every execution is
+///recorded with its bound parameters, <i>verify</i> finally checks that each expectation has been met.
+///
+///A MockDb is a handle on shared state: its clones, the connections it opens and the statements prepared on them all
+///see the same expectations and the same record. It is also a Driver, so it can be registered under a
+///<i>DbType::Custom</i> name for code that opens its own connections.
+#[derive(Clone, Default)]
+pub struct MockDb {
+	state : Arc<Mutex<MockState>>
+}
+
+///Connection opened by a MockDb.
+pub struct MockConnection {
+	db : MockDb,
+	changes : Cell<i64>,
+	total_changes : Cell<i64>,
+	last_insert_rowid : Cell<i64>,
+	in_transaction : Cell<bool>
+}
+
+///Statement prepared on a MockConnection.
+///
+///Parameters are counted from the placeholders of the SQL text (?, ?NNN, :name, @name and $name); parameters that
+///are not bound are recorded as NULL.
+pub struct MockStatement<'c> {
+	p_con : &'c MockConnection,
+	sql : String,
+	params : Vec<Value>,
+	columns : Vec<String>,
+	rows : Vec<Vec<Value>>,
+	//Index of the current row, None before the first step or once the rows are exhausted.
+	row : Option<usize>,
+	//Text rendering of the numbers of the current row, read by column_text.
+	text : Vec<String>,
+	exec : bool
+}
+
+///A statement the MockDb expects, with the outcome it produces.
+///
+///An expectation is met once by default; see <i>times</i> and <i>any_times</i>.
+#[derive(Clone, Debug)]
+pub struct Expectation {
+	pattern : Pattern,
+	params : Option<Vec<Value>>,
+	columns : Vec<String>,
+	rows : Vec<Vec<Value>>,
+	changes : i64,
+	insert_rowid : Option<i64>,
+	error : Option<(ErrorKind, String)>,
+	on_prepare : bool,
+	times : Option<usize>,
+	calls : usize
+}
+
+///A statement executed on a MockDb: its SQL text and the values of its parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Execution {
+	sql : String,
+	params : Vec<Value>,
+	expected : bool
+}
+
+#[derive(Default)]
+struct MockState {
+	expectations : Vec<Expectation>,
+	executions : Vec<Execution>
+}
+
+#[derive(Clone)]
+enum Pattern {
+	Sql(String),
+	Regex(Arc<Regex>)
+}
+
+impl MockDb {
+	///Create a MockDb with no expectation.
+	pub fn new() -> MockDb {
+		MockDb::default()
+	}
+
+	///Register an expectation. Expectations are tried in the order they are registered.
+	pub fn expect(&self, expectation : Expectation) {
+		self.lock().expectations.push(expectation);
+	}
+
+	///Open a connection to the mock database, with DbType <i>Custom("mock")</i>.
+	pub fn connection(&self) -> Connection {
+		Connection::from_raw(DbType::Custom("mock"), Box::new(self.open()))
+	}
+
+	///Open a raw connection to the mock database.
+	pub fn open(&self) -> MockConnection {
+		MockConnection { db : self.clone(), changes : Cell::new(0), total_changes : Cell::new(0),
+						 last_insert_rowid : Cell::new(0), in_transaction : Cell::new(false) }
+	}
+
+	///Return the statements executed so far, in execution order, including the unexpected ones.
+	pub fn executions(&self) -> Vec<Execution> {
+		self.lock().executions.clone()
+	}
+
+	///Return the expectations registered so far, with the number of times each has been met.
+	pub fn expectations(&self) -> Vec<Expectation> {
+		self.lock().expectations.clone()
+	}
+
+	///Check that every expectation has been met the expected number of times and that no unexpected statement has
+	///been executed.
+	///
+	///Returns an <i>Other</i> IoError listing each problem on its own line.
+	pub fn verify(&self) -> Result<()> {
+		let state = self.lock();
+		let mut problems = Vec::new();
+		for e in &state.expectations {
+			if let Some(n) = e.times {
+				if e.calls != n { problems.push(format!("{} expected {} time(s), met {} time(s)", e, n, e.calls)); }
+			}
+		}
+		for x in state.executions.iter().filter(|x| !x.expected) {
+			problems.push(format!("unexpected statement {}", x));
+		}
+		if problems.is_empty() { Ok(()) } else { Err(Error::other(problems.join("\n"))) }
+	}
+
+	///Remove all the expectations and forget the executed statements.
+	pub fn clear(&self) {
+		let mut state = self.lock();
+		state.expectations.clear();
+		state.executions.clear();
+	}
+
+	fn lock(&self) -> MutexGuard<'_, MockState> {
+		self.state.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+impl Driver for MockDb {
+	///Open a connection to the mock database, the target is ignored.
+	fn connect(&self, _target : &str) -> Result<Box<dyn RawConnection>> {
+		Ok(Box::new(self.open()))
+	}
+}
+
+impl MockConnection {
+	///Return the MockDb the connection has been opened by.
+	pub fn db(&self) -> &MockDb {
+		&self.db
+	}
+}
+
+impl RawConnection for MockConnection {
+	///Prepare a statement. The SQL text is not parsed: the statement only fails if an expectation matching it has been
+	///set up with <i>fails_on_prepare</i>.
+	fn prepare<'c>(&'c self, sql : &str) -> Result<Box<dyn RawStatement + 'c>> {
+		let mut state = self.db.lock();
+		let mut columns = Vec::new();
+		if let Some(e) = state.expectations.iter_mut().find(|e| !e.used_up() && e.pattern.matches(sql)) {
+			if let (true, Some((kind, message))) = (e.on_prepare, e.error.as_ref()) {
+				e.calls += 1;
+				return Err(Error::new(*kind, message.clone()));
+			}
+			//The columns are known before execution, parameters may still select another expectation.
+			columns = e.columns.clone();
+		}
+		Ok(Box::new(MockStatement { p_con : self, sql : sql.to_string(), params : vec![Value::Null; parameters(sql).len()],
+									columns, rows : Vec::new(), row : None, text : Vec::new(), exec : false }))
+	}
+
+	fn last_insert_rowid(&self) -> i64 {
+		self.last_insert_rowid.get()
+	}
+
+	fn changes(&self) -> i64 {
+		self.changes.get()
+	}
+
+	fn total_changes(&self) -> i64 {
+		self.total_changes.get()
+	}
+
+	///Return false between a statement starting with BEGIN or START and a statement starting with COMMIT, END or
+	///ROLLBACK (but not ROLLBACK TO).
+	fn is_autocommit(&self) -> bool {
+		!self.in_transaction.get()
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+impl<'c> MockStatement<'c> {
+	fn bind(&mut self, index : i32, value : Value) -> Result<()> {
+		if index < 1 || index as usize > self.params.len() {
+			return Err(Error::other(format!("bind index {} out of range, the statement has {} parameter(s)", index, self.params.len())));
+		}
+		self.params[index as usize - 1] = value;
+		Ok(())
+	}
+
+	//Match the statement against the expectations and take the outcome of the first one.
+	fn run(&mut self) -> Result<()> {
+		let con = self.p_con;
+		let mut state = con.db.lock();
+		let state = &mut *state;
+		let found = state.expectations.iter_mut()
+			.find(|e| !e.used_up() && !e.on_prepare && e.pattern.matches(&self.sql) && e.params.as_ref().is_none_or(|p| *p == self.params));
+		state.executions.push(Execution { sql : self.sql.clone(), params : self.params.clone(), expected : found.is_some() });
+		let e = match found {
+			Some(e) => e,
+			None => return Err(Error::other(format!("unexpected statement {}", state.executions.last().unwrap())))
+		};
+		e.calls += 1;
+		if let Some((kind, ref message)) = e.error { return Err(Error::new(kind, message.clone())); }
+		self.columns = e.columns.clone();
+		self.rows = e.rows.clone();
+		con.changes.set(e.changes);
+		con.total_changes.set(con.total_changes.get() + e.changes);
+		if let Some(rowid) = e.insert_rowid { con.last_insert_rowid.set(rowid); }
+		let mut words = self.sql.split_whitespace().map(|w| w.trim_end_matches(';').to_ascii_uppercase());
+		match words.next().as_deref() {
+			Some("BEGIN") | Some("START") => con.in_transaction.set(true),
+			Some("COMMIT") | Some("END") => con.in_transaction.set(false),
+			Some("ROLLBACK") if words.next().as_deref() != Some("TO") => con.in_transaction.set(false),
+			_ => ()
+		}
+		Ok(())
+	}
+
+	fn current(&self) -> Option<&[Value]> {
+		self.row.and_then(|r| self.rows.get(r)).map(|r| &r[..])
+	}
+}
+
+impl<'c> RawStatement for MockStatement<'c> {
+	fn reset(&mut self) -> Result<()> {
+		self.exec = false;
+		self.row = None;
+		self.rows.clear();
+		Ok(())
+	}
+
+	fn step(&mut self) -> Result<bool> {
+		let next = if !self.exec {
+			self.exec = true;
+			self.run()?;
+			0
+		} else {
+			match self.row { Some(r) => r + 1, None => return Ok(false) }
+		};
+		if next >= self.rows.len() {
+			self.row = None;
+			self.rows.clear();
+			return Ok(false);
+		}
+		self.row = Some(next);
+		self.text = self.rows[next].iter().map(|v| match *v {
+			Value::Integer(i) => i.to_string(),
+			Value::Real(f) => format_real(f),
+			_ => String::new()
+		}).collect();
+		Ok(true)
+	}
+
+	fn bind_null(&mut self, index : i32) -> Result<()> {
+		self.bind(index, Value::Null)
+	}
+
+	fn bind_int64(&mut self, index : i32, value : i64) -> Result<()> {
+		self.bind(index, Value::Integer(value))
+	}
+
+	fn bind_double(&mut self, index : i32, value : f64) -> Result<()> {
+		self.bind(index, Value::Real(value))
+	}
+
+	fn bind_text(&mut self, index : i32, value : &str) -> Result<()> {
+		self.bind(index, Value::Text(value.to_string()))
+	}
+
+	fn bind_blob(&mut self, index : i32, value : &[u8]) -> Result<()> {
+		self.bind(index, Value::Blob(value.to_vec()))
+	}
+
+	fn column_count(&self) -> i32 {
+		self.columns.len() as i32
+	}
+
+	fn column_name(&self, index : i32) -> String {
+		if index < 0 { return String::new(); }
+		self.columns.get(index as usize).cloned().unwrap_or_default()
+	}
+
+	fn column_value(&self, index : i32) -> ValueRef<'_> {
+		match self.current() {
+			Some(r) if index >= 0 && (index as usize) < r.len() => r[index as usize].as_value_ref(),
+			_ => ValueRef::Null
+		}
+	}
+
+	fn column_text(&self, index : i32) -> &[u8] {
+		match self.column_value(index) {
+			ValueRef::Text(b) | ValueRef::Blob(b) => b,
+			ValueRef::Null => &[],
+			_ => self.text[index as usize].as_bytes()
+		}
+	}
+
+	fn sql(&self) -> String {
+		self.sql.clone()
+	}
+
+	fn is_busy(&self) -> bool {
+		self.row.is_some()
+	}
+}
+
+impl Expectation {
+	///Expect a statement whose SQL text is exactly <i>sql</i>, once leading and trailing whitespace is ignored.
+	pub fn sql(sql : &str) -> Expectation {
+		Expectation::with_pattern(Pattern::Sql(sql.trim().to_string()))
+	}
+
+	///Expect a statement whose SQL text matches the regular expression <i>pattern</i> anywhere; anchor it with ^ and $
+	///to match the whole text. The syntax supports classes, \d \w \s \b, groups, alternation, the usual quantifiers and
+	///a leading (?i) for a case-insensitive match. Matching takes a time linear in the length of the text, repetition
+	///bounds go up to 1000.
+	///
+	///Returns an <i>InvalidInput</i> IoError if the pattern is malformed or too large.
+	pub fn regex(pattern : &str) -> Result<Expectation> {
+		Ok(Expectation::with_pattern(Pattern::Regex(Arc::new(Regex::new(pattern)?))))
+	}
+
+	fn with_pattern(pattern : Pattern) -> Expectation {
+		Expectation { pattern, params : None, columns : Vec::new(), rows : Vec::new(), changes : 0, insert_rowid : None,
+					  error : None, on_prepare : false, times : Some(1), calls : 0 }
+	}
+
+	///Only match when the statement is executed with these parameter values, the first one being parameter 1.
+	pub fn with_params(mut self, params : Vec<Value>) -> Expectation {
+		self.params = Some(params);
+		self
+	}
+
+	///Set the names of the columns of the result.
+	pub fn columns(mut self, names : &[&str]) -> Expectation {
+		self.columns = names.iter().map(|n| n.to_string()).collect();
+		self
+	}
+
+	///Add a row to the result.
+	pub fn row(mut self, values : Vec<Value>) -> Expectation {
+		self.rows.push(values);
+		self
+	}
+
+	///Add rows to the result.
+	pub fn rows(mut self, rows : Vec<Vec<Value>>) -> Expectation {
+		self.rows.extend(rows);
+		self
+	}
+
+	///Set the number of rows the statement inserts, modifies or deletes, as reported by <i>execute_update</i>.
+	pub fn affects(mut self, changes : i64) -> Expectation {
+		self.changes = changes;
+		self
+	}
+
+	///Set the rowid the statement inserts, as reported by <i>Connection::last_insert_rowid</i>.
+	pub fn inserts_rowid(mut self, rowid : i64) -> Expectation {
+		self.insert_rowid = Some(rowid);
+		self
+	}
+
+	///Make the statement fail when it is executed, with an IoError of kind <i>kind</i>.
+	pub fn fails(mut self, kind : ErrorKind, message : &str) -> Expectation {
+		self.error = Some((kind, message.to_string()));
+		self.on_prepare = false;
+		self
+	}
+
+	///Make the statement fail when it is prepared, with an IoError of kind <i>kind</i>, as a syntax error would.
+	pub fn fails_on_prepare(mut self, kind : ErrorKind, message : &str) -> Expectation {
+		self.error = Some((kind, message.to_string()));
+		self.on_prepare = true;
+		self
+	}
+
+	///Expect the statement exactly <i>n</i> times; it is used up afterwards.
+	pub fn times(mut self, n : usize) -> Expectation {
+		self.times = Some(n);
+		self
+	}
+
+	///Accept the statement any number of times, including never; it is never used up.
+	pub fn any_times(mut self) -> Expectation {
+		self.times = None;
+		self
+	}
+
+	///Return the number of times the expectation has been met.
+	pub fn calls(&self) -> usize {
+		self.calls
+	}
+
+	fn used_up(&self) -> bool {
+		self.times.is_some_and(|n| self.calls >= n)
+	}
+}
+
+impl fmt::Display for Expectation {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		match self.pattern {
+			Pattern::Sql(ref s) => write!(f, "statement \"{}\"", s)?,
+			Pattern::Regex(ref r) => write!(f, "statement matching /{}/", r.as_str())?
+		}
+		if let Some(ref p) = self.params { write!(f, " with {}", Params(p))?; }
+		Ok(())
+	}
+}
+
+impl Execution {
+	///Return the SQL text of the statement.
+	pub fn sql(&self) -> &str {
+		&self.sql
+	}
+
+	///Return the values of the parameters when the statement was executed, the first one being parameter 1.
+	pub fn params(&self) -> &[Value] {
+		&self.params
+	}
+
+	///Return true if the statement matched an expectation.
+	pub fn is_expected(&self) -> bool {
+		self.expected
+	}
+}
+
+impl fmt::Display for Execution {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "\"{}\"", self.sql)?;
+		if !self.params.is_empty() { write!(f, " with {}", Params(&self.params))?; }
+		Ok(())
+	}
+}
+
+impl Pattern {
+	fn matches(&self, sql : &str) -> bool {
+		match *self {
+			Pattern::Sql(ref s) => s == sql.trim(),
+			Pattern::Regex(ref r) => r.is_match(sql)
+		}
+	}
+}
+
+impl fmt::Debug for Pattern {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Pattern::Sql(ref s) => write!(f, "Sql({:?})", s),
+			Pattern::Regex(ref r) => write!(f, "Regex({:?})", r.as_str())
+		}
+	}
+}
+
+struct Params<'v>(&'v [Value]);
+
+impl<'v> fmt::Display for Params<'v> {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "[")?;
+		for (i, v) in self.0.iter().enumerate() {
+			if i > 0 { write!(f, ", ")?; }
+			match *v {
+				Value::Null => write!(f, "NULL")?,
+				Value::Integer(n) => write!(f, "{}", n)?,
+				Value::Real(r) => write!(f, "{}", format_real(r))?,
+				Value::Text(ref s) => write!(f, "'{}'", s.replace('\'', "''"))?,
+				Value::Blob(ref b) => { write!(f, "x'")?; for x in b { write!(f, "{:02x}", x)?; } write!(f, "'")? }
+			}
+		}
+		write!(f, "]")
+	}
+}
@@ -0,0 +1,265 @@
+use std::fmt;
+use std::io::{Error, ErrorKind, Result};
+use std::time::Duration;
+use sql::{Connection, DbType, ValueRef};
+
+///The journal mode of an SQLite database, PRAGMA journal_mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JournalMode {
+	///The rollback journal is deleted at the end of each transaction, the default.
+	Delete,
+	///The rollback journal is truncated to zero length.
+	Truncate,
+	///The header of the rollback journal is overwritten with zeros.
+	Persist,
+	///The rollback journal is kept in memory.
+	Memory,
+	///Write-ahead log: readers do not block the writer, nor the writer the readers. Not available for in-memory
+	///databases.
+	Wal,
+	///No rollback journal, a transaction cannot be rolled back safely.
+	Off
+}
+
+///How often SQLite waits for the data to reach the disk, PRAGMA synchronous.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Synchronous {
+	Off,
+	///Sync at the critical moments only: safe from corruption in WAL mode, a power loss may roll back the last
+	///transactions.
+	Normal,
+	///Sync at each transaction, the default.
+	Full,
+	///As Full, and sync the directory of a deleted rollback journal too.
+	Extra
+}
+
+///Where temporary tables and indexes are stored, PRAGMA temp_store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TempStore {
+	///As chosen when SQLite was compiled, a file by default.
+	Default,
+	File,
+	Memory
+}
+
+impl JournalMode {
+	fn name(&self) -> &'static str {
+		match *self {
+			JournalMode::Delete => "delete",
+			JournalMode::Truncate => "truncate",
+			JournalMode::Persist => "persist",
+			JournalMode::Memory => "memory",
+			JournalMode::Wal => "wal",
+			JournalMode::Off => "off"
+		}
+	}
+
+	fn from_name(name : &str) -> Option<JournalMode> {
+		[JournalMode::Delete, JournalMode::Truncate, JournalMode::Persist, JournalMode::Memory, JournalMode::Wal, JournalMode::Off]
+			.iter().find(|m| m.name().eq_ignore_ascii_case(name)).cloned()
+	}
+}
+
+impl fmt::Display for JournalMode {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl Synchronous {
+	fn from_level(level : i64) -> Option<Synchronous> {
+		[Synchronous::Off, Synchronous::Normal, Synchronous::Full, Synchronous::Extra].get(level as usize).cloned()
+	}
+}
+
+impl TempStore {
+	fn from_level(level : i64) -> Option<TempStore> {
+		[TempStore::Default, TempStore::File, TempStore::Memory].get(level as usize).cloned()
+	}
+}
+
+///Settings applied to a connection when it is opened, with <i>Connection::open</i> or <i>Connection::configure</i>.
+///
+///Only the settings that are given are changed, in the order of the fields: the journal mode first, as it may need
+///the database to itself. Each setting is read back after it is changed, and an error is returned if the database
+///did not take it, such as WAL on an in-memory database.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConnectionConfig {
+	journal_mode : Option<JournalMode>,
+	synchronous : Option<Synchronous>,
+	foreign_keys : Option<bool>,
+	busy_timeout : Option<Duration>,
+	cache_size : Option<i64>,
+	mmap_size : Option<u64>,
+	temp_store : Option<TempStore>
+}
+
+impl ConnectionConfig {
+	///Create a configuration that changes nothing.
+	pub fn new() -> ConnectionConfig {
+		ConnectionConfig::default()
+	}
+
+	pub fn journal_mode(mut self, mode : JournalMode) -> ConnectionConfig {
+		self.journal_mode = Some(mode);
+		self
+	}
+
+	pub fn synchronous(mut self, synchronous : Synchronous) -> ConnectionConfig {
+		self.synchronous = Some(synchronous);
+		self
+	}
+
+	pub fn foreign_keys(mut self, enabled : bool) -> ConnectionConfig {
+		self.foreign_keys = Some(enabled);
+		self
+	}
+
+	pub fn busy_timeout(mut self, timeout : Duration) -> ConnectionConfig {
+		self.busy_timeout = Some(timeout);
+		self
+	}
+
+	///Set the size of the page cache: a number of pages if positive, of kibibytes if negative.
+	pub fn cache_size(mut self, size : i64) -> ConnectionConfig {
+		self.cache_size = Some(size);
+		self
+	}
+
+	///Set the number of bytes of the database file mapped in memory, 0 to disable memory-mapped I/O.
+	pub fn mmap_size(mut self, size : u64) -> ConnectionConfig {
+		self.mmap_size = Some(size);
+		self
+	}
+
+	pub fn temp_store(mut self, store : TempStore) -> ConnectionConfig {
+		self.temp_store = Some(store);
+		self
+	}
+
+	//Apply the settings to a connection, stopping at the first that fails.
+	pub fn apply(&self, con : &Connection) -> Result<()> {
+		if let Some(mode) = self.journal_mode { set_journal_mode(con, mode)?; }
+		if let Some(s) = self.synchronous { set_synchronous(con, s)?; }
+		if let Some(on) = self.foreign_keys { set_foreign_keys(con, on)?; }
+		if let Some(t) = self.busy_timeout { set_busy_timeout(con, t)?; }
+		if let Some(n) = self.cache_size { set_cache_size(con, n)?; }
+		if let Some(n) = self.mmap_size { set_mmap_size(con, n)?; }
+		if let Some(t) = self.temp_store { set_temp_store(con, t)?; }
+		Ok(())
+	}
+}
+
+//Run a PRAGMA statement and return the first column of its first row, None if it returns no row.
+fn pragma<T, F : Fn(ValueRef) -> T>(con : &Connection, sql : &str, read : F) -> Result<Option<T>> {
+	if con.db_type() != DbType::SQLite3 {
+		return Err(Error::new(ErrorKind::Unsupported, format!("no PRAGMA for {:?}", con.db_type())));
+	}
+	let mut st = con.prepare_statement(sql)?;
+	let mut rows = st.query();
+	let value = match rows.next() {
+		Some(row) => Some(read(row?.get_value_ref(0))),
+		None => None
+	};
+	Ok(value)
+}
+
+//Read the value of a PRAGMA, an error if it returns no row or a value that cannot be read.
+fn get<T, F : Fn(ValueRef) -> Option<T>>(con : &Connection, name : &str, read : F) -> Result<T> {
+	match pragma(con, &format!("PRAGMA {}", name), |v| read(v).ok_or_else(|| v.as_text().into_owned()))? {
+		Some(Ok(value)) => Ok(value),
+		Some(Err(text)) => Err(Error::new(ErrorKind::InvalidData, format!("unexpected {} '{}'", name, text))),
+		None => Err(Error::new(ErrorKind::InvalidData, format!("no value for {}", name)))
+	}
+}
+
+//Error of a setting the database did not take.
+fn refused<T : fmt::Debug>(name : &str, wanted : T, got : T) -> Error {
+	Error::other(format!("{} is {:?}, could not be set to {:?}", name, got, wanted))
+}
+
+fn integer(v : ValueRef) -> Option<i64> {
+	match v { ValueRef::Integer(i) => Some(i), _ => None }
+}
+
+pub fn journal_mode(con : &Connection) -> Result<JournalMode> {
+	get(con, "journal_mode", |v| JournalMode::from_name(&v.as_text()))
+}
+
+//Change the journal mode. The new mode is returned by the PRAGMA itself, which keeps the current mode when the change
+//is not possible.
+pub fn set_journal_mode(con : &Connection, mode : JournalMode) -> Result<()> {
+	let got = pragma(con, &format!("PRAGMA journal_mode = {}", mode.name()), |v| JournalMode::from_name(&v.as_text()))?.and_then(|m| m);
+	match got {
+		Some(m) if m == mode => Ok(()),
+		Some(m) => Err(refused("journal_mode", mode, m)),
+		None => journal_mode(con).and_then(|m| if m == mode { Ok(()) } else { Err(refused("journal_mode", mode, m)) })
+	}
+}
+
+pub fn synchronous(con : &Connection) -> Result<Synchronous> {
+	get(con, "synchronous", |v| integer(v).and_then(Synchronous::from_level))
+}
+
+pub fn set_synchronous(con : &Connection, synchronous : Synchronous) -> Result<()> {
+	pragma(con, &format!("PRAGMA synchronous = {}", synchronous as i64), |_| ())?;
+	let got = self::synchronous(con)?;
+	if got == synchronous { Ok(()) } else { Err(refused("synchronous", synchronous, got)) }
+}
+
+pub fn foreign_keys(con : &Connection) -> Result<bool> {
+	get(con, "foreign_keys", |v| integer(v).map(|i| i != 0))
+}
+
+//Enable or disable the foreign key constraints, which SQLite ignores within a transaction.
+pub fn set_foreign_keys(con : &Connection, enabled : bool) -> Result<()> {
+	pragma(con, &format!("PRAGMA foreign_keys = {}", if enabled { "ON" } else { "OFF" }), |_| ())?;
+	let got = foreign_keys(con)?;
+	if got == enabled { Ok(()) } else { Err(refused("foreign_keys", enabled, got)) }
+}
+
+pub fn busy_timeout(con : &Connection) -> Result<Duration> {
+	get(con, "busy_timeout", |v| integer(v).map(|ms| Duration::from_millis(ms.max(0) as u64)))
+}
+
+pub fn set_busy_timeout(con : &Connection, timeout : Duration) -> Result<()> {
+	let ms = timeout.as_millis().min(i32::MAX as u128);
+	pragma(con, &format!("PRAGMA busy_timeout = {}", ms), |_| ())?;
+	Ok(())
+}
+
+pub fn cache_size(con : &Connection) -> Result<i64> {
+	get(con, "cache_size", integer)
+}
+
+pub fn set_cache_size(con : &Connection, size : i64) -> Result<()> {
+	pragma(con, &format!("PRAGMA cache_size = {}", size), |_| ())?;
+	let got = cache_size(con)?;
+	if got == size { Ok(()) } else { Err(refused("cache_size", size, got)) }
+}
+
+pub fn mmap_size(con : &Connection) -> Result<u64> {
+	get(con, "mmap_size", |v| integer(v).map(|n| n.max(0) as u64))
+}
+
+//Change the size of the memory map, which SQLite caps at the maximum it was compiled with: the size in effect is
+//returned.
+pub fn set_mmap_size(con : &Connection, size : u64) -> Result<u64> {
+	let size = size.min(i64::MAX as u64);
+	let got = pragma(con, &format!("PRAGMA mmap_size = {}", size), integer)?.and_then(|n| n);
+	match got {
+		Some(n) => Ok(n.max(0) as u64),
+		None => mmap_size(con)
+	}
+}
+
+pub fn temp_store(con : &Connection) -> Result<TempStore> {
+	get(con, "temp_store", |v| integer(v).and_then(TempStore::from_level))
+}
+
+pub fn set_temp_store(con : &Connection, store : TempStore) -> Result<()> {
+	pragma(con, &format!("PRAGMA temp_store = {}", store as i64), |_| ())?;
+	let got = temp_store(con)?;
+	if got == store { Ok(()) } else { Err(refused("temp_store", store, got)) }
+}
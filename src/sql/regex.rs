@@ -0,0 +1,339 @@
+use std::io::{Error, ErrorKind, Result};
+
+//A small regular expression matcher, enough to match SQL texts without depending on an external crate.
+//Supported: literals, '.', classes [a-z] [^...], \d \w \s \D \W \S \b, anchors ^ $, groups ( ) and (?: ),
+//alternation |, quantifiers * + ? {n} {n,} {n,m} (lazy forms accepted), and a leading (?i) for case-insensitivity.
+//The pattern is compiled to the instructions of a nondeterministic automaton whose states all advance together over
+//the text, so matching takes a time proportional to the length of the text times the size of the program, whatever
+//the pattern: there is no backtracking to blow up. Bounded repetitions are expanded into copies, which is why their
+//bounds and the size of the program are limited.
+pub struct Regex {
+	pattern : String,
+	program : Vec<Inst>,
+	icase : bool
+}
+
+enum Node {
+	Char(char),
+	Any,
+	Class(Vec<(char, char)>, bool),
+	Start,
+	End,
+	WordBoundary(bool),
+	Concat(Vec<Node>),
+	Alt(Vec<Node>),
+	Repeat(Box<Node>, usize, Option<usize>)
+}
+
+//An instruction of the automaton. The first three consume a character, the others move to other instructions.
+enum Inst {
+	Char(char),
+	Any,
+	Class(Vec<(char, char)>, bool),
+	Start,
+	End,
+	WordBoundary(bool),
+	Split(usize, usize),
+	Jump(usize),
+	Match
+}
+
+struct Parser<'p> {
+	chars : Vec<char>,
+	pos : usize,
+	pattern : &'p str
+}
+
+//The largest bound of a repetition and the largest number of instructions of a program.
+const MAX_REPEAT : usize = 1000;
+const MAX_PROGRAM : usize = 100000;
+
+impl Regex {
+	//Compile a pattern, returns an InvalidInput IoError if it is malformed or too large.
+	pub fn new(pattern : &str) -> Result<Regex> {
+		let (icase, body) = if let Some(rest) = pattern.strip_prefix("(?i)") { (true, rest) } else { (false, pattern) };
+		let mut p = Parser { chars : body.chars().collect(), pos : 0, pattern };
+		let root = p.alternation()?;
+		if p.pos < p.chars.len() { return Err(p.error("unmatched ')'")); }
+		let mut program = Vec::new();
+		if !compile(&root, &mut program) {
+			return Err(Error::new(ErrorKind::InvalidInput, format!("invalid regular expression '{}': too large once repetitions are expanded", pattern)));
+		}
+		program.push(Inst::Match);
+		Ok(Regex { pattern : pattern.to_string(), program, icase })
+	}
+
+	pub fn as_str(&self) -> &str {
+		&self.pattern
+	}
+
+	//Return true if the pattern matches somewhere in the text.
+	pub fn is_match(&self, text : &str) -> bool {
+		let s : Vec<char> = text.chars().collect();
+		let (mut current, mut next) = (Vec::new(), Vec::new());
+		//The position at which each instruction was last added, so that it is added once per position.
+		let mut added = vec![usize::MAX; self.program.len()];
+		let mut stack = Vec::new();
+		for i in 0..s.len() + 1 {
+			//A new match may start at every position.
+			if self.add(&mut current, &mut added, &mut stack, 0, &s, i) { return true; }
+			if i == s.len() { break; }
+			for &pc in &current {
+				let hit = match self.program[pc] {
+					Inst::Char(c) => self.same(s[i], c),
+					Inst::Any => s[i] != '\n',
+					Inst::Class(ref ranges, negated) => self.in_class(ranges, s[i]) != negated,
+					_ => false
+				};
+				if hit && self.add(&mut next, &mut added, &mut stack, pc + 1, &s, i + 1) { return true; }
+			}
+			current.clear();
+			::std::mem::swap(&mut current, &mut next);
+		}
+		false
+	}
+
+	//Add to <i>list</i> the instructions consuming a character reachable from <i>pc</i> at position <i>i</i>, return
+	//true if the match is reached.
+	fn add(&self, list : &mut Vec<usize>, added : &mut [usize], stack : &mut Vec<usize>, pc : usize, s : &[char], i : usize) -> bool {
+		stack.clear();
+		stack.push(pc);
+		while let Some(pc) = stack.pop() {
+			if added[pc] == i { continue; }
+			added[pc] = i;
+			match self.program[pc] {
+				Inst::Match => return true,
+				Inst::Jump(to) => stack.push(to),
+				//The order of the branches does not change whether a text matches.
+				Inst::Split(a, b) => { stack.push(b); stack.push(a); },
+				Inst::Start => if i == 0 { stack.push(pc + 1); },
+				Inst::End => if i == s.len() { stack.push(pc + 1); },
+				Inst::WordBoundary(expected) => {
+					let before = i > 0 && is_word(s[i - 1]);
+					let after = i < s.len() && is_word(s[i]);
+					if (before != after) == expected { stack.push(pc + 1); }
+				},
+				_ => list.push(pc)
+			}
+		}
+		false
+	}
+
+	fn same(&self, a : char, b : char) -> bool {
+		a == b || (self.icase && a.to_lowercase().eq(b.to_lowercase()))
+	}
+
+	fn in_class(&self, ranges : &[(char, char)], c : char) -> bool {
+		let hit = |c : char| ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi);
+		hit(c) || (self.icase && (c.to_lowercase().any(hit) || c.to_uppercase().any(hit)))
+	}
+}
+
+//Append the instructions of a node to the program, return false if the program grows too large.
+fn compile(n : &Node, program : &mut Vec<Inst>) -> bool {
+	match *n {
+		Node::Char(c) => program.push(Inst::Char(c)),
+		Node::Any => program.push(Inst::Any),
+		Node::Class(ref ranges, negated) => program.push(Inst::Class(ranges.clone(), negated)),
+		Node::Start => program.push(Inst::Start),
+		Node::End => program.push(Inst::End),
+		Node::WordBoundary(expected) => program.push(Inst::WordBoundary(expected)),
+		Node::Concat(ref v) => {
+			for item in v { if !compile(item, program) { return false; } }
+		},
+		Node::Alt(ref v) => {
+			//Split to each branch in turn, every branch but the last jumps to the end.
+			let mut jumps = Vec::new();
+			for (k, a) in v.iter().enumerate() {
+				let split = program.len();
+				if k + 1 < v.len() { program.push(Inst::Split(split + 1, 0)); }
+				if !compile(a, program) { return false; }
+				if k + 1 < v.len() {
+					jumps.push(program.len());
+					program.push(Inst::Jump(0));
+					let next = program.len();
+					program[split] = Inst::Split(split + 1, next);
+				}
+			}
+			let end = program.len();
+			for j in jumps { program[j] = Inst::Jump(end); }
+		},
+		Node::Repeat(ref r, min, max) => {
+			for _ in 0..min { if !compile(r, program) { return false; } }
+			match max {
+				//Loop: split to one more repetition or out, the repetition jumps back to the split.
+				None => {
+					let split = program.len();
+					program.push(Inst::Split(split + 1, 0));
+					if !compile(r, program) { return false; }
+					program.push(Inst::Jump(split));
+					let end = program.len();
+					program[split] = Inst::Split(split + 1, end);
+				},
+				//Optional copies, each split skips the remaining ones.
+				Some(max) => {
+					let mut splits = Vec::new();
+					for _ in min..max {
+						splits.push(program.len());
+						program.push(Inst::Split(0, 0));
+						if !compile(r, program) { return false; }
+					}
+					let end = program.len();
+					for s in splits { program[s] = Inst::Split(s + 1, end); }
+				}
+			}
+		}
+	}
+	program.len() <= MAX_PROGRAM
+}
+
+impl<'p> Parser<'p> {
+	fn error(&self, what : &str) -> Error {
+		Error::new(ErrorKind::InvalidInput, format!("invalid regular expression '{}': {} at offset {}", self.pattern, what, self.pos))
+	}
+
+	fn peek(&self) -> Option<char> {
+		self.chars.get(self.pos).cloned()
+	}
+
+	fn alternation(&mut self) -> Result<Node> {
+		let mut branches = vec![self.concatenation()?];
+		while self.peek() == Some('|') {
+			self.pos += 1;
+			branches.push(self.concatenation()?);
+		}
+		Ok(if branches.len() == 1 { branches.pop().unwrap() } else { Node::Alt(branches) })
+	}
+
+	fn concatenation(&mut self) -> Result<Node> {
+		let mut items = Vec::new();
+		while let Some(c) = self.peek() {
+			if c == '|' || c == ')' { break; }
+			let atom = self.atom()?;
+			items.push(self.quantified(atom)?);
+		}
+		Ok(Node::Concat(items))
+	}
+
+	fn quantified(&mut self, atom : Node) -> Result<Node> {
+		let (min, max) = match self.peek() {
+			Some('*') => { self.pos += 1; (0, None) },
+			Some('+') => { self.pos += 1; (1, None) },
+			Some('?') => { self.pos += 1; (0, Some(1)) },
+			Some('{') => match self.bounds() {
+				Some(b) => b,
+				None => return Ok(atom)
+			},
+			_ => return Ok(atom)
+		};
+		if let Some(m) = max { if m < min { return Err(self.error("invalid repetition bounds")); } }
+		if max.unwrap_or(min) > MAX_REPEAT { return Err(self.error("repetition bound too large")); }
+		if let Node::Start | Node::End | Node::WordBoundary(_) = atom { return Err(self.error("nothing to repeat")); }
+		//Laziness does not change whether a text matches.
+		if self.peek() == Some('?') { self.pos += 1; }
+		Ok(Node::Repeat(Box::new(atom), min, max))
+	}
+
+	//Parse {n}, {n,} or {n,m}; a brace that does not start bounds is a literal.
+	fn bounds(&mut self) -> Option<(usize, Option<usize>)> {
+		let rest : String = self.chars[self.pos..].iter().collect();
+		let end = rest.find('}')?;
+		let inner = &rest[1..end];
+		let parse = |t : &str| if !t.is_empty() && t.bytes().all(|b| b.is_ascii_digit()) { t.parse::<usize>().ok() } else { None };
+		let b = match inner.find(',') {
+			None => { let n = parse(inner)?; (n, Some(n)) },
+			Some(c) => {
+				let min = parse(&inner[..c])?;
+				if inner[c + 1..].is_empty() { (min, None) } else { (min, Some(parse(&inner[c + 1..])?)) }
+			}
+		};
+		self.pos += inner.chars().count() + 2;
+		Some(b)
+	}
+
+	fn atom(&mut self) -> Result<Node> {
+		let c = self.chars[self.pos];
+		self.pos += 1;
+		match c {
+			'.' => Ok(Node::Any),
+			'^' => Ok(Node::Start),
+			'$' => Ok(Node::End),
+			'*' | '+' | '?' => { self.pos -= 1; Err(self.error("nothing to repeat")) },
+			'(' => {
+				if self.chars[self.pos..].starts_with(&['?', ':']) { self.pos += 2; }
+				else if self.peek() == Some('?') { return Err(self.error("unsupported group")); }
+				let inner = self.alternation()?;
+				if self.peek() != Some(')') { return Err(self.error("missing ')'")); }
+				self.pos += 1;
+				Ok(inner)
+			},
+			'[' => self.class(),
+			'\\' => self.escape(),
+			c => Ok(Node::Char(c))
+		}
+	}
+
+	fn escape(&mut self) -> Result<Node> {
+		let c = self.peek().ok_or_else(|| self.error("trailing '\\'"))?;
+		self.pos += 1;
+		Ok(match c {
+			'b' => Node::WordBoundary(true),
+			'B' => Node::WordBoundary(false),
+			'd' | 'w' | 's' => Node::Class(shorthand(c), false),
+			'D' | 'W' | 'S' => Node::Class(shorthand(c.to_ascii_lowercase()), true),
+			c => Node::Char(control(c))
+		})
+	}
+
+	fn class(&mut self) -> Result<Node> {
+		let negated = self.peek() == Some('^');
+		if negated { self.pos += 1; }
+		let mut ranges = Vec::new();
+		let mut first = true;
+		loop {
+			let c = self.peek().ok_or_else(|| self.error("missing ']'"))?;
+			self.pos += 1;
+			if c == ']' && !first { break; }
+			first = false;
+			let lo = if c == '\\' {
+				let e = self.peek().ok_or_else(|| self.error("missing ']'"))?;
+				self.pos += 1;
+				match e {
+					'd' | 'w' | 's' => { ranges.extend(shorthand(e)); continue; },
+					'D' | 'W' | 'S' | 'b' | 'B' => return Err(self.error("unsupported escape in class")),
+					e => control(e)
+				}
+			} else { c };
+			if self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|&n| n != ']') {
+				self.pos += 1;
+				let mut hi = self.chars[self.pos];
+				self.pos += 1;
+				if hi == '\\' {
+					hi = control(self.peek().ok_or_else(|| self.error("missing ']'"))?);
+					self.pos += 1;
+				}
+				if hi < lo { return Err(self.error("invalid class range")); }
+				ranges.push((lo, hi));
+			} else {
+				ranges.push((lo, lo));
+			}
+		}
+		Ok(Node::Class(ranges, negated))
+	}
+}
+
+fn shorthand(c : char) -> Vec<(char, char)> {
+	match c {
+		'd' => vec![('0', '9')],
+		'w' => vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')],
+		_ => vec![(' ', ' '), ('\t', '\r')]
+	}
+}
+
+fn control(c : char) -> char {
+	match c { 'n' => '\n', 't' => '\t', 'r' => '\r', c => c }
+}
+
+fn is_word(c : char) -> bool {
+	c.is_alphanumeric() || c == '_'
+}
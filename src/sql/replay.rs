@@ -0,0 +1,699 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::fs::{self, File};
+use std::io::{BufWriter, Error, ErrorKind, Result, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use sql::{Connection, DbType};
+use sql::crypto::{from_hex, to_hex};
+use sql::driver::{Driver, RawConnection, RawStatement, get_driver};
+use sql::value::{Value, ValueRef, format_real};
+use sql::columnar::ColumnType;
+
+const HEADER : &str = "rustic-replay\t1";
+
+///Driver recording the interactions of the connections it opens to a file, for a later replay by a ReplayDriver.
+///
+///Each connection is opened by the wrapped driver and works as usual, while every prepare, bind, step and reset is
+///written to the recording with its outcome: the columns, the rows read, the errors returned, and the changes and
+///rowid reported by the connection. The recording is a text file, one event per line, written as the connection goes and
+///flushed when it is closed. Each connection overwrites the file, so open one connection per recording.
+///
+///The connection target is not recorded, so passwords do not end up in the file.
+pub struct RecordDriver {
+	inner : Arc<dyn Driver>,
+	path : PathBuf
+}
+
+///Driver replaying a recording made by a RecordDriver, without any database.
+///
+///The code under test must interact with the replayed connection exactly as it did with the recorded one: same
+///statements, in the same order, with the same parameter values. The replay fails loudly otherwise: on the first
+///divergence it panics, with the line of the recording and the expected and actual interactions. Dropping the connection
+///also panics if part of the recording has not been replayed, unless the thread is already panicking.
+///
+///Rows, column names and errors are those of the recording; errors are replayed with their kind and message only,
+///backend specific errors such as PgError are not restored. The connection target is ignored.
+pub struct ReplayDriver {
+	path : PathBuf
+}
+
+///Connection recording its interactions, opened by a RecordDriver.
+pub struct RecordConnection {
+	inner : Box<dyn RawConnection>,
+	out : RefCell<BufWriter<File>>,
+	next_id : Cell<u64>,
+	state : Cell<(i64, i64, i64, bool)>
+}
+
+///Statement prepared on a RecordConnection.
+pub struct RecordStatement<'c> {
+	p_con : &'c RecordConnection,
+	inner : Box<dyn RawStatement + 'c>,
+	id : u64,
+	columns : String,
+	//True until the statement is stepped after being prepared or reset, when its columns are checked again.
+	fresh : bool
+}
+
+///Connection replaying a recording, opened by a ReplayDriver.
+pub struct ReplayConnection {
+	path : PathBuf,
+	lines : Vec<String>,
+	pos : Cell<usize>,
+	next_id : Cell<u64>,
+	state : Cell<(i64, i64, i64, bool)>
+}
+
+///Statement prepared on a ReplayConnection.
+pub struct ReplayStatement<'c> {
+	p_con : &'c ReplayConnection,
+	id : u64,
+	sql : String,
+	readonly : bool,
+	explain : bool,
+	columns : Vec<(String, Option<String>)>,
+	row : Option<Vec<Value>>,
+	//Text rendering of the numbers of the current row, as recorded.
+	text : Vec<String>
+}
+
+impl RecordDriver {
+	///Create a driver recording to <i>path</i> the connections opened by <i>inner</i>.
+	pub fn new<P : AsRef<Path>>(inner : Arc<dyn Driver>, path : P) -> RecordDriver {
+		RecordDriver { inner, path : path.as_ref().to_path_buf() }
+	}
+
+	///Open a connection with the driver registered under <i>db_type</i> and record it to <i>path</i>.
+	///
+	///Returns an IoError if no driver is registered under <i>db_type</i>, if the connection fails or if the recording
+	///cannot be created.
+	pub fn open<P : AsRef<Path>>(db_type : DbType, target : &str, path : P) -> Result<Connection> {
+		let raw = RecordDriver::new(get_driver(db_type)?, path).connect(target)?;
+		Ok(Connection::from_raw(db_type, raw))
+	}
+}
+
+impl Driver for RecordDriver {
+	fn connect(&self, target : &str) -> Result<Box<dyn RawConnection>> {
+		let inner = self.inner.connect(target)?;
+		let con = RecordConnection { inner, out : RefCell::new(BufWriter::new(File::create(&self.path)?)),
+									 next_id : Cell::new(0), state : Cell::new((0, 0, 0, true)) };
+		con.log(HEADER.to_string())?;
+		con.log_state()?;
+		Ok(Box::new(con))
+	}
+}
+
+impl ReplayDriver {
+	///Create a driver replaying the recording <i>path</i>.
+	pub fn new<P : AsRef<Path>>(path : P) -> ReplayDriver {
+		ReplayDriver { path : path.as_ref().to_path_buf() }
+	}
+
+	///Open a connection replaying the recording <i>path</i>, presented as a connection of type <i>db_type</i>.
+	///
+	///Returns an IoError if the recording cannot be read, an <i>InvalidData</i> IoError if it is not a recording.
+	pub fn open<P : AsRef<Path>>(db_type : DbType, path : P) -> Result<Connection> {
+		Ok(Connection::from_raw(db_type, ReplayDriver::new(path).connect("")?))
+	}
+}
+
+impl Driver for ReplayDriver {
+	fn connect(&self, _target : &str) -> Result<Box<dyn RawConnection>> {
+		let text = fs::read_to_string(&self.path)?;
+		let lines : Vec<String> = text.lines().map(|l| l.to_string()).collect();
+		if lines.first().map(|l| l.as_str()) != Some(HEADER) {
+			return Err(Error::new(ErrorKind::InvalidData, format!("{} is not a rustic-replay recording", self.path.display())));
+		}
+		let con = ReplayConnection { path : self.path.clone(), lines, pos : Cell::new(1), next_id : Cell::new(0),
+									 state : Cell::new((0, 0, 0, true)) };
+		con.read_state();
+		Ok(Box::new(con))
+	}
+}
+
+impl RecordConnection {
+	///Give access to the recorded connection.
+	pub fn inner(&self) -> &dyn RawConnection {
+		&*self.inner
+	}
+
+	fn log(&self, line : String) -> Result<()> {
+		let mut out = self.out.borrow_mut();
+		out.write_all(line.as_bytes())?;
+		out.write_all(b"\n")
+	}
+
+	//Write the outcome of an operation, then pass the operation result on.
+	fn log_result<T>(&self, r : Result<T>) -> Result<T> {
+		match r {
+			Ok(v) => { self.log("ok".to_string())?; Ok(v) },
+			Err(e) => { self.log_error(&e)?; Err(e) }
+		}
+	}
+
+	fn log_error(&self, e : &Error) -> Result<()> {
+		self.log(format!("error\t{:?}\t{}", e.kind(), escape(e.to_string().as_bytes())))
+	}
+
+	//Write the changes, total changes, last rowid and autocommit state of the connection if they have changed.
+	fn log_state(&self) -> Result<()> {
+		let i = &self.inner;
+		let state = (i.changes(), i.total_changes(), i.last_insert_rowid(), i.is_autocommit());
+		if state == self.state.get() { return Ok(()); }
+		self.state.set(state);
+		self.log(format!("state\t{}\t{}\t{}\t{}", state.0, state.1, state.2, state.3 as u8))
+	}
+}
+
+impl RawConnection for RecordConnection {
+	fn prepare<'c>(&'c self, sql : &str) -> Result<Box<dyn RawStatement + 'c>> {
+		let id = self.next_id.get();
+		self.next_id.set(id + 1);
+		self.log(format!("prepare\t{}\t{}", id, escape(sql.as_bytes())))?;
+		match self.inner.prepare(sql) {
+			Ok(inner) => {
+				self.log(format!("prepared\t{}\t{}", inner.is_readonly() as u8, inner.is_explain() as u8))?;
+				let columns = columns_line(&*inner);
+				self.log(columns.clone())?;
+				Ok(Box::new(RecordStatement { p_con : self, inner, id, columns, fresh : true }))
+			},
+			Err(e) => { self.log_error(&e)?; Err(e) }
+		}
+	}
+
+	fn last_insert_rowid(&self) -> i64 {
+		self.inner.last_insert_rowid()
+	}
+
+	fn changes(&self) -> i64 {
+		self.inner.changes()
+	}
+
+	fn total_changes(&self) -> i64 {
+		self.inner.total_changes()
+	}
+
+	fn is_autocommit(&self) -> bool {
+		self.inner.is_autocommit()
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+impl Drop for RecordConnection {
+	///The drop method flushes the recording.
+	fn drop(&mut self) {
+		let _ = self.out.get_mut().flush();
+	}
+}
+
+impl<'c> RecordStatement<'c> {
+	///Give access to the recorded statement.
+	pub fn inner(&self) -> &(dyn RawStatement + 'c) {
+		&*self.inner
+	}
+
+	fn bind_with<F>(&mut self, index : i32, value : ValueRef, bind : F) -> Result<()>
+		where F : FnOnce(&mut (dyn RawStatement + 'c)) -> Result<()> {
+		self.p_con.log(format!("bind\t{}\t{}\t{}", self.id, index, encode(value, None)))?;
+		self.fresh = true;
+		let r = bind(&mut *self.inner);
+		self.p_con.log_result(r)
+	}
+
+	fn log_row(&self) -> Result<()> {
+		let mut line = String::from("row");
+		for i in 0..self.inner.column_count() {
+			let v = self.inner.column_value(i);
+			let text = match v {
+				ValueRef::Integer(_) | ValueRef::Real(_) => Some(self.inner.column_text(i)),
+				_ => None
+			};
+			line.push('\t');
+			line.push_str(&encode(v, text));
+		}
+		self.p_con.log(line)
+	}
+}
+
+impl<'c> RawStatement for RecordStatement<'c> {
+	fn reset(&mut self) -> Result<()> {
+		self.p_con.log(format!("reset\t{}", self.id))?;
+		self.fresh = true;
+		let r = self.inner.reset();
+		self.p_con.log_result(r)
+	}
+
+	fn step(&mut self) -> Result<bool> {
+		self.p_con.log(format!("step\t{}", self.id))?;
+		let r = self.inner.step();
+		if self.fresh {
+			//The columns of some results are only known once the statement is executed.
+			self.fresh = false;
+			let columns = columns_line(&*self.inner);
+			if columns != self.columns { self.p_con.log(columns.clone())?; self.columns = columns; }
+		}
+		match r {
+			Ok(true) => self.log_row()?,
+			Ok(false) => self.p_con.log("done".to_string())?,
+			Err(ref e) => self.p_con.log_error(e)?
+		}
+		self.p_con.log_state()?;
+		r
+	}
+
+	fn bind_null(&mut self, index : i32) -> Result<()> {
+		self.bind_with(index, ValueRef::Null, |s| s.bind_null(index))
+	}
+
+	fn bind_int64(&mut self, index : i32, value : i64) -> Result<()> {
+		self.bind_with(index, ValueRef::Integer(value), |s| s.bind_int64(index, value))
+	}
+
+	fn bind_double(&mut self, index : i32, value : f64) -> Result<()> {
+		self.bind_with(index, ValueRef::Real(value), |s| s.bind_double(index, value))
+	}
+
+	fn bind_text(&mut self, index : i32, value : &str) -> Result<()> {
+		self.bind_with(index, ValueRef::Text(value.as_bytes()), |s| s.bind_text(index, value))
+	}
+
+	fn bind_blob(&mut self, index : i32, value : &[u8]) -> Result<()> {
+		self.bind_with(index, ValueRef::Blob(value), |s| s.bind_blob(index, value))
+	}
+
+	unsafe fn bind_text_in_place(&mut self, index : i32, value : &str) -> Result<()> {
+		//The caller keeps the value alive for the recorded statement as well.
+		self.bind_with(index, ValueRef::Text(value.as_bytes()), |s| unsafe { s.bind_text_in_place(index, value) })
+	}
+
+	unsafe fn bind_blob_in_place(&mut self, index : i32, value : &[u8]) -> Result<()> {
+		self.bind_with(index, ValueRef::Blob(value), |s| unsafe { s.bind_blob_in_place(index, value) })
+	}
+
+	fn column_count(&self) -> i32 {
+		self.inner.column_count()
+	}
+
+	fn column_name(&self, index : i32) -> String {
+		self.inner.column_name(index)
+	}
+
+	fn column_decltype(&self, index : i32) -> Option<String> {
+		self.inner.column_decltype(index)
+	}
+
+	fn column_value(&self, index : i32) -> ValueRef<'_> {
+		self.inner.column_value(index)
+	}
+
+	fn column_int64(&self, index : i32) -> i64 {
+		self.inner.column_int64(index)
+	}
+
+	fn column_double(&self, index : i32) -> f64 {
+		self.inner.column_double(index)
+	}
+
+	fn column_text(&self, index : i32) -> &[u8] {
+		self.inner.column_text(index)
+	}
+
+	fn column_blob(&self, index : i32) -> &[u8] {
+		self.inner.column_blob(index)
+	}
+
+	fn column_is_null(&self, index : i32) -> bool {
+		self.inner.column_is_null(index)
+	}
+
+	fn column_type(&self, index : i32) -> Option<ColumnType> {
+		self.inner.column_type(index)
+	}
+
+	fn sql(&self) -> String {
+		self.inner.sql()
+	}
+
+	fn expanded_sql(&self) -> Option<String> {
+		self.inner.expanded_sql()
+	}
+
+	fn is_readonly(&self) -> bool {
+		self.inner.is_readonly()
+	}
+
+	fn is_explain(&self) -> bool {
+		self.inner.is_explain()
+	}
+
+	fn is_busy(&self) -> bool {
+		self.inner.is_busy()
+	}
+}
+
+impl ReplayConnection {
+	///Return the number of lines of the recording replayed so far.
+	pub fn position(&self) -> usize {
+		self.pos.get()
+	}
+
+	///Return true if the whole recording has been replayed.
+	pub fn is_finished(&self) -> bool {
+		self.pos.get() >= self.lines.len()
+	}
+
+	//Check that the next recorded interaction is <i>line</i>, panic otherwise.
+	fn request(&self, line : String) {
+		let n = self.pos.get();
+		match self.lines.get(n) {
+			Some(l) if *l == line => self.pos.set(n + 1),
+			Some(l) => panic!("replay of {} diverged at line {}: recorded `{}`, got `{}`", self.path.display(), n + 1, l, line),
+			None => panic!("replay of {} diverged after its last line: got `{}`", self.path.display(), line)
+		}
+	}
+
+	//Return the fields of the next line if it is a reply of kind <i>tag</i>, consuming it.
+	fn reply_if(&self, tag : &str) -> Option<Vec<&str>> {
+		let n = self.pos.get();
+		let fields : Vec<&str> = self.lines.get(n)?.split('\t').collect();
+		if fields[0] != tag { return None; }
+		self.pos.set(n + 1);
+		Some(fields)
+	}
+
+	//Return the fields of the next line, which must be a reply.
+	fn reply(&self) -> Vec<&str> {
+		let n = self.pos.get();
+		match self.lines.get(n) {
+			Some(l) => { self.pos.set(n + 1); l.split('\t').collect() },
+			None => panic!("replay of {} is truncated: a reply is missing after line {}", self.path.display(), n)
+		}
+	}
+
+	//Replay the outcome of an operation without result.
+	fn outcome(&self) -> Result<()> {
+		let fields = self.reply();
+		match fields[0] {
+			"ok" => Ok(()),
+			"error" => Err(self.error(&fields)),
+			_ => self.malformed()
+		}
+	}
+
+	fn error(&self, fields : &[&str]) -> Error {
+		if fields.len() != 3 { self.malformed() }
+		Error::new(parse_kind(fields[1]), String::from_utf8_lossy(&unescape(fields[2])).into_owned())
+	}
+
+	fn read_state(&self) {
+		if let Some(f) = self.reply_if("state") {
+			let n = |i : usize| f.get(i).and_then(|v| v.parse::<i64>().ok()).unwrap_or_else(|| self.malformed());
+			self.state.set((n(1), n(2), n(3), n(4) != 0));
+		}
+	}
+
+	fn malformed(&self) -> ! {
+		panic!("replay of {} is malformed at line {}", self.path.display(), self.pos.get())
+	}
+}
+
+impl RawConnection for ReplayConnection {
+	fn prepare<'c>(&'c self, sql : &str) -> Result<Box<dyn RawStatement + 'c>> {
+		let id = self.next_id.get();
+		self.next_id.set(id + 1);
+		self.request(format!("prepare\t{}\t{}", id, escape(sql.as_bytes())));
+		let fields = self.reply();
+		match fields[0] {
+			"prepared" if fields.len() == 3 => {
+				let (readonly, explain) = (fields[1] == "1", fields[2] == "1");
+				let mut st = ReplayStatement { p_con : self, id, sql : sql.to_string(), readonly, explain, columns : Vec::new(),
+											   row : None, text : Vec::new() };
+				match self.reply_if("columns") { Some(f) => st.set_columns(&f), None => self.malformed() }
+				Ok(Box::new(st))
+			},
+			"error" => Err(self.error(&fields)),
+			_ => self.malformed()
+		}
+	}
+
+	fn last_insert_rowid(&self) -> i64 {
+		self.state.get().2
+	}
+
+	fn changes(&self) -> i64 {
+		self.state.get().0
+	}
+
+	fn total_changes(&self) -> i64 {
+		self.state.get().1
+	}
+
+	fn is_autocommit(&self) -> bool {
+		self.state.get().3
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+impl Drop for ReplayConnection {
+	///The drop method panics if the recording has not been replayed to its end.
+	fn drop(&mut self) {
+		if !self.is_finished() && !thread::panicking() {
+			panic!("replay of {} stopped at line {}, {} recorded line(s) have not been replayed: next is `{}`",
+				   self.path.display(), self.pos.get(), self.lines.len() - self.pos.get(), self.lines[self.pos.get()]);
+		}
+	}
+}
+
+impl<'c> ReplayStatement<'c> {
+	fn bind(&mut self, index : i32, value : ValueRef) -> Result<()> {
+		self.p_con.request(format!("bind\t{}\t{}\t{}", self.id, index, encode(value, None)));
+		self.p_con.outcome()
+	}
+
+	fn set_columns(&mut self, fields : &[&str]) {
+		self.columns = fields[1..].chunks(2).map(|c| {
+			let name = String::from_utf8_lossy(&unescape(c[0])).into_owned();
+			let decl = match c.get(1) { Some(&"\\N") | None => None, Some(d) => Some(String::from_utf8_lossy(&unescape(d)).into_owned()) };
+			(name, decl)
+		}).collect();
+	}
+}
+
+impl<'c> RawStatement for ReplayStatement<'c> {
+	fn reset(&mut self) -> Result<()> {
+		self.row = None;
+		self.p_con.request(format!("reset\t{}", self.id));
+		self.p_con.outcome()
+	}
+
+	fn step(&mut self) -> Result<bool> {
+		let con = self.p_con;
+		con.request(format!("step\t{}", self.id));
+		if let Some(f) = con.reply_if("columns") { self.set_columns(&f); }
+		let fields = con.reply();
+		let r = match fields[0] {
+			"row" => {
+				let mut row = Vec::with_capacity(fields.len() - 1);
+				self.text.clear();
+				for f in &fields[1..] {
+					let (v, t) = decode(f).unwrap_or_else(|| con.malformed());
+					row.push(v);
+					self.text.push(t);
+				}
+				self.row = Some(row);
+				Ok(true)
+			},
+			"done" => { self.row = None; Ok(false) },
+			"error" => { self.row = None; Err(con.error(&fields)) },
+			_ => con.malformed()
+		};
+		con.read_state();
+		r
+	}
+
+	fn bind_null(&mut self, index : i32) -> Result<()> {
+		self.bind(index, ValueRef::Null)
+	}
+
+	fn bind_int64(&mut self, index : i32, value : i64) -> Result<()> {
+		self.bind(index, ValueRef::Integer(value))
+	}
+
+	fn bind_double(&mut self, index : i32, value : f64) -> Result<()> {
+		self.bind(index, ValueRef::Real(value))
+	}
+
+	fn bind_text(&mut self, index : i32, value : &str) -> Result<()> {
+		self.bind(index, ValueRef::Text(value.as_bytes()))
+	}
+
+	fn bind_blob(&mut self, index : i32, value : &[u8]) -> Result<()> {
+		self.bind(index, ValueRef::Blob(value))
+	}
+
+	fn column_count(&self) -> i32 {
+		self.columns.len() as i32
+	}
+
+	fn column_name(&self, index : i32) -> String {
+		if index < 0 { return String::new(); }
+		self.columns.get(index as usize).map(|c| c.0.clone()).unwrap_or_default()
+	}
+
+	fn column_decltype(&self, index : i32) -> Option<String> {
+		if index < 0 { return None; }
+		self.columns.get(index as usize).and_then(|c| c.1.clone())
+	}
+
+	fn column_value(&self, index : i32) -> ValueRef<'_> {
+		match self.row {
+			Some(ref r) if index >= 0 && (index as usize) < r.len() => r[index as usize].as_value_ref(),
+			_ => ValueRef::Null
+		}
+	}
+
+	fn column_text(&self, index : i32) -> &[u8] {
+		match self.column_value(index) {
+			ValueRef::Text(b) | ValueRef::Blob(b) => b,
+			ValueRef::Null => &[],
+			_ => self.text[index as usize].as_bytes()
+		}
+	}
+
+	fn sql(&self) -> String {
+		self.sql.clone()
+	}
+
+	fn is_readonly(&self) -> bool {
+		self.readonly
+	}
+
+	fn is_explain(&self) -> bool {
+		self.explain
+	}
+
+	fn is_busy(&self) -> bool {
+		self.row.is_some()
+	}
+}
+
+//The columns line of a statement: name and declared type of each column, \N for an unknown type.
+fn columns_line(st : &dyn RawStatement) -> String {
+	let mut line = String::from("columns");
+	for i in 0..st.column_count() {
+		line.push('\t');
+		line.push_str(&escape(st.column_name(i).as_bytes()));
+		line.push('\t');
+		match st.column_decltype(i) { Some(d) => line.push_str(&escape(d.as_bytes())), None => line.push_str("\\N") }
+	}
+	line
+}
+
+//Encode a value as n, i:<integer>, r:<real>, t:<escaped text> or b:<hexadecimal>. The text rendering of a number is
+//appended after '=' when it differs from the default rendering.
+fn encode(v : ValueRef, text : Option<&[u8]>) -> String {
+	let (mut s, default) = match v {
+		ValueRef::Null => return "n".to_string(),
+		ValueRef::Integer(i) => (format!("i:{}", i), i.to_string()),
+		ValueRef::Real(f) => (format!("r:{:?}", f), format_real(f)),
+		ValueRef::Text(t) => return format!("t:{}", escape(t)),
+		ValueRef::Blob(b) => return format!("b:{}", to_hex(b))
+	};
+	if let Some(t) = text {
+		if t != default.as_bytes() { s.push('='); s.push_str(&escape(t)); }
+	}
+	s
+}
+
+//Decode a value encoded by encode, with the text rendering of numbers.
+fn decode(f : &str) -> Option<(Value, String)> {
+	if f == "n" { return Some((Value::Null, String::new())); }
+	let (tag, body) = (f.get(..2)?, f.get(2..)?);
+	let number = |body : &str| match body.find('=') {
+		Some(p) => (body[..p].to_string(), Some(String::from_utf8_lossy(&unescape(&body[p + 1..])).into_owned())),
+		None => (body.to_string(), None)
+	};
+	match tag {
+		"i:" => { let (n, t) = number(body); let i = n.parse::<i64>().ok()?; Some((Value::Integer(i), t.unwrap_or_else(|| i.to_string()))) },
+		"r:" => { let (n, t) = number(body); let r = n.parse::<f64>().ok()?; Some((Value::Real(r), t.unwrap_or_else(|| format_real(r)))) },
+		"t:" => Some((Value::Text(String::from_utf8_lossy(&unescape(body)).into_owned()), String::new())),
+		"b:" => Some((Value::Blob(from_hex(body)?), String::new())),
+		_ => None
+	}
+}
+
+//Escape a byte string into a line field: backslash, tab, line feed and carriage return are escaped, so are the bytes
+//of invalid UTF-8 sequences, as \xHH.
+fn escape(b : &[u8]) -> String {
+	let mut s = String::with_capacity(b.len());
+	for chunk in b.utf8_chunks() {
+		for c in chunk.valid().chars() {
+			match c {
+				'\\' => s.push_str("\\\\"),
+				'\t' => s.push_str("\\t"),
+				'\n' => s.push_str("\\n"),
+				'\r' => s.push_str("\\r"),
+				c => s.push(c)
+			}
+		}
+		for x in chunk.invalid() { s.push_str(&format!("\\x{:02x}", x)); }
+	}
+	s
+}
+
+fn unescape(s : &str) -> Vec<u8> {
+	let b = s.as_bytes();
+	let mut out = Vec::with_capacity(b.len());
+	let mut i = 0;
+	while i < b.len() {
+		if b[i] == b'\\' && i + 1 < b.len() {
+			i += 1;
+			match b[i] {
+				b't' => out.push(b'\t'),
+				b'n' => out.push(b'\n'),
+				b'r' => out.push(b'\r'),
+				b'x' if i + 2 < b.len() => {
+					match s.get(i + 1..i + 3).and_then(from_hex) { Some(x) => out.extend(x), None => out.extend_from_slice(b"\\x") }
+					i += 2;
+				},
+				c => out.push(c)
+			}
+		} else {
+			out.push(b[i]);
+		}
+		i += 1;
+	}
+	out
+}
+
+fn parse_kind(name : &str) -> ErrorKind {
+	match name {
+		"NotFound" => ErrorKind::NotFound,
+		"PermissionDenied" => ErrorKind::PermissionDenied,
+		"ConnectionRefused" => ErrorKind::ConnectionRefused,
+		"ConnectionReset" => ErrorKind::ConnectionReset,
+		"ConnectionAborted" => ErrorKind::ConnectionAborted,
+		"NotConnected" => ErrorKind::NotConnected,
+		"BrokenPipe" => ErrorKind::BrokenPipe,
+		"AlreadyExists" => ErrorKind::AlreadyExists,
+		"WouldBlock" => ErrorKind::WouldBlock,
+		"InvalidInput" => ErrorKind::InvalidInput,
+		"InvalidData" => ErrorKind::InvalidData,
+		"TimedOut" => ErrorKind::TimedOut,
+		"WriteZero" => ErrorKind::WriteZero,
+		"Interrupted" => ErrorKind::Interrupted,
+		"Unsupported" => ErrorKind::Unsupported,
+		"UnexpectedEof" => ErrorKind::UnexpectedEof,
+		"OutOfMemory" => ErrorKind::OutOfMemory,
+		_ => ErrorKind::Other
+	}
+}
@@ -0,0 +1,321 @@
+use std::borrow::Cow;
+use std::io::{Error, ErrorKind, Result};
+use std::str::from_utf8;
+use std::time::Duration;
+use sql::connection::Rows;
+use sql::value::{OwnedRow, ValueRef};
+use sql::types::{Date, Decimal, DecimalFormat, TimeUnit, Timestamp, Uuid};
+use sql::types::{duration_from_value, i128_from_value, u64_from_value};
+
+///Describes a column of a ResultSet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+	name : String,
+	decl_type : Option<String>
+}
+
+impl Column {
+	///Return the name of the column.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	///Return the declared type of the column, None if the column is an expression or a subquery.
+	pub fn decl_type(&self) -> Option<&str> {
+		self.decl_type.as_ref().map(|s| s.as_ref())
+	}
+}
+
+//A cell is 16 bytes: text and blob values are stored as (offset, length) in the shared data buffer
+//instead of one allocation per value.
+#[derive(Clone, Copy, Debug)]
+enum Cell {
+	Null,
+	Integer(i64),
+	Real(f64),
+	Text(u32, u32),
+	Blob(u32, u32)
+}
+
+///ResultSet holds the whole result of a query, detached from the Statement that produced it.
+///
+///Rows are stored contiguously with all text and blob values packed in a single buffer, so large results stay compact.
+///A ResultSet can be sorted, paged through with random access, or sent to another thread.
+#[derive(Clone, Debug)]
+pub struct ResultSet {
+	columns : Vec<Column>,
+	cells : Vec<Cell>,
+	data : Vec<u8>
+}
+
+///ResultRow gives access to the columns of a row of a ResultSet.
+///
+///Getters follow the conventions of the Row getters: same names, same conversions, same results for NULL. As the
+///ResultSet keeps numbers as numbers, get_str and get_bytes return a Cow where Row returns a reference: borrowed for
+///text and blobs, a new String or Vec for a number formatted as text.
+#[derive(Clone, Copy, Debug)]
+pub struct ResultRow<'r> {
+	set : &'r ResultSet,
+	cells : &'r [Cell]
+}
+
+///Iterator over the rows of a ResultSet.
+pub struct ResultSetIter<'r> {
+	set : &'r ResultSet,
+	next : usize,
+	end : usize
+}
+
+impl ResultSet {
+	//Read all the remaining rows of a query result.
+	pub(crate) fn read(mut rows : Rows) -> Result<ResultSet> {
+		let n = rows.column_count();
+		let columns = (0..n).map(|i| Column { name : rows.column_name(i), decl_type : rows.column_decltype(i) }).collect();
+		let mut set = ResultSet { columns, cells : Vec::new(), data : Vec::new() };
+		while let Some(row) = rows.next() {
+			let row = row?;
+			for i in 0..n {
+				let cell = match row.get_value_ref(i) {
+					ValueRef::Null => Cell::Null,
+					ValueRef::Integer(v) => Cell::Integer(v),
+					ValueRef::Real(v) => Cell::Real(v),
+					ValueRef::Text(t) => { let (o, l) = set.push_data(t)?; Cell::Text(o, l) }
+					ValueRef::Blob(b) => { let (o, l) = set.push_data(b)?; Cell::Blob(o, l) }
+				};
+				set.cells.push(cell);
+			}
+		}
+		set.cells.shrink_to_fit();
+		set.data.shrink_to_fit();
+		Ok(set)
+	}
+
+	fn push_data(&mut self, bytes : &[u8]) -> Result<(u32, u32)> {
+		let offset = self.data.len();
+		if offset + bytes.len() > u32::MAX as usize {
+			return Err(Error::other("result set text and blob data exceeds 4 GiB"));
+		}
+		self.data.extend_from_slice(bytes);
+		Ok((offset as u32, bytes.len() as u32))
+	}
+
+	///Return the columns of the result.
+	pub fn columns(&self) -> &[Column] {
+		&self.columns
+	}
+
+	///Return the number of columns in the result.
+	pub fn column_count(&self) -> i32 {
+		self.columns.len() as i32
+	}
+
+	///Return the index of the first column named <i>name</i>, compared case-insensitively. Returns None if not found.
+	pub fn column_index(&self, name : &str) -> Option<i32> {
+		self.columns.iter().position(|c| c.name.eq_ignore_ascii_case(name)).map(|i| i as i32)
+	}
+
+	///Return the number of rows in the result.
+	pub fn row_count(&self) -> usize {
+		if self.columns.is_empty() { 0 } else { self.cells.len() / self.columns.len() }
+	}
+
+	///Return true if the result has no row.
+	pub fn is_empty(&self) -> bool {
+		self.row_count() == 0
+	}
+
+	///Return the row with index <i>row_index</i>, the first row is 0. Returns None if out of range.
+	pub fn row(&self, row_index : usize) -> Option<ResultRow<'_>> {
+		if row_index >= self.row_count() { return None; }
+		let n = self.columns.len();
+		Some(ResultRow { set : self, cells : &self.cells[row_index * n..(row_index + 1) * n] })
+	}
+
+	///Return an iterator over the rows of the result.
+	pub fn iter(&self) -> ResultSetIter<'_> {
+		ResultSetIter { set : self, next : 0, end : self.row_count() }
+	}
+
+	///Sort the rows of the result with a comparator function.
+	pub fn sort_by<F>(&mut self, mut compare : F) where F : FnMut(&ResultRow, &ResultRow) -> ::std::cmp::Ordering {
+		let mut order : Vec<usize> = (0..self.row_count()).collect();
+		order.sort_by(|&a, &b| compare(&self.row(a).unwrap(), &self.row(b).unwrap()));
+		let n = self.columns.len();
+		let mut cells = Vec::with_capacity(self.cells.len());
+		for i in order { cells.extend_from_slice(&self.cells[i * n..(i + 1) * n]); }
+		self.cells = cells;
+	}
+}
+
+impl<'r> IntoIterator for &'r ResultSet {
+	type Item = ResultRow<'r>;
+	type IntoIter = ResultSetIter<'r>;
+	fn into_iter(self) -> ResultSetIter<'r> {
+		self.iter()
+	}
+}
+
+impl<'r> Iterator for ResultSetIter<'r> {
+	type Item = ResultRow<'r>;
+	fn next(&mut self) -> Option<ResultRow<'r>> {
+		if self.next >= self.end { return None; }
+		self.next += 1;
+		self.set.row(self.next - 1)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.end - self.next, Some(self.end - self.next))
+	}
+}
+
+impl<'r> DoubleEndedIterator for ResultSetIter<'r> {
+	fn next_back(&mut self) -> Option<ResultRow<'r>> {
+		if self.next >= self.end { return None; }
+		self.end -= 1;
+		self.set.row(self.end)
+	}
+}
+
+impl<'r> ExactSizeIterator for ResultSetIter<'r> {}
+
+impl<'r> ResultRow<'r> {
+	///Return the number of columns in the row.
+	pub fn column_count(&self) -> i32 {
+		self.cells.len() as i32
+	}
+
+	///Retrieve the column value with index <i>column_index</i>, tagged with its storage class. The first column is 0.
+	///Returns a NULL value if the index is out of range.
+	pub fn get_value_ref(&self, column_index : i32) -> ValueRef<'r> {
+		if column_index < 0 { return ValueRef::Null; }
+		match self.cells.get(column_index as usize) {
+			Some(&Cell::Integer(v)) => ValueRef::Integer(v),
+			Some(&Cell::Real(v)) => ValueRef::Real(v),
+			Some(&Cell::Text(o, l)) => ValueRef::Text(&self.set.data[o as usize..(o + l) as usize]),
+			Some(&Cell::Blob(o, l)) => ValueRef::Blob(&self.set.data[o as usize..(o + l) as usize]),
+			Some(&Cell::Null) | None => ValueRef::Null
+		}
+	}
+
+	///Retrieve the column value as int with index <i>column_index</i>, the first column is 0.
+	pub fn get_int(&self, column_index : i32) -> i32 {
+		self.get_value_ref(column_index).as_long() as i32
+	}
+
+	///Retrieve the column value as i64 with index <i>column_index</i>, the first column is 0.
+	pub fn get_long(&self, column_index : i32) -> i64 {
+		self.get_value_ref(column_index).as_long()
+	}
+
+	///Retrieve the column value as float with index <i>column_index</i>, the first column is 0.
+	pub fn get_float(&self, column_index : i32) -> f32 {
+		self.get_value_ref(column_index).as_double() as f32
+	}
+
+	///Retrieve the column value as double with index <i>column_index</i>, the first column is 0.
+	pub fn get_double(&self, column_index : i32) -> f64 {
+		self.get_value_ref(column_index).as_double()
+	}
+
+	///Retrieve the column value as String with index <i>column_index</i>, the first column is 0.
+	///If column value is NULL, returns "NULL"
+	pub fn get_string(&self, column_index : i32) -> String {
+		if self.is_null(column_index) { return "NULL".to_string(); }
+		self.get_value_ref(column_index).as_text().into_owned()
+	}
+
+	///Retrieve the column value as an array of bytes with index <i>column_index</i>, the first column is 0.
+	///If column value is NULL, returns ['N', 'U', 'L', 'L']
+	pub fn get_blob(&self, column_index : i32) -> Vec<u8> {
+		if self.is_null(column_index) { return vec![78, 85, 76, 76]; }
+		self.get_value_ref(column_index).as_bytes().into_owned()
+	}
+
+	///Return true if the column value with index <i>column_index</i> is NULL, the first column is 0.
+	pub fn is_null(&self, column_index : i32) -> bool {
+		self.get_value_ref(column_index).is_null()
+	}
+
+	///Borrow the column value as text with index <i>column_index</i>, the first column is 0.
+	///Numbers are formatted as the database formats them, in a new String: unlike Row::get_str, which returns a &str,
+	///the text is a Cow. If column value is NULL, returns "".
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not valid UTF-8.
+	pub fn get_str(&self, column_index : i32) -> Result<Cow<'r, str>> {
+		match self.get_value_ref(column_index) {
+			ValueRef::Text(t) | ValueRef::Blob(t) => from_utf8(t).map(Cow::Borrowed).map_err(|e| Error::new(ErrorKind::InvalidData, e)),
+			v => Ok(v.as_text())
+		}
+	}
+
+	///Borrow the column value as text with index <i>column_index</i>, the first column is 0.
+	///Numbers are formatted and invalid UTF-8 sequences are replaced by U+FFFD. If column value is NULL, returns "".
+	pub fn get_str_lossy(&self, column_index : i32) -> Cow<'r, str> {
+		self.get_value_ref(column_index).as_text()
+	}
+
+	///Borrow the column value as bytes with index <i>column_index</i>, the first column is 0.
+	///Numbers are formatted as text, in a new Vec: unlike Row::get_bytes, which returns a &[u8], the bytes are a Cow. If
+	///column value is NULL, returns an empty slice.
+	pub fn get_bytes(&self, column_index : i32) -> Cow<'r, [u8]> {
+		self.get_value_ref(column_index).as_bytes()
+	}
+
+	///Retrieve the column value as u64 with index <i>column_index</i>, the first column is 0.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not an integer, or text holding one, within the u64 range.
+	pub fn get_u64(&self, column_index : i32) -> Result<u64> {
+		u64_from_value(self.get_value_ref(column_index))
+	}
+
+	///Retrieve the column value as i128 with index <i>column_index</i>, the first column is 0.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not an integer, or text holding one, within the i128 range.
+	pub fn get_i128(&self, column_index : i32) -> Result<i128> {
+		i128_from_value(self.get_value_ref(column_index))
+	}
+
+	///Retrieve the column value as a UTC instant with index <i>column_index</i>, the first column is 0.
+	///Text is read as ISO-8601, integers as Unix seconds and reals as julian days.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not an instant.
+	pub fn get_timestamp(&self, column_index : i32) -> Result<Timestamp> {
+		Timestamp::from_value(self.get_value_ref(column_index))
+	}
+
+	///Retrieve the column value as a date with index <i>column_index</i>, the first column is 0.
+	///The value is read as by get_timestamp, and its time dropped.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not a date.
+	pub fn get_date(&self, column_index : i32) -> Result<Date> {
+		Date::from_value(self.get_value_ref(column_index))
+	}
+
+	///Retrieve the column value as a duration with index <i>column_index</i>, the first column is 0.
+	///The value is a number of <i>unit</i>.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not a positive number.
+	pub fn get_duration(&self, column_index : i32, unit : TimeUnit) -> Result<Duration> {
+		duration_from_value(self.get_value_ref(column_index), unit)
+	}
+
+	///Retrieve the column value as a UUID with index <i>column_index</i>, the first column is 0.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is neither a 16-byte blob nor the text of a UUID.
+	pub fn get_uuid(&self, column_index : i32) -> Result<Uuid> {
+		Uuid::from_value(self.get_value_ref(column_index))
+	}
+
+	///Retrieve the column value as a decimal with index <i>column_index</i>, the first column is 0.
+	///Integers are read with the scale of <i>format</i>, text and reals as written.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not a decimal.
+	pub fn get_decimal(&self, column_index : i32, format : DecimalFormat) -> Result<Decimal> {
+		Decimal::from_value(self.get_value_ref(column_index), format)
+	}
+
+	///Copy the row into an OwnedRow.
+	pub fn to_owned(&self) -> OwnedRow {
+		OwnedRow::new((0..self.column_count()).map(|i| self.get_value_ref(i).to_value()).collect())
+	}
+}
@@ -0,0 +1,200 @@
+//Lexical helpers for SQL texts: splitting a script into statements and comparing statements regardless of layout.
+
+//Return the tokens of an SQL text as byte ranges: words, numbers, quoted strings and identifiers, and single
+//punctuation characters. Whitespace and comments are skipped, an unterminated quote or comment runs to the end.
+pub fn tokens(sql : &str) -> Vec<(usize, usize)> {
+	let b = sql.as_bytes();
+	let mut tokens = Vec::new();
+	let mut i = 0;
+	while i < b.len() {
+		let c = b[i];
+		let start = i;
+		if c.is_ascii_whitespace() { i += 1; continue; }
+		if c == b'-' && b.get(i + 1) == Some(&b'-') {
+			while i < b.len() && b[i] != b'\n' { i += 1; }
+			continue;
+		}
+		if c == b'/' && b.get(i + 1) == Some(&b'*') {
+			i += 2;
+			while i < b.len() && !(b[i] == b'*' && b.get(i + 1) == Some(&b'/')) { i += 1; }
+			i = (i + 2).min(b.len());
+			continue;
+		}
+		match c {
+			b'\'' | b'"' | b'`' | b'[' => {
+				let close = if c == b'[' { b']' } else { c };
+				i += 1;
+				loop {
+					if i >= b.len() { break; }
+					if b[i] == close {
+						//A doubled quote stands for the quote itself.
+						if close != b']' && b.get(i + 1) == Some(&close) { i += 2; continue; }
+						i += 1;
+						break;
+					}
+					i += 1;
+				}
+			},
+			c if is_word(c) => { while i < b.len() && is_word(b[i]) { i += 1; } },
+			_ => {
+				i += 1;
+				while i < b.len() && !sql.is_char_boundary(i) { i += 1; }
+			}
+		}
+		tokens.push((start, i));
+	}
+	tokens
+}
+
+fn is_word(c : u8) -> bool {
+	c.is_ascii_alphanumeric() || c == b'_' || c == b'$' || c >= 0x80
+}
+
+//Split a script into its statements, without their terminating ';'. Comments between statements are dropped and
+//the ';' inside the BEGIN ... END body of a CREATE TRIGGER do not end the statement.
+pub fn split_statements(sql : &str) -> Vec<String> {
+	let mut statements = Vec::new();
+	let toks = tokens(sql);
+	let mut first = 0;
+	let mut depth = 0;
+	let mut trigger = false;
+	for (k, &(s, e)) in toks.iter().enumerate() {
+		let word = sql[s..e].to_ascii_uppercase();
+		if k == first {
+			let head : Vec<String> = toks[k..].iter().take(3).map(|&(s, e)| sql[s..e].to_ascii_uppercase()).collect();
+			trigger = head.first().map(|w| w.as_str()) == Some("CREATE")
+					  && (head.get(1).map(|w| w.as_str()) == Some("TRIGGER") || head.get(2).map(|w| w.as_str()) == Some("TRIGGER"));
+			depth = 0;
+		}
+		if trigger {
+			match &*word {
+				"BEGIN" | "CASE" => depth += 1,
+				"END" => depth -= 1,
+				_ => ()
+			}
+		}
+		if word == ";" && (!trigger || depth <= 0) {
+			if k > first { statements.push(sql[toks[first].0..toks[k - 1].1].to_string()); }
+			first = k + 1;
+		}
+	}
+	if first < toks.len() { statements.push(sql[toks[first].0..toks[toks.len() - 1].1].to_string()); }
+	statements
+}
+
+//Return a canonical form of an SQL text, to compare statements regardless of whitespace, comments and the case of
+//keywords: tokens separated by one space, unquoted words in lower case.
+pub fn normalize(sql : &str) -> String {
+	let mut out = String::with_capacity(sql.len());
+	for (s, e) in tokens(sql) {
+		if !out.is_empty() { out.push(' '); }
+		let t = &sql[s..e];
+		if is_word(t.as_bytes()[0]) { out.push_str(&t.to_lowercase()); } else { out.push_str(t); }
+	}
+	out
+}
+
+//Split the body of a CREATE TABLE statement into its column definitions and table constraints, as texts.
+//Returns the definitions and the byte offsets of the opening and closing parentheses, None if the statement has no body.
+pub fn table_definitions(sql : &str) -> Option<(Vec<&str>, usize, usize)> {
+	let toks = tokens(sql);
+	let open = toks.iter().position(|&(s, _)| sql.as_bytes()[s] == b'(')?;
+	let mut defs = Vec::new();
+	let mut depth = 0;
+	let mut start = open + 1;
+	for k in open + 1..toks.len() {
+		match &sql[toks[k].0..toks[k].1] {
+			"(" => depth += 1,
+			")" if depth > 0 => depth -= 1,
+			"," | ")" if depth == 0 => {
+				if k > start { defs.push(&sql[toks[start].0..toks[k - 1].1]); }
+				if &sql[toks[k].0..toks[k].1] == ")" { return Some((defs, toks[open].0, toks[k].0)); }
+				start = k + 1;
+			},
+			_ => ()
+		}
+	}
+	None
+}
+
+//Return the first word of an SQL text in upper case, unquoted, empty if none.
+pub fn first_word(sql : &str) -> String {
+	match tokens(sql).first() {
+		Some(&(s, e)) => unquote(&sql[s..e]).to_uppercase(),
+		None => String::new()
+	}
+}
+
+//Remove the quotes around an identifier.
+pub fn unquote(t : &str) -> String {
+	let b = t.as_bytes();
+	if b.len() >= 2 {
+		let (o, c) = (b[0], b[b.len() - 1]);
+		if (o == b'"' || o == b'`' || o == b'\'') && c == o {
+			let q = &t[..1];
+			return t[1..t.len() - 1].replace(&format!("{}{}", q, q), q);
+		}
+		if o == b'[' && c == b']' { return t[1..t.len() - 1].to_string(); }
+	}
+	t.to_string()
+}
+
+//Return the names of the parameters of an SQL text, numbered the way SQLite numbers them: ? takes the next number,
+//?NNN and $NNN their own, and each distinct :name, @name or $name the next number. The name of the parameter numbered
+//n is at index n - 1, with its prefix; ? parameters and unused numbers have an empty name. Literals, quoted
+//identifiers and comments are skipped.
+pub fn parameters(sql : &str) -> Vec<String> {
+	scan_parameters(sql).0
+}
+
+//Return the placeholders of an SQL text in the order they are written, as their byte range and the number of their
+//parameter, numbered as by parameters.
+pub fn placeholders(sql : &str) -> Vec<(usize, usize, usize)> {
+	scan_parameters(sql).1
+}
+
+fn scan_parameters(sql : &str) -> (Vec<String>, Vec<(usize, usize, usize)>) {
+	let b = sql.as_bytes();
+	let mut names : Vec<String> = Vec::new();
+	let mut marks = Vec::new();
+	let mut i = 0;
+	while i < b.len() {
+		match b[i] {
+			q @ (b'\'' | b'"' | b'`') => {
+				i += 1;
+				while i < b.len() && b[i] != q { i += 1; }
+			},
+			b'[' => { while i < b.len() && b[i] != b']' { i += 1; } },
+			b'-' if b.get(i + 1) == Some(&b'-') => { while i < b.len() && b[i] != b'\n' { i += 1; } },
+			b'/' if b.get(i + 1) == Some(&b'*') => {
+				i += 2;
+				while i + 1 < b.len() && !(b[i] == b'*' && b[i + 1] == b'/') { i += 1; }
+				i += 1;
+			},
+			b'?' | b'$' | b':' | b'@' => {
+				let start = i + 1;
+				let mut end = start;
+				while end < b.len() && (b[end].is_ascii_alphanumeric() || b[end] == b'_') { end += 1; }
+				let name = &sql[i..end];
+				if end > start && b[start..end].iter().all(|c| c.is_ascii_digit()) && (b[i] == b'?' || b[i] == b'$') {
+					let n = sql[start..end].parse().unwrap_or(0);
+					if names.len() < n { names.resize(n, String::new()); }
+					if n > 0 && names[n - 1].is_empty() { names[n - 1] = name.to_string(); }
+					if n > 0 { marks.push((i, end, n)); }
+				} else if b[i] == b'?' {
+					names.push(String::new());
+					marks.push((i, end, names.len()));
+				} else if !(end == start || (b[i] == b':' && i > 0 && b[i - 1] == b':')) {
+					match names.iter().position(|n| n == name) {
+						Some(n) => marks.push((i, end, n + 1)),
+						None => { names.push(name.to_string()); marks.push((i, end, names.len())); }
+					}
+				}
+				i = end.max(start) - 1;
+			},
+			_ => ()
+		}
+		i += 1;
+	}
+	(names, marks)
+}
@@ -0,0 +1,556 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::marker::PhantomData;
+use std::str::from_utf8;
+use serde::de::{self, Deserialize, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::de::value::{SeqDeserializer, StringDeserializer};
+use serde::ser::{self, Impossible, Serialize};
+use sql::{Connection, DbType, Statement};
+use sql::builder::{ident, placeholder};
+use sql::connection::{Row, Rows};
+use sql::value::{Value, ValueRef};
+
+///Iterator over the rows of a query deserialized into values of type T, returned by <i>Statement::query_as</i>.
+pub struct QueryAs<'a: 'b, 'b, T> {
+	rows : Rows<'a, 'b>,
+	marker : PhantomData<T>
+}
+
+impl<'a, 'b, T> QueryAs<'a, 'b, T> {
+	pub fn new(rows : Rows<'a, 'b>) -> QueryAs<'a, 'b, T> {
+		QueryAs { rows, marker : PhantomData }
+	}
+}
+
+impl<'a, 'b, T : DeserializeOwned> Iterator for QueryAs<'a, 'b, T> {
+	type Item = io::Result<T>;
+
+	fn next(&mut self) -> Option<io::Result<T>> {
+		self.rows.next().map(|r| r.and_then(|row| from_row(&row)))
+	}
+}
+
+//Error of a conversion between serde data and rows or parameters, returned to the caller as an InvalidData IoError.
+#[derive(Debug)]
+struct Error(String);
+
+impl fmt::Display for Error {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl error::Error for Error {}
+
+impl de::Error for Error {
+	fn custom<T : fmt::Display>(msg : T) -> Error {
+		Error(msg.to_string())
+	}
+}
+
+impl ser::Error for Error {
+	fn custom<T : fmt::Display>(msg : T) -> Error {
+		Error(msg.to_string())
+	}
+}
+
+impl From<Error> for io::Error {
+	fn from(e : Error) -> io::Error {
+		io::Error::new(io::ErrorKind::InvalidData, e.0)
+	}
+}
+
+//Deserialize a row: a struct or a map by column name, a tuple or a sequence by column position, any other type from
+//the only column of the row.
+pub fn from_row<'de, 'r, T : Deserialize<'de>>(row : &'de Row<'r>) -> io::Result<T> {
+	Ok(T::deserialize(RowDeserializer { row })?)
+}
+
+struct RowDeserializer<'de, 'r : 'de> {
+	row : &'de Row<'r>
+}
+
+impl<'de, 'r> RowDeserializer<'de, 'r> {
+	fn single(&self) -> Result<ValueDeserializer<'de>, Error> {
+		match self.row.column_count() {
+			1 => Ok(ValueDeserializer(self.row.get_value_ref(0))),
+			n => Err(Error(format!("expected a row of one column, found {} columns", n)))
+		}
+	}
+}
+
+macro_rules! single_column {
+	($($method:ident)*) => {
+		$(fn $method<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+			self.single()?.$method(visitor)
+		})*
+	}
+}
+
+impl<'de, 'r> de::Deserializer<'de> for RowDeserializer<'de, 'r> {
+	type Error = Error;
+
+	fn deserialize_any<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		visitor.visit_map(Columns { row : self.row, index : 0 })
+	}
+
+	fn deserialize_seq<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		visitor.visit_seq(Columns { row : self.row, index : 0 })
+	}
+
+	fn deserialize_tuple<V : Visitor<'de>>(self, _len : usize, visitor : V) -> Result<V::Value, Error> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_tuple_struct<V : Visitor<'de>>(self, _name : &'static str, _len : usize, visitor : V) -> Result<V::Value, Error> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_newtype_struct<V : Visitor<'de>>(self, _name : &'static str, visitor : V) -> Result<V::Value, Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_enum<V : Visitor<'de>>(self, name : &'static str, variants : &'static [&'static str], visitor : V)
+										  -> Result<V::Value, Error> {
+		self.single()?.deserialize_enum(name, variants, visitor)
+	}
+
+	single_column! {
+		deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128 deserialize_u8
+		deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char
+		deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf deserialize_option deserialize_unit
+	}
+
+	serde::forward_to_deserialize_any! { unit_struct map struct identifier ignored_any }
+}
+
+//The columns of a row, read as a map by name or as a sequence.
+struct Columns<'de, 'r : 'de> {
+	row : &'de Row<'r>,
+	index : i32
+}
+
+impl<'de, 'r> Columns<'de, 'r> {
+	fn value<T : DeserializeSeed<'de>>(&mut self, seed : T) -> Result<T::Value, Error> {
+		let index = self.index;
+		self.index += 1;
+		seed.deserialize(ValueDeserializer(self.row.get_value_ref(index)))
+			.map_err(|e| Error(format!("column {}: {}", self.row.column_name(index), e)))
+	}
+}
+
+impl<'de, 'r> MapAccess<'de> for Columns<'de, 'r> {
+	type Error = Error;
+
+	fn next_key_seed<K : DeserializeSeed<'de>>(&mut self, seed : K) -> Result<Option<K::Value>, Error> {
+		if self.index >= self.row.column_count() { return Ok(None); }
+		let key : StringDeserializer<Error> = self.row.column_name(self.index).into_deserializer();
+		seed.deserialize(key).map(Some)
+	}
+
+	fn next_value_seed<T : DeserializeSeed<'de>>(&mut self, seed : T) -> Result<T::Value, Error> {
+		self.value(seed)
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some((self.row.column_count() - self.index) as usize)
+	}
+}
+
+impl<'de, 'r> SeqAccess<'de> for Columns<'de, 'r> {
+	type Error = Error;
+
+	fn next_element_seed<T : DeserializeSeed<'de>>(&mut self, seed : T) -> Result<Option<T::Value>, Error> {
+		if self.index >= self.row.column_count() { return Ok(None); }
+		self.value(seed).map(Some)
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some((self.row.column_count() - self.index) as usize)
+	}
+}
+
+//Deserializer of a column value. NULL is read as None or (), integers as booleans or numbers, reals as numbers, text
+//as strings or unit variants, blobs as bytes or sequences of u8.
+struct ValueDeserializer<'de>(ValueRef<'de>);
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de> {
+	type Error = Error;
+
+	fn deserialize_any<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		match self.0 {
+			ValueRef::Null => visitor.visit_unit(),
+			ValueRef::Integer(i) => visitor.visit_i64(i),
+			ValueRef::Real(f) => visitor.visit_f64(f),
+			ValueRef::Text(t) => match from_utf8(t) {
+				Ok(s) => visitor.visit_borrowed_str(s),
+				Err(_) => visitor.visit_borrowed_bytes(t)
+			},
+			ValueRef::Blob(b) => visitor.visit_borrowed_bytes(b)
+		}
+	}
+
+	fn deserialize_option<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		match self.0 {
+			ValueRef::Null => visitor.visit_none(),
+			_ => visitor.visit_some(self)
+		}
+	}
+
+	fn deserialize_bool<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		match self.0 {
+			ValueRef::Integer(i) => visitor.visit_bool(i != 0),
+			_ => self.deserialize_any(visitor)
+		}
+	}
+
+	fn deserialize_f32<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		self.deserialize_f64(visitor)
+	}
+
+	fn deserialize_f64<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		match self.0 {
+			ValueRef::Integer(i) => visitor.visit_f64(i as f64),
+			_ => self.deserialize_any(visitor)
+		}
+	}
+
+	fn deserialize_str<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		match self.0 {
+			ValueRef::Integer(_) | ValueRef::Real(_) => visitor.visit_string(self.0.as_text().into_owned()),
+			_ => self.deserialize_any(visitor)
+		}
+	}
+
+	fn deserialize_string<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		self.deserialize_str(visitor)
+	}
+
+	fn deserialize_bytes<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		match self.0 {
+			ValueRef::Text(t) => visitor.visit_borrowed_bytes(t),
+			_ => self.deserialize_any(visitor)
+		}
+	}
+
+	fn deserialize_byte_buf<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		self.deserialize_bytes(visitor)
+	}
+
+	fn deserialize_seq<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		match self.0 {
+			ValueRef::Blob(b) => visitor.visit_seq(SeqDeserializer::<_, Error>::new(b.iter().cloned())),
+			_ => self.deserialize_any(visitor)
+		}
+	}
+
+	fn deserialize_unit<V : Visitor<'de>>(self, visitor : V) -> Result<V::Value, Error> {
+		match self.0 {
+			ValueRef::Null => visitor.visit_unit(),
+			_ => self.deserialize_any(visitor)
+		}
+	}
+
+	fn deserialize_newtype_struct<V : Visitor<'de>>(self, _name : &'static str, visitor : V) -> Result<V::Value, Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_enum<V : Visitor<'de>>(self, _name : &'static str, _variants : &'static [&'static str], visitor : V)
+										  -> Result<V::Value, Error> {
+		match self.0 {
+			ValueRef::Text(t) => match from_utf8(t) {
+				Ok(s) => visitor.visit_enum(IntoDeserializer::<Error>::into_deserializer(s)),
+				Err(_) => self.deserialize_any(visitor)
+			},
+			_ => self.deserialize_any(visitor)
+		}
+	}
+
+	serde::forward_to_deserialize_any! {
+		i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 char unit_struct tuple tuple_struct map struct identifier ignored_any
+	}
+}
+
+//The fields of a serialized struct or map, in order, and the name of the struct.
+struct Fields {
+	name : Option<&'static str>,
+	values : Vec<(String, Value)>
+}
+
+//Serialize a struct or a map into its fields.
+fn to_fields<T : Serialize + ?Sized>(value : &T) -> io::Result<Fields> {
+	Ok(value.serialize(FieldsSerializer)?)
+}
+
+//Bind the fields of a struct or a map to the parameters of the statement named after them, with the prefix ':', '@' or
+//'$'. Fields without parameter are ignored.
+pub fn bind<T : Serialize + ?Sized>(st : &mut Statement, value : &T) -> Option<io::Error> {
+	let fields = match to_fields(value) { Ok(f) => f, Err(e) => return Some(e) };
+	for (name, v) in &fields.values {
+		let index = [":", "@", "$"].iter().filter_map(|p| st.parameter_index(&format!("{}{}", p, name))).next();
+		if let Some(e) = index.and_then(|i| st.set_value(i, v)) { return Some(e); }
+	}
+	None
+}
+
+//Insert the fields of a struct or a map as a row of a table, by default the table named after the struct.
+//Returns the rowid of the row, None for PostgreSQL which has none.
+pub fn insert<T : Serialize + ?Sized>(con : &Connection, table : Option<&str>, entity : &T) -> io::Result<Option<i64>> {
+	let fields = to_fields(entity)?;
+	let table = match table.or(fields.name) {
+		Some(t) => t,
+		None => return Err(io::Error::new(io::ErrorKind::InvalidInput, "no table to insert a map into"))
+	};
+	let db_type = con.db_type();
+	let sql = if fields.values.is_empty() {
+		format!("INSERT INTO {} DEFAULT VALUES", ident(db_type, table))
+	} else {
+		let columns : Vec<String> = fields.values.iter().map(|f| ident(db_type, &f.0)).collect();
+		let params : Vec<String> = (1..=columns.len()).map(|i| placeholder(db_type, i)).collect();
+		format!("INSERT INTO {}({}) VALUES({})", ident(db_type, table), columns.join(","), params.join(","))
+	};
+	let mut st = con.prepare_statement(&sql)?;
+	for (i, f) in fields.values.iter().enumerate() {
+		if let Some(e) = st.set_value(i as i32 + 1, &f.1) { return Err(e); }
+	}
+	st.execute_update()?;
+	Ok(match db_type { DbType::PostgreSQL => None, _ => Some(con.last_insert_rowid()) })
+}
+
+fn not_fields() -> Error {
+	Error("only a struct or a map can be serialized into fields".to_string())
+}
+
+//Serializer of a struct or a map into its fields.
+struct FieldsSerializer;
+
+//Builds the fields of a struct or a map.
+struct FieldsBuilder {
+	fields : Fields,
+	key : Option<String>
+}
+
+impl ser::Serializer for FieldsSerializer {
+	type Ok = Fields;
+	type Error = Error;
+	type SerializeSeq = Impossible<Fields, Error>;
+	type SerializeTuple = Impossible<Fields, Error>;
+	type SerializeTupleStruct = Impossible<Fields, Error>;
+	type SerializeTupleVariant = Impossible<Fields, Error>;
+	type SerializeMap = FieldsBuilder;
+	type SerializeStruct = FieldsBuilder;
+	type SerializeStructVariant = Impossible<Fields, Error>;
+
+	fn serialize_bool(self, _v : bool) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_i8(self, _v : i8) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_i16(self, _v : i16) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_i32(self, _v : i32) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_i64(self, _v : i64) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_u8(self, _v : u8) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_u16(self, _v : u16) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_u32(self, _v : u32) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_u64(self, _v : u64) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_f32(self, _v : f32) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_f64(self, _v : f64) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_char(self, _v : char) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_str(self, _v : &str) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_bytes(self, _v : &[u8]) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_none(self) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_unit(self) -> Result<Fields, Error> { Err(not_fields()) }
+	fn serialize_unit_struct(self, _name : &'static str) -> Result<Fields, Error> { Err(not_fields()) }
+
+	fn serialize_some<T : Serialize + ?Sized>(self, value : &T) -> Result<Fields, Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit_variant(self, _name : &'static str, _index : u32, _variant : &'static str) -> Result<Fields, Error> {
+		Err(not_fields())
+	}
+
+	fn serialize_newtype_struct<T : Serialize + ?Sized>(self, _name : &'static str, value : &T) -> Result<Fields, Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T : Serialize + ?Sized>(self, _name : &'static str, _index : u32, _variant : &'static str, _value : &T)
+														 -> Result<Fields, Error> {
+		Err(not_fields())
+	}
+
+	fn serialize_seq(self, _len : Option<usize>) -> Result<Self::SerializeSeq, Error> { Err(not_fields()) }
+	fn serialize_tuple(self, _len : usize) -> Result<Self::SerializeTuple, Error> { Err(not_fields()) }
+
+	fn serialize_tuple_struct(self, _name : &'static str, _len : usize) -> Result<Self::SerializeTupleStruct, Error> {
+		Err(not_fields())
+	}
+
+	fn serialize_tuple_variant(self, _name : &'static str, _index : u32, _variant : &'static str, _len : usize)
+							   -> Result<Self::SerializeTupleVariant, Error> {
+		Err(not_fields())
+	}
+
+	fn serialize_map(self, len : Option<usize>) -> Result<FieldsBuilder, Error> {
+		Ok(FieldsBuilder { fields : Fields { name : None, values : Vec::with_capacity(len.unwrap_or(0)) }, key : None })
+	}
+
+	fn serialize_struct(self, name : &'static str, len : usize) -> Result<FieldsBuilder, Error> {
+		Ok(FieldsBuilder { fields : Fields { name : Some(name), values : Vec::with_capacity(len) }, key : None })
+	}
+
+	fn serialize_struct_variant(self, _name : &'static str, _index : u32, _variant : &'static str, _len : usize)
+								-> Result<Self::SerializeStructVariant, Error> {
+		Err(not_fields())
+	}
+}
+
+impl ser::SerializeStruct for FieldsBuilder {
+	type Ok = Fields;
+	type Error = Error;
+
+	fn serialize_field<T : Serialize + ?Sized>(&mut self, key : &'static str, value : &T) -> Result<(), Error> {
+		let v = value.serialize(ValueSerializer).map_err(|e| Error(format!("field {}: {}", key, e)))?;
+		self.fields.values.push((key.to_string(), v));
+		Ok(())
+	}
+
+	fn end(self) -> Result<Fields, Error> {
+		Ok(self.fields)
+	}
+}
+
+impl ser::SerializeMap for FieldsBuilder {
+	type Ok = Fields;
+	type Error = Error;
+
+	fn serialize_key<T : Serialize + ?Sized>(&mut self, key : &T) -> Result<(), Error> {
+		match key.serialize(ValueSerializer)? {
+			Value::Text(k) => { self.key = Some(k); Ok(()) },
+			_ => Err(Error("the keys of a map must be strings".to_string()))
+		}
+	}
+
+	fn serialize_value<T : Serialize + ?Sized>(&mut self, value : &T) -> Result<(), Error> {
+		let key = self.key.take().unwrap_or_default();
+		let v = value.serialize(ValueSerializer).map_err(|e| Error(format!("field {}: {}", key, e)))?;
+		self.fields.values.push((key, v));
+		Ok(())
+	}
+
+	fn end(self) -> Result<Fields, Error> {
+		Ok(self.fields)
+	}
+}
+
+fn not_value(what : &str) -> Error {
+	Error(format!("{} cannot be stored in a column", what))
+}
+
+//Serializer of a field into a column value. Integers must fit in an i64, None and () are NULL, unit variants are
+//stored as their name, bytes and sequences of u8 as blobs.
+struct ValueSerializer;
+
+//Builds a blob from a sequence of u8.
+struct BlobBuilder(Vec<u8>);
+
+impl ser::Serializer for ValueSerializer {
+	type Ok = Value;
+	type Error = Error;
+	type SerializeSeq = BlobBuilder;
+	type SerializeTuple = Impossible<Value, Error>;
+	type SerializeTupleStruct = Impossible<Value, Error>;
+	type SerializeTupleVariant = Impossible<Value, Error>;
+	type SerializeMap = Impossible<Value, Error>;
+	type SerializeStruct = Impossible<Value, Error>;
+	type SerializeStructVariant = Impossible<Value, Error>;
+
+	fn serialize_bool(self, v : bool) -> Result<Value, Error> { Ok(Value::Integer(v as i64)) }
+	fn serialize_i8(self, v : i8) -> Result<Value, Error> { Ok(Value::Integer(v as i64)) }
+	fn serialize_i16(self, v : i16) -> Result<Value, Error> { Ok(Value::Integer(v as i64)) }
+	fn serialize_i32(self, v : i32) -> Result<Value, Error> { Ok(Value::Integer(v as i64)) }
+	fn serialize_i64(self, v : i64) -> Result<Value, Error> { Ok(Value::Integer(v)) }
+	fn serialize_u8(self, v : u8) -> Result<Value, Error> { Ok(Value::Integer(v as i64)) }
+	fn serialize_u16(self, v : u16) -> Result<Value, Error> { Ok(Value::Integer(v as i64)) }
+	fn serialize_u32(self, v : u32) -> Result<Value, Error> { Ok(Value::Integer(v as i64)) }
+	fn serialize_f32(self, v : f32) -> Result<Value, Error> { Ok(Value::Real(v as f64)) }
+	fn serialize_f64(self, v : f64) -> Result<Value, Error> { Ok(Value::Real(v)) }
+	fn serialize_char(self, v : char) -> Result<Value, Error> { Ok(Value::Text(v.to_string())) }
+	fn serialize_str(self, v : &str) -> Result<Value, Error> { Ok(Value::Text(v.to_string())) }
+	fn serialize_bytes(self, v : &[u8]) -> Result<Value, Error> { Ok(Value::Blob(v.to_vec())) }
+	fn serialize_none(self) -> Result<Value, Error> { Ok(Value::Null) }
+	fn serialize_unit(self) -> Result<Value, Error> { Ok(Value::Null) }
+	fn serialize_unit_struct(self, _name : &'static str) -> Result<Value, Error> { Ok(Value::Null) }
+
+	fn serialize_u64(self, v : u64) -> Result<Value, Error> {
+		if v > i64::MAX as u64 { return Err(Error(format!("{} does not fit in a 64-bit signed integer", v))); }
+		Ok(Value::Integer(v as i64))
+	}
+
+	fn serialize_i128(self, v : i128) -> Result<Value, Error> {
+		if v > i64::MAX as i128 || v < i64::MIN as i128 { return Err(Error(format!("{} does not fit in a 64-bit signed integer", v))); }
+		Ok(Value::Integer(v as i64))
+	}
+
+	fn serialize_u128(self, v : u128) -> Result<Value, Error> {
+		if v > i64::MAX as u128 { return Err(Error(format!("{} does not fit in a 64-bit signed integer", v))); }
+		Ok(Value::Integer(v as i64))
+	}
+
+	fn serialize_some<T : Serialize + ?Sized>(self, value : &T) -> Result<Value, Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit_variant(self, _name : &'static str, _index : u32, variant : &'static str) -> Result<Value, Error> {
+		Ok(Value::Text(variant.to_string()))
+	}
+
+	fn serialize_newtype_struct<T : Serialize + ?Sized>(self, _name : &'static str, value : &T) -> Result<Value, Error> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T : Serialize + ?Sized>(self, _name : &'static str, _index : u32, _variant : &'static str, _value : &T)
+														 -> Result<Value, Error> {
+		Err(not_value("an enum variant with data"))
+	}
+
+	fn serialize_seq(self, len : Option<usize>) -> Result<BlobBuilder, Error> {
+		Ok(BlobBuilder(Vec::with_capacity(len.unwrap_or(0))))
+	}
+
+	fn serialize_tuple(self, _len : usize) -> Result<Self::SerializeTuple, Error> { Err(not_value("a tuple")) }
+
+	fn serialize_tuple_struct(self, _name : &'static str, _len : usize) -> Result<Self::SerializeTupleStruct, Error> {
+		Err(not_value("a tuple struct"))
+	}
+
+	fn serialize_tuple_variant(self, _name : &'static str, _index : u32, _variant : &'static str, _len : usize)
+							   -> Result<Self::SerializeTupleVariant, Error> {
+		Err(not_value("an enum variant with data"))
+	}
+
+	fn serialize_map(self, _len : Option<usize>) -> Result<Self::SerializeMap, Error> { Err(not_value("a map")) }
+
+	fn serialize_struct(self, _name : &'static str, _len : usize) -> Result<Self::SerializeStruct, Error> {
+		Err(not_value("a struct"))
+	}
+
+	fn serialize_struct_variant(self, _name : &'static str, _index : u32, _variant : &'static str, _len : usize)
+								-> Result<Self::SerializeStructVariant, Error> {
+		Err(not_value("an enum variant with data"))
+	}
+}
+
+impl ser::SerializeSeq for BlobBuilder {
+	type Ok = Value;
+	type Error = Error;
+
+	fn serialize_element<T : Serialize + ?Sized>(&mut self, value : &T) -> Result<(), Error> {
+		match value.serialize(ValueSerializer)? {
+			Value::Integer(b) if (0..=255).contains(&b) => { self.0.push(b as u8); Ok(()) },
+			_ => Err(not_value("a sequence of other elements than u8"))
+		}
+	}
+
+	fn end(self) -> Result<Value, Error> {
+		Ok(Value::Blob(self.0))
+	}
+}
@@ -0,0 +1,559 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{CString, CStr};
+use std::io::{Error, ErrorKind, Result};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr::{null, null_mut};
+use std::slice;
+use std::str::from_utf8;
+use sql::driver::{Driver, RawConnection, RawStatement};
+use sql::value::ValueRef;
+use sql::columnar::ColumnType;
+
+#[link(name = "sqlite3")]
+extern "C" {
+	pub fn sqlite3_open_v2(filename : *const i8, ppDb : *const*const i8, flags : i32, zVfs : *const i8) -> i32;
+	pub fn sqlite3_close_v2(pDb : *const i8) -> i32;
+	pub fn sqlite3_errmsg(pDb : *const i8) -> *const i8;
+	pub fn sqlite3_errstr(erno : i32) -> *const i8;
+	pub fn sqlite3_prepare_v2(pDb : *const i8, sql : *const i8, nByte : i32, ppStmt : *const*const i8, pzTail : *const*const i8) -> i32;
+	pub fn sqlite3_step(pStmt : *const i8) -> i32;
+	pub fn sqlite3_changes64(pDb : *const i8) -> i64;
+	pub fn sqlite3_total_changes64(pDb : *const i8) -> i64;
+	pub fn sqlite3_last_insert_rowid(pDb : *const i8) -> i64;
+	pub fn sqlite3_get_autocommit(pDb : *const i8) -> i32;
+	pub fn sqlite3_column_int64(pStmt : *const i8, iCol : i32) -> i64;
+	pub fn sqlite3_column_double(pStmt : *const i8, iCol : i32) -> f64;
+	pub fn sqlite3_column_text(pStmt : *const i8, iCol : i32) -> *const u8;
+	pub fn sqlite3_column_blob(pStmt : *const i8, iCol : i32) -> *const u8;
+	pub fn sqlite3_column_bytes(pStmt : *const i8, iCol : i32) -> i32;
+	pub fn sqlite3_column_type(pStmt : *const i8, iCol : i32) -> i32;
+	pub fn sqlite3_column_count(pStmt : *const i8) -> i32;
+	pub fn sqlite3_column_name(pStmt : *const i8, iCol : i32) -> *const i8;
+	pub fn sqlite3_column_decltype(pStmt : *const i8, iCol : i32) -> *const i8;
+	pub fn sqlite3_bind_int64(pStmt : *const i8, iCol : i32, value : i64) -> i32;
+	pub fn sqlite3_bind_double(pStmt : *const i8, iCol : i32, value : f64) -> i32;
+	pub fn sqlite3_bind_text64(pStmt : *const i8, iCol : i32, value : *const i8, n : u64, f: *const extern "C" fn(*const i8), encoding : u8) -> i32;
+	pub fn sqlite3_bind_null(pStmt : *const i8, iCol : i32) -> i32;
+	pub fn sqlite3_bind_blob64(pStmt : *const i8, iCol : i32, value : *const i8, n : u64, f: *const extern "C" fn(*const i8)) -> i32;
+	pub fn sqlite3_reset(pStmt : *const i8) -> i32;
+	pub fn sqlite3_bind_parameter_index(pStmt : *const i8, zName : *const i8) -> i32;
+	pub fn sqlite3_sql(pStmt : *const i8) -> *const i8;
+	pub fn sqlite3_expanded_sql(pStmt : *const i8) -> *mut i8;
+	pub fn sqlite3_stmt_readonly(pStmt : *const i8) -> i32;
+	pub fn sqlite3_stmt_isexplain(pStmt : *const i8) -> i32;
+	pub fn sqlite3_stmt_busy(pStmt : *const i8) -> i32;
+	pub fn sqlite3_free(p : *mut i8);
+	pub fn sqlite3_finalize(pStmt : *const i8) -> i32;
+	pub fn sqlite3_wal_checkpoint_v2(pDb : *const i8, zDb : *const i8, eMode : i32, pnLog : *mut i32, pnCkpt : *mut i32) -> i32;
+	pub fn sqlite3_wal_hook(pDb : *const i8, callback : Option<WalCallback>, pArg : *const i8) -> *const i8;
+	pub fn sqlite3_wal_autocheckpoint(pDb : *const i8, N : i32) -> i32;
+	pub fn sqlite3_db_config(pDb : *const i8, op : i32, ...) -> i32;
+	pub fn sqlite3_serialize(pDb : *const i8, zSchema : *const i8, piSize : *mut i64, mFlags : u32) -> *mut u8;
+	pub fn sqlite3_deserialize(pDb : *const i8, zSchema : *const i8, pData : *mut u8, szDb : i64, szBuf : i64, mFlags : u32) -> i32;
+	pub fn sqlite3_malloc64(n : u64) -> *mut u8;
+	pub fn sqlite3_db_filename(pDb : *const i8, zDbName : *const i8) -> *const i8;
+	pub fn sqlite3_db_readonly(pDb : *const i8, zDbName : *const i8) -> i32;
+	pub fn sqlite3_limit(pDb : *const i8, id : i32, newVal : i32) -> i32;
+	pub fn sqlite3_load_extension(pDb : *const i8, zFile : *const i8, zProc : *const i8, pzErrMsg : *mut *mut i8) -> i32;
+}
+
+//Destructor values telling SQLite whether bound text or blob must be copied (TRANSIENT) or can be used in place (STATIC).
+const SQLITE_STATIC : isize = 0;
+const SQLITE_TRANSIENT : isize = -1;
+const SQLITE_UTF8 : u8 = 1;
+const SQLITE_ERROR : i32 = 1;
+const SQLITE_BUSY : i32 = 5;
+const SQLITE_DBCONFIG_ENABLE_LOAD_EXTENSION : i32 = 1005;
+const SQLITE_LIMIT_ATTACHED : i32 = 7;
+const SQLITE_OPEN_READWRITE : i32 = 0x02;
+const SQLITE_OPEN_CREATE : i32 = 0x04;
+const SQLITE_OPEN_URI : i32 = 0x40;
+const SQLITE_DESERIALIZE_FREEONCLOSE : u32 = 1;
+const SQLITE_DESERIALIZE_RESIZEABLE : u32 = 2;
+const SQLITE_DESERIALIZE_READONLY : u32 = 4;
+
+type WalCallback = extern "C" fn(*const i8, *const i8, *const i8, i32) -> i32;
+type WalFn = dyn FnMut(&Sqlite3Connection, &str, i32);
+
+///Driver of the SQLite3 backend, registered under <i>DbType::SQLite3</i>. The connection target is a file name.
+pub struct Sqlite3Driver;
+
+///Connection to an SQLite3 database.
+pub struct Sqlite3Connection {
+	p_db : *const i8,
+	wal_hook : RefCell<Option<Box<WalHook>>>
+}
+
+///Mode of a WAL checkpoint, see <i>Sqlite3Connection::wal_checkpoint</i>.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckpointMode {
+	///Checkpoint as many frames as possible without waiting for readers or writers.
+	Passive,
+	///Wait for the writers, then checkpoint every frame, waiting for the readers of older frames.
+	Full,
+	///As Full, then wait for the readers to leave the WAL so that the next writer restarts it from the beginning.
+	Restart,
+	///As Restart, then truncate the WAL file to zero bytes.
+	Truncate
+}
+
+///Outcome of a WAL checkpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WalCheckpoint {
+	log_frames : i32,
+	checkpointed_frames : i32,
+	busy : bool
+}
+
+//The closure registered with sqlite3_wal_hook, and the connection it is given.
+struct WalHook {
+	p_con : *const Sqlite3Connection,
+	hook : RefCell<Box<WalFn>>
+}
+
+///Prepared statement of an SQLite3 database.
+pub struct Sqlite3Statement<'c> {
+	p_con : &'c Sqlite3Connection,
+	p_stmt : *const i8
+}
+
+impl Driver for Sqlite3Driver {
+	///Open a new connection to an SQLite3 database file, created if it does not exist. <i>target</i> is a path,
+	///:memory: or a file: URI whose parameters, such as mode=ro, are applied; URI filenames are also enabled for the
+	///databases the connection attaches.
+	///
+	///Returns a <i>ConnectionRefused</i> IoError with (if available from the underlying database)
+	///text that describes the error, result code, and text that describes the result code.
+	fn connect(&self, target : &str) -> Result<Box<dyn RawConnection>> {
+		let p_db : *const i8 = null();
+		match CString::new(target) {
+			Ok(s)	=> match unsafe{sqlite3_open_v2(s.as_ptr(), &p_db, SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE | SQLITE_OPEN_URI, null())} {
+						0 => Ok(Box::new(Sqlite3Connection { p_db, wal_hook : RefCell::new(None) })),
+						i => {	let e = Error::new(ErrorKind::ConnectionRefused, get_error(p_db, i));
+								if !p_db.is_null() { unsafe { sqlite3_close_v2(p_db); } }
+								Err(e) } },
+			Err(e)	=> Err(Error::other(e))
+		}
+	}
+}
+
+impl Sqlite3Connection {
+	///Return the native sqlite3 handle of the connection.
+	pub fn handle(&self) -> *const i8 {
+		self.p_db
+	}
+
+	///Build an Io Error of kind <i>kind</i> from an SQLite result code, with the last error message of the connection.
+	pub fn error(&self, kind : ErrorKind, errno : i32) -> Error {
+		Error::new(kind, get_error(self.p_db, errno))
+	}
+
+	///Copy the frames of the write-ahead log back into the database file, with sqlite3_wal_checkpoint_v2.
+	///
+	///<i>schema</i> is the attached database to checkpoint, such as "main"; None checkpoints every attached database in
+	///WAL mode. The frame counts are -1 if the database is not in WAL mode. A Full, Restart or Truncate checkpoint
+	///that could not wait for a reader or writer is reported busy, with the frames it could checkpoint.
+	///Returns an <i>Other</i> IoError if the checkpoint fails, such as for an unknown schema.
+	pub fn wal_checkpoint(&self, schema : Option<&str>, mode : CheckpointMode) -> Result<WalCheckpoint> {
+		let schema = match schema.map(CString::new) {
+			Some(Ok(s)) => Some(s),
+			Some(Err(e)) => return Err(Error::other(e)),
+			None => None
+		};
+		let (mut log_frames, mut checkpointed_frames) = (-1, -1);
+		let rc = unsafe { sqlite3_wal_checkpoint_v2(self.p_db, schema.as_ref().map_or(null(), |s| s.as_ptr()), mode as i32,
+													&mut log_frames, &mut checkpointed_frames) };
+		match rc {
+			0 | SQLITE_BUSY => Ok(WalCheckpoint { log_frames, checkpointed_frames, busy : rc == SQLITE_BUSY }),
+			e => Err(self.error(ErrorKind::Other, e))
+		}
+	}
+
+	///Register a closure called after each transaction is committed to the write-ahead log, with sqlite3_wal_hook.
+	///
+	///The closure is given the connection, the name of the database written and the number of frames in its WAL,
+	///and may checkpoint it with <i>wal_checkpoint</i>. It replaces the previous hook and the automatic checkpoints,
+	///which <i>set_wal_autocheckpoint</i> restores. A panic in the closure makes the commit report an error, though the
+	///transaction is committed.
+	///Returns an <i>Other</i> IoError if called from the hook itself.
+	pub fn set_wal_hook<F>(&self, hook : F) -> Result<()> where F : FnMut(&Sqlite3Connection, &str, i32) + 'static {
+		let hook = Box::new(WalHook { p_con : self, hook : RefCell::new(Box::new(hook)) });
+		let p_arg = &*hook as *const WalHook as *const i8;
+		self.replace_wal_hook(|| unsafe { sqlite3_wal_hook(self.p_db, Some(wal_hook), p_arg); }, Some(hook))
+	}
+
+	///Remove the closure registered by <i>set_wal_hook</i>. Automatic checkpoints remain disabled.
+	///Returns an <i>Other</i> IoError if called from the hook itself.
+	pub fn clear_wal_hook(&self) -> Result<()> {
+		self.replace_wal_hook(|| unsafe { sqlite3_wal_hook(self.p_db, None, null()); }, None)
+	}
+
+	///Checkpoint automatically once the write-ahead log holds <i>frames</i> frames or more, with
+	///sqlite3_wal_autocheckpoint; 0 or less disables automatic checkpoints. SQLite checkpoints every 1000 frames by
+	///default. This replaces the closure registered by <i>set_wal_hook</i>.
+	///Returns an <i>Other</i> IoError if called from the hook itself.
+	pub fn set_wal_autocheckpoint(&self, frames : i32) -> Result<()> {
+		self.replace_wal_hook(|| unsafe { sqlite3_wal_autocheckpoint(self.p_db, frames); }, None)
+	}
+
+	///Load the SQLite extension at <i>path</i>, calling its <i>entry_point</i> function, or the entry point SQLite
+	///derives from the file name if None, with sqlite3_load_extension.
+	///
+	///Loading extensions stays disabled on the connection but for the duration of the call, and the SQL function
+	///load_extension() is never enabled: only the Rust side can load an extension.
+	///Returns an <i>InvalidInput</i> IoError if the path or entry point holds a NUL character, or an <i>Other</i> IoError
+	///with the message of SQLite if the extension could not be loaded.
+	pub fn load_extension(&self, path : &str, entry_point : Option<&str>) -> Result<()> {
+		let path = CString::new(path).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+		let entry_point = match entry_point.map(CString::new) {
+			Some(Ok(s)) => Some(s),
+			Some(Err(e)) => return Err(Error::new(ErrorKind::InvalidInput, e)),
+			None => None
+		};
+		self.enable_load_extension(true)?;
+		let mut p_err : *mut i8 = null_mut();
+		let rc = unsafe { sqlite3_load_extension(self.p_db, path.as_ptr(), entry_point.as_ref().map_or(null(), |s| s.as_ptr()), &mut p_err) };
+		let message = to_string(p_err);
+		if !p_err.is_null() { unsafe { sqlite3_free(p_err) }; }
+		self.enable_load_extension(false)?;
+		match rc {
+			0 => Ok(()),
+			e => Err(Error::other(message.unwrap_or_else(|| get_error(self.p_db, e))))
+		}
+	}
+
+	///Copy a database into a byte vector, the image of its file, with sqlite3_serialize.
+	///
+	///<i>schema</i> is the attached database to copy, main if None. An empty database gives an empty vector.
+	///Returns a <i>NotFound</i> IoError if there is no such database, or an <i>OutOfMemory</i> IoError if SQLite could
+	///not allocate the copy.
+	pub fn serialize(&self, schema : Option<&str>) -> Result<Vec<u8>> {
+		let name = CString::new(schema.unwrap_or("main")).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+		let mut size : i64 = 0;
+		let p = unsafe { sqlite3_serialize(self.p_db, name.as_ptr(), &mut size, 0) };
+		if p.is_null() {
+			//SQLite returns NULL for an unknown schema and for a database without any page, with the size set.
+			return match size {
+				0 => Ok(Vec::new()),
+				-1 => Err(Error::new(ErrorKind::NotFound, format!("unknown database {}", name.to_string_lossy()))),
+				_ => Err(Error::new(ErrorKind::OutOfMemory, "could not serialize the database"))
+			};
+		}
+		let bytes = unsafe { ::std::slice::from_raw_parts(p, size.max(0) as usize) }.to_vec();
+		unsafe { sqlite3_free(p as *mut i8) };
+		Ok(bytes)
+	}
+
+	///Replace a database with a copy of <i>bytes</i>, the image of a database file such as <i>serialize</i> returns,
+	///with sqlite3_deserialize. The database is then kept in memory, read-only or growing as it is written.
+	///
+	///<i>schema</i> is the attached database to replace, main if None. The image of a database in WAL mode is read in
+	///rollback journal mode, as the WAL of an in-memory database cannot be shared.
+	///Returns a <i>NotFound</i> IoError if there is no such database, or an <i>Other</i> IoError if the database is in
+	///use, such as by a pending statement or a transaction.
+	pub fn deserialize(&self, schema : Option<&str>, bytes : &[u8], read_only : bool) -> Result<()> {
+		let name = CString::new(schema.unwrap_or("main")).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+		if unsafe { sqlite3_db_filename(self.p_db, name.as_ptr()) }.is_null() {
+			return Err(Error::new(ErrorKind::NotFound, format!("unknown database {}", name.to_string_lossy())));
+		}
+		let p = unsafe { sqlite3_malloc64(bytes.len().max(1) as u64) };
+		if p.is_null() { return Err(Error::new(ErrorKind::OutOfMemory, "could not allocate the database")); }
+		let data = unsafe { ::std::slice::from_raw_parts_mut(p, bytes.len()) };
+		data.copy_from_slice(bytes);
+		//Bytes 18 and 19 of the header are the read and write versions, 2 for WAL.
+		if data.len() >= 20 && data[18] == 2 && data[19] == 2 {
+			data[18] = 1;
+			data[19] = 1;
+		}
+		let flags = SQLITE_DESERIALIZE_FREEONCLOSE | if read_only { SQLITE_DESERIALIZE_READONLY } else { SQLITE_DESERIALIZE_RESIZEABLE };
+		//SQLite frees the copy even if it fails.
+		match unsafe { sqlite3_deserialize(self.p_db, name.as_ptr(), p, bytes.len() as i64, bytes.len() as i64, flags) } {
+			0 => Ok(()),
+			e => Err(self.error(ErrorKind::Other, e))
+		}
+	}
+
+	///Return the maximum number of databases that can be attached to the connection, 10 unless SQLite was compiled
+	///otherwise.
+	pub fn attach_limit(&self) -> i32 {
+		unsafe { sqlite3_limit(self.p_db, SQLITE_LIMIT_ATTACHED, -1) }
+	}
+
+	///Return true if the database <i>schema</i> is read-only, None if there is no such database.
+	pub fn is_read_only(&self, schema : &str) -> Option<bool> {
+		let name = CString::new(schema).ok()?;
+		match unsafe { sqlite3_db_readonly(self.p_db, name.as_ptr()) } {
+			-1 => None,
+			r => Some(r == 1)
+		}
+	}
+
+	//Enable or disable sqlite3_load_extension, leaving the SQL function disabled.
+	fn enable_load_extension(&self, enabled : bool) -> Result<()> {
+		let mut state = 0;
+		match unsafe { sqlite3_db_config(self.p_db, SQLITE_DBCONFIG_ENABLE_LOAD_EXTENSION, enabled as i32, &mut state as *mut i32) } {
+			0 => Ok(()),
+			e => Err(self.error(ErrorKind::Other, e))
+		}
+	}
+
+	//Register another WAL hook and drop the previous closure, unless it is running.
+	fn replace_wal_hook<F : FnOnce()>(&self, register : F, hook : Option<Box<WalHook>>) -> Result<()> {
+		let mut current = self.wal_hook.borrow_mut();
+		if current.as_ref().is_some_and(|h| h.hook.try_borrow_mut().is_err()) {
+			return Err(Error::other("the WAL hook cannot be changed from the hook"));
+		}
+		register();
+		*current = hook;
+		Ok(())
+	}
+}
+
+impl WalCheckpoint {
+	///Return the number of frames in the write-ahead log, -1 if the database is not in WAL mode.
+	pub fn log_frames(&self) -> i32 {
+		self.log_frames
+	}
+
+	///Return the number of frames of the write-ahead log now in the database file, -1 if the database is not in WAL
+	///mode.
+	pub fn checkpointed_frames(&self) -> i32 {
+		self.checkpointed_frames
+	}
+
+	///Return true if the checkpoint could not complete because of another connection.
+	pub fn is_busy(&self) -> bool {
+		self.busy
+	}
+}
+
+extern "C" fn wal_hook(p_arg : *const i8, _p_db : *const i8, z_db : *const i8, frames : i32) -> i32 {
+	//The hook lives in the connection until it is replaced, which cannot happen while it runs.
+	let hook = unsafe { &*(p_arg as *const WalHook) };
+	let name = to_string(z_db).unwrap_or_default();
+	let r = catch_unwind(AssertUnwindSafe(|| {
+		if let Ok(mut f) = hook.hook.try_borrow_mut() { f(unsafe { &*hook.p_con }, &name, frames); }
+	}));
+	if r.is_ok() { 0 } else { SQLITE_ERROR }
+}
+
+impl RawConnection for Sqlite3Connection {
+	fn prepare<'c>(&'c self, sql : &str) -> Result<Box<dyn RawStatement + 'c>> {
+		let p_stmt  : *const i8 = null();
+		let pz_tail : *const i8 = null();
+		match CString::new(sql) {
+			Ok(s)	=> match unsafe { sqlite3_prepare_v2(self.p_db, s.as_ptr(), -1, &p_stmt, &pz_tail) } {
+						0 => Ok(Box::new(Sqlite3Statement { p_con : self, p_stmt })),
+						e => Err(self.error(ErrorKind::InvalidInput, e)) },
+			Err(e)	=> Err(Error::other(e))
+		}
+	}
+
+	fn last_insert_rowid(&self) -> i64 {
+		unsafe { sqlite3_last_insert_rowid(self.p_db) }
+	}
+
+	fn changes(&self) -> i64 {
+		unsafe { sqlite3_changes64(self.p_db) }
+	}
+
+	fn total_changes(&self) -> i64 {
+		unsafe { sqlite3_total_changes64(self.p_db) }
+	}
+
+	fn is_autocommit(&self) -> bool {
+		unsafe { sqlite3_get_autocommit(self.p_db) != 0 }
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+impl Drop for Sqlite3Connection {
+	///The drop method is called when Connection goes out of scope, and therefore close properly the connection.
+	fn drop(&mut self) {
+		if !self.p_db.is_null() { unsafe { sqlite3_close_v2(self.p_db); } }
+	}
+}
+
+impl<'c> Sqlite3Statement<'c> {
+	///Return the native sqlite3_stmt handle of the statement.
+	pub fn handle(&self) -> *const i8 {
+		self.p_stmt
+	}
+
+	fn check(&self, rc : i32) -> Result<()> {
+		match rc {
+			0 => Ok(()),
+			n => Err(self.p_con.error(ErrorKind::Other, n))
+		}
+	}
+
+	fn bind_text_with(&mut self, index : i32, value : &str, destructor : isize) -> Result<()> {
+		self.check(unsafe { sqlite3_bind_text64(self.p_stmt, index, value.as_ptr() as *const i8, value.len() as u64,
+												destructor as *const extern "C" fn(*const i8), SQLITE_UTF8) })
+	}
+
+	fn bind_blob_with(&mut self, index : i32, value : &[u8], destructor : isize) -> Result<()> {
+		self.check(unsafe { sqlite3_bind_blob64(self.p_stmt, index, value.as_ptr() as *const i8, value.len() as u64,
+												destructor as *const extern "C" fn(*const i8)) })
+	}
+
+	fn bytes(&self, p : *const u8, index : i32) -> &[u8] {
+		let n = unsafe { sqlite3_column_bytes(self.p_stmt, index) };
+		if p.is_null() || n <= 0 { return &[]; }
+		unsafe { slice::from_raw_parts(p, n as usize) }
+	}
+}
+
+impl<'c> RawStatement for Sqlite3Statement<'c> {
+	fn reset(&mut self) -> Result<()> {
+		//The result code of sqlite3_reset repeats the error of the last step, which has already been reported.
+		unsafe { sqlite3_reset(self.p_stmt) };
+		Ok(())
+	}
+
+	fn step(&mut self) -> Result<bool> {
+		match unsafe { sqlite3_step(self.p_stmt) } {
+			100 => Ok(true),
+			101 => Ok(false),
+			err => Err(self.p_con.error(ErrorKind::Other, err))
+		}
+	}
+
+	fn bind_null(&mut self, index : i32) -> Result<()> {
+		self.check(unsafe { sqlite3_bind_null(self.p_stmt, index) })
+	}
+
+	fn bind_int64(&mut self, index : i32, value : i64) -> Result<()> {
+		self.check(unsafe { sqlite3_bind_int64(self.p_stmt, index, value) })
+	}
+
+	fn bind_double(&mut self, index : i32, value : f64) -> Result<()> {
+		self.check(unsafe { sqlite3_bind_double(self.p_stmt, index, value) })
+	}
+
+	fn bind_text(&mut self, index : i32, value : &str) -> Result<()> {
+		self.bind_text_with(index, value, SQLITE_TRANSIENT)
+	}
+
+	fn bind_blob(&mut self, index : i32, value : &[u8]) -> Result<()> {
+		self.bind_blob_with(index, value, SQLITE_TRANSIENT)
+	}
+
+	unsafe fn bind_text_in_place(&mut self, index : i32, value : &str) -> Result<()> {
+		self.bind_text_with(index, value, SQLITE_STATIC)
+	}
+
+	unsafe fn bind_blob_in_place(&mut self, index : i32, value : &[u8]) -> Result<()> {
+		self.bind_blob_with(index, value, SQLITE_STATIC)
+	}
+
+	fn column_count(&self) -> i32 {
+		unsafe { sqlite3_column_count(self.p_stmt) }
+	}
+
+	fn column_name(&self, index : i32) -> String {
+		to_string(unsafe { sqlite3_column_name(self.p_stmt, index) }).unwrap_or_default()
+	}
+
+	fn column_decltype(&self, index : i32) -> Option<String> {
+		to_string(unsafe { sqlite3_column_decltype(self.p_stmt, index) })
+	}
+
+	fn column_value(&self, index : i32) -> ValueRef<'_> {
+		match unsafe { sqlite3_column_type(self.p_stmt, index) } {
+			1 => ValueRef::Integer(self.column_int64(index)),
+			2 => ValueRef::Real(self.column_double(index)),
+			3 => ValueRef::Text(self.column_text(index)),
+			4 => ValueRef::Blob(self.column_blob(index)),
+			_ => ValueRef::Null
+		}
+	}
+
+	fn column_int64(&self, index : i32) -> i64 {
+		unsafe { sqlite3_column_int64(self.p_stmt, index) }
+	}
+
+	fn column_double(&self, index : i32) -> f64 {
+		unsafe { sqlite3_column_double(self.p_stmt, index) }
+	}
+
+	fn column_text(&self, index : i32) -> &[u8] {
+		self.bytes(unsafe { sqlite3_column_text(self.p_stmt, index) }, index)
+	}
+
+	fn column_blob(&self, index : i32) -> &[u8] {
+		self.bytes(unsafe { sqlite3_column_blob(self.p_stmt, index) }, index)
+	}
+
+	fn column_is_null(&self, index : i32) -> bool {
+		unsafe { sqlite3_column_type(self.p_stmt, index) == 5 }
+	}
+
+	fn column_type(&self, index : i32) -> Option<ColumnType> {
+		match unsafe { sqlite3_column_type(self.p_stmt, index) } {
+			1 => Some(ColumnType::Integer),
+			2 => Some(ColumnType::Real),
+			3 => Some(ColumnType::Text),
+			4 => Some(ColumnType::Blob),
+			_ => None
+		}
+	}
+
+	fn sql(&self) -> String {
+		to_string(unsafe { sqlite3_sql(self.p_stmt) }).unwrap_or_default()
+	}
+
+	fn parameter_index(&self, name : &str) -> Option<i32> {
+		let name = CString::new(name).ok()?;
+		match unsafe { sqlite3_bind_parameter_index(self.p_stmt, name.as_ptr()) } {
+			0 => None,
+			i => Some(i)
+		}
+	}
+
+	fn expanded_sql(&self) -> Option<String> {
+		let p = unsafe { sqlite3_expanded_sql(self.p_stmt) };
+		let s = to_string(p);
+		if !p.is_null() { unsafe { sqlite3_free(p) }; }
+		s
+	}
+
+	fn is_readonly(&self) -> bool {
+		unsafe { sqlite3_stmt_readonly(self.p_stmt) != 0 }
+	}
+
+	fn is_explain(&self) -> bool {
+		unsafe { sqlite3_stmt_isexplain(self.p_stmt) != 0 }
+	}
+
+	fn is_busy(&self) -> bool {
+		unsafe { sqlite3_stmt_busy(self.p_stmt) != 0 }
+	}
+}
+
+impl<'c> Drop for Sqlite3Statement<'c> {
+	///The drop method is called when Statement goes out of scope, and therefore release the prepared statement.
+	fn drop(&mut self) {
+		if !self.p_stmt.is_null() { unsafe { sqlite3_finalize(self.p_stmt); } }
+	}
+}
+
+//Copy a NUL terminated string owned by SQLite, None if the pointer is NULL.
+pub fn to_string(p : *const i8) -> Option<String> {
+	if p.is_null() { return None; }
+	Some(String::from_utf8_lossy(unsafe { CStr::from_ptr(p) }.to_bytes()).into_owned())
+}
+
+pub fn get_error(p_db : *const i8, errno : i32) -> String {
+	let mut buf=String::new();
+	if let Ok(s) = from_utf8( unsafe{CStr::from_ptr(sqlite3_errmsg(p_db))}.to_bytes() ) { buf.push_str(s) }
+	if !buf.is_empty() { buf.push(' '); }
+	buf.push('('); buf.push_str(errno.to_string().as_ref());
+	if let Ok(s) = from_utf8( unsafe{CStr::from_ptr(sqlite3_errstr(errno))}.to_bytes() ) { buf.push(':');  buf.push_str(s); }
+	buf.push(')');
+	buf
+}
@@ -0,0 +1,640 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{Error, ErrorKind, Result};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sql::crypto::random_bytes;
+use sql::value::{Value, ValueRef};
+
+const NANOS : i64 = 1_000_000_000;
+const DAY : i64 = 86_400;
+//Julian day of the Unix epoch, 1970-01-01 00:00:00 UTC.
+const UNIX_JULIAN_DAY : f64 = 2_440_587.5;
+
+///How a Timestamp or a Date is stored in a column.
+///
+///Whatever the format, a column is read back according to its storage class: text as ISO-8601, integers as Unix
+///seconds and reals as julian days. The SQLite date and time functions read text and reals the same way, and integers
+///with the 'unixepoch' modifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeFormat {
+	///ISO-8601 text, "YYYY-MM-DD HH:MM:SS" with the fraction of second if any, or "YYYY-MM-DD" for a Date.
+	Text,
+	///Integer number of seconds since 1970-01-01 00:00:00 UTC, the fraction of second being dropped.
+	UnixSeconds,
+	///Real number of days since the julian day epoch, to the millisecond.
+	JulianDay
+}
+
+///The unit of the integer a Duration is stored as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeUnit {
+	Seconds,
+	Millis,
+	Micros,
+	Nanos
+}
+
+///How a Uuid is stored in a column. Both are read back whatever the format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UuidFormat {
+	///A 16-byte blob.
+	Blob,
+	///Lower case text with hyphens, as 123e4567-e89b-12d3-a456-426614174000.
+	Text
+}
+
+///How a Decimal is stored in a column.
+///
+///Text keeps every digit; a scaled integer is the value multiplied by 10<sup>scale</sup>, such as cents for a scale of
+///2, which the database can sum and compare exactly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecimalFormat {
+	Text,
+	Scaled(u32)
+}
+
+///An instant in UTC, to the nanosecond, from year 0 to 9999.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp {
+	seconds : i64,
+	nanos : u32
+}
+
+///A calendar date of the proleptic Gregorian calendar, from year 0 to 9999.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+	year : i32,
+	month : u32,
+	day : u32
+}
+
+///A universally unique identifier, of 16 bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uuid([u8; 16]);
+
+///A fixed-point decimal number: an integer mantissa and a number of digits after the decimal point.
+///
+///Decimals are compared by value, 1.5 being equal to 1.50, and formatted with all the digits of their scale.
+#[derive(Clone, Copy, Debug)]
+pub struct Decimal {
+	mantissa : i128,
+	scale : u32
+}
+
+fn invalid(what : &str, value : ValueRef) -> Error {
+	let shown = match value {
+		ValueRef::Null => "NULL".to_string(),
+		ValueRef::Blob(b) => format!("a blob of {} byte(s)", b.len()),
+		v => format!("'{}'", v.as_text())
+	};
+	Error::new(ErrorKind::InvalidData, format!("{} is not {}", shown, what))
+}
+
+fn out_of_range<T : fmt::Display>(value : T) -> Error {
+	Error::new(ErrorKind::InvalidInput, format!("{} is out of the range of an integer column", value))
+}
+
+//Days from 1970-01-01 to a date of the proleptic Gregorian calendar.
+fn days_from_civil(year : i32, month : u32, day : u32) -> i64 {
+	let y = year as i64 - if month <= 2 { 1 } else { 0 };
+	let era = y.div_euclid(400);
+	let yoe = y - era * 400;
+	let m = month as i64;
+	let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+	let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+	era * 146_097 + doe - 719_468
+}
+
+//Date of the proleptic Gregorian calendar a number of days after 1970-01-01.
+fn civil_from_days(days : i64) -> (i32, u32, u32) {
+	let z = days + 719_468;
+	let era = z.div_euclid(146_097);
+	let doe = z - era * 146_097;
+	let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+	((yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32, month, day)
+}
+
+//Read a fixed number of digits at the start of a text.
+fn digits(s : &mut &str, n : usize) -> Option<u32> {
+	if s.len() < n || !s.as_bytes()[..n].iter().all(|c| c.is_ascii_digit()) { return None; }
+	let v = s[..n].parse().ok();
+	*s = &s[n..];
+	v
+}
+
+fn skip(s : &mut &str, c : char) -> bool {
+	if s.starts_with(c) { *s = &s[c.len_utf8()..]; true } else { false }
+}
+
+impl Date {
+	///Return the date, None if it does not exist or its year is not within 0 and 9999.
+	pub fn new(year : i32, month : u32, day : u32) -> Option<Date> {
+		let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+		let days = match month { 2 => if leap { 29 } else { 28 }, 4 | 6 | 9 | 11 => 30, 1..=12 => 31, _ => 0 };
+		if (0..=9999).contains(&year) && day >= 1 && day <= days { Some(Date { year, month, day }) } else { None }
+	}
+
+	///Return the date a number of days after 1970-01-01, None if its year is not within 0 and 9999.
+	pub fn from_days_since_epoch(days : i64) -> Option<Date> {
+		if !(-719_528..=2_932_896).contains(&days) { return None; }
+		let (year, month, day) = civil_from_days(days);
+		Some(Date { year, month, day })
+	}
+
+	///Return the number of days since 1970-01-01, negative before.
+	pub fn days_since_epoch(&self) -> i64 {
+		days_from_civil(self.year, self.month, self.day)
+	}
+
+	pub fn year(&self) -> i32 {
+		self.year
+	}
+
+	///Return the month, from 1 to 12.
+	pub fn month(&self) -> u32 {
+		self.month
+	}
+
+	///Return the day of the month, from 1 to 31.
+	pub fn day(&self) -> u32 {
+		self.day
+	}
+
+	///Return the Timestamp of the start of the day, at midnight UTC.
+	pub fn midnight(&self) -> Timestamp {
+		Timestamp { seconds : self.days_since_epoch() * DAY, nanos : 0 }
+	}
+
+	///Return the value stored for the date in the format.
+	pub fn to_value(&self, format : TimeFormat) -> Value {
+		match format {
+			TimeFormat::Text => Value::Text(self.to_string()),
+			_ => self.midnight().to_value(format)
+		}
+	}
+
+	///Read a date from a column value: the date part of ISO-8601 text, Unix seconds or a julian day.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not a date.
+	pub fn from_value(value : ValueRef) -> Result<Date> {
+		Timestamp::from_value(value).map(|t| t.date()).map_err(|_| invalid("a date", value))
+	}
+}
+
+impl fmt::Display for Date {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+	}
+}
+
+impl FromStr for Date {
+	type Err = Error;
+
+	///Parse a date written YYYY-MM-DD.
+	fn from_str(s : &str) -> Result<Date> {
+		let mut t = s;
+		let year = digits(&mut t, 4);
+		let month = if skip(&mut t, '-') { digits(&mut t, 2) } else { None };
+		let day = if skip(&mut t, '-') { digits(&mut t, 2) } else { None };
+		let date = match (year, month, day) { (Some(y), Some(m), Some(d)) => Date::new(y as i32, m, d), _ => None };
+		match date {
+			Some(d) if t.is_empty() => Ok(d),
+			_ => Err(Error::new(ErrorKind::InvalidData, format!("'{}' is not a date", s)))
+		}
+	}
+}
+
+impl Timestamp {
+	///Return the instant <i>seconds</i> and <i>nanos</i> nanoseconds after 1970-01-01 00:00:00 UTC, None if <i>nanos</i>
+	///is not below one second or the year is not within 0 and 9999.
+	pub fn from_unix(seconds : i64, nanos : u32) -> Option<Timestamp> {
+		if nanos as i64 >= NANOS || Date::from_days_since_epoch(seconds.div_euclid(DAY)).is_none() { return None; }
+		Some(Timestamp { seconds, nanos })
+	}
+
+	///Return the instant at a time of a date, None if the time does not exist.
+	pub fn from_date_time(date : Date, hour : u32, minute : u32, second : u32, nanos : u32) -> Option<Timestamp> {
+		if hour > 23 || minute > 59 || second > 59 { return None; }
+		Timestamp::from_unix(date.midnight().seconds + (hour * 3600 + minute * 60 + second) as i64, nanos)
+	}
+
+	///Return the instant of a julian day, to the millisecond. Returns None if the year is not within 0 and 9999.
+	pub fn from_julian_day(day : f64) -> Option<Timestamp> {
+		let millis = ((day - UNIX_JULIAN_DAY) * (DAY * 1000) as f64).round();
+		if !millis.is_finite() || millis.abs() > 1e15 { return None; }
+		let millis = millis as i64;
+		Timestamp::from_unix(millis.div_euclid(1000), (millis.rem_euclid(1000) * 1_000_000) as u32)
+	}
+
+	///Return the current instant.
+	pub fn now() -> Timestamp {
+		Timestamp::from(SystemTime::now())
+	}
+
+	///Return the number of whole seconds since 1970-01-01 00:00:00 UTC, negative before.
+	pub fn unix_seconds(&self) -> i64 {
+		self.seconds
+	}
+
+	///Return the nanoseconds past the second.
+	pub fn subsec_nanos(&self) -> u32 {
+		self.nanos
+	}
+
+	///Return the julian day of the instant.
+	pub fn julian_day(&self) -> f64 {
+		UNIX_JULIAN_DAY + (self.seconds as f64 + self.nanos as f64 / NANOS as f64) / DAY as f64
+	}
+
+	///Return the date of the instant, in UTC.
+	pub fn date(&self) -> Date {
+		let (year, month, day) = civil_from_days(self.seconds.div_euclid(DAY));
+		Date { year, month, day }
+	}
+
+	///Return the hour, minute and second of the instant, in UTC.
+	pub fn time(&self) -> (u32, u32, u32) {
+		let s = self.seconds.rem_euclid(DAY) as u32;
+		(s / 3600, s / 60 % 60, s % 60)
+	}
+
+	///Return the value stored for the instant in the format.
+	pub fn to_value(&self, format : TimeFormat) -> Value {
+		match format {
+			TimeFormat::Text => Value::Text(self.to_string()),
+			TimeFormat::UnixSeconds => Value::Integer(self.seconds),
+			TimeFormat::JulianDay => Value::Real(self.julian_day())
+		}
+	}
+
+	///Read an instant from a column value: ISO-8601 text, integer Unix seconds or a real julian day.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not an instant.
+	pub fn from_value(value : ValueRef) -> Result<Timestamp> {
+		let t = match value {
+			ValueRef::Integer(s) => Timestamp::from_unix(s, 0),
+			ValueRef::Real(d) => Timestamp::from_julian_day(d),
+			ValueRef::Text(t) => ::std::str::from_utf8(t).ok().and_then(|t| t.parse().ok()),
+			_ => None
+		};
+		t.ok_or_else(|| invalid("a timestamp", value))
+	}
+}
+
+impl fmt::Display for Timestamp {
+	///Write the instant as ISO-8601 text, YYYY-MM-DD HH:MM:SS followed by the fraction of second if any, without
+	///trailing zeros.
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		let (h, m, s) = self.time();
+		write!(f, "{} {:02}:{:02}:{:02}", self.date(), h, m, s)?;
+		if self.nanos != 0 {
+			let fraction = format!("{:09}", self.nanos);
+			write!(f, ".{}", fraction.trim_end_matches('0'))?;
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for Timestamp {
+	type Err = Error;
+
+	///Parse ISO-8601 text: a date YYYY-MM-DD, optionally followed by a time HH:MM, HH:MM:SS or HH:MM:SS.fraction
+	///after a space or a 'T', and by a time zone Z, +HH:MM or -HH:MM. The instant is converted to UTC.
+	fn from_str(s : &str) -> Result<Timestamp> {
+		let error = || Error::new(ErrorKind::InvalidData, format!("'{}' is not a timestamp", s));
+		let split = s.find([' ', 'T']).unwrap_or(s.len());
+		let date : Date = s[..split].parse().map_err(|_| error())?;
+		let mut t = &s[split..];
+		let (mut hour, mut minute, mut second, mut nanos) = (0, 0, 0, 0);
+		if skip(&mut t, ' ') || skip(&mut t, 'T') {
+			hour = digits(&mut t, 2).ok_or_else(error)?;
+			if !skip(&mut t, ':') { return Err(error()); }
+			minute = digits(&mut t, 2).ok_or_else(error)?;
+			if skip(&mut t, ':') {
+				second = digits(&mut t, 2).ok_or_else(error)?;
+				if skip(&mut t, '.') {
+					let n = t.bytes().take_while(|c| c.is_ascii_digit()).count();
+					if n == 0 { return Err(error()); }
+					let fraction = &t[..n.min(9)];
+					nanos = fraction.parse::<u32>().map_err(|_| error())? * 10u32.pow(9 - fraction.len() as u32);
+					t = &t[n..];
+				}
+			}
+		}
+		let mut offset = 0;
+		if !skip(&mut t, 'Z') && !t.is_empty() {
+			let sign = if skip(&mut t, '+') { 1 } else if skip(&mut t, '-') { -1 } else { return Err(error()); };
+			let h = digits(&mut t, 2).ok_or_else(error)?;
+			skip(&mut t, ':');
+			let m = digits(&mut t, 2).ok_or_else(error)?;
+			offset = sign * (h * 3600 + m * 60) as i64;
+		}
+		if !t.is_empty() { return Err(error()); }
+		let local = Timestamp::from_date_time(date, hour, minute, second, nanos).ok_or_else(error)?;
+		Timestamp::from_unix(local.seconds - offset, nanos).ok_or_else(error)
+	}
+}
+
+impl From<SystemTime> for Timestamp {
+	fn from(t : SystemTime) -> Timestamp {
+		let (seconds, nanos) = match t.duration_since(UNIX_EPOCH) {
+			Ok(d) => (d.as_secs() as i64, d.subsec_nanos()),
+			Err(e) => {
+				let d = e.duration();
+				if d.subsec_nanos() == 0 { (-(d.as_secs() as i64), 0) } else { (-(d.as_secs() as i64) - 1, NANOS as u32 - d.subsec_nanos()) }
+			}
+		};
+		Timestamp { seconds, nanos }
+	}
+}
+
+impl From<Timestamp> for SystemTime {
+	fn from(t : Timestamp) -> SystemTime {
+		if t.seconds >= 0 {
+			UNIX_EPOCH + Duration::new(t.seconds as u64, t.nanos)
+		} else {
+			UNIX_EPOCH - Duration::new(t.seconds.unsigned_abs(), 0) + Duration::new(0, t.nanos)
+		}
+	}
+}
+
+//Return the value stored for a duration: the integer number of units, the remainder being dropped.
+//
+//Returns an <i>InvalidInput</i> IoError if the number exceeds the range of an integer column.
+pub fn duration_to_value(duration : Duration, unit : TimeUnit) -> Result<Value> {
+	let n = match unit {
+		TimeUnit::Seconds => duration.as_secs() as u128,
+		TimeUnit::Millis => duration.as_millis(),
+		TimeUnit::Micros => duration.as_micros(),
+		TimeUnit::Nanos => duration.as_nanos()
+	};
+	if n > i64::MAX as u128 { return Err(out_of_range(format!("{:?}", duration))); }
+	Ok(Value::Integer(n as i64))
+}
+
+//Read a duration from a column value: a number of units, integer, real or text.
+//
+//Returns an <i>InvalidData</i> IoError if the value is not a positive number.
+pub fn duration_from_value(value : ValueRef, unit : TimeUnit) -> Result<Duration> {
+	let per_second = match unit { TimeUnit::Seconds => 1.0, TimeUnit::Millis => 1e3, TimeUnit::Micros => 1e6, TimeUnit::Nanos => 1e9 };
+	let n = match value {
+		ValueRef::Integer(n) if n >= 0 => {
+			let per = per_second as u64;
+			return Ok(Duration::new(n as u64 / per, ((n as u64 % per) * (NANOS as u64 / per)) as u32));
+		},
+		ValueRef::Real(f) => f,
+		ValueRef::Text(t) => ::std::str::from_utf8(t).ok().and_then(|t| t.trim().parse::<f64>().ok()).unwrap_or(-1.0),
+		_ => -1.0
+	};
+	Duration::try_from_secs_f64(n / per_second).map_err(|_| invalid("a duration", value))
+}
+
+//Return the value stored for a u64.
+//
+//Returns an <i>InvalidInput</i> IoError if it exceeds the range of an integer column, 2<sup>63</sup> - 1.
+pub fn u64_to_value(value : u64) -> Result<Value> {
+	if value > i64::MAX as u64 { Err(out_of_range(value)) } else { Ok(Value::Integer(value as i64)) }
+}
+
+//Return the value stored for an i128.
+//
+//Returns an <i>InvalidInput</i> IoError if it exceeds the range of an integer column, that of an i64.
+pub fn i128_to_value(value : i128) -> Result<Value> {
+	if value > i64::MAX as i128 || value < i64::MIN as i128 { Err(out_of_range(value)) } else { Ok(Value::Integer(value as i64)) }
+}
+
+//Read an integer from a column value, an integer or text holding an integer.
+fn integer<T : FromStr + From<i64>>(value : ValueRef) -> Option<T> {
+	match value {
+		ValueRef::Integer(i) => Some(T::from(i)),
+		ValueRef::Text(t) => ::std::str::from_utf8(t).ok().and_then(|t| t.trim().parse().ok()),
+		_ => None
+	}
+}
+
+//Read a u64 from a column value, an integer or text holding an integer.
+//
+//Returns an <i>InvalidData</i> IoError if the value is not an integer or is negative.
+pub fn u64_from_value(value : ValueRef) -> Result<u64> {
+	integer::<i128>(value).and_then(|i| if (0..=u64::MAX as i128).contains(&i) { Some(i as u64) } else { None })
+						  .ok_or_else(|| invalid("a u64", value))
+}
+
+//Read an i128 from a column value, an integer or text holding an integer.
+//
+//Returns an <i>InvalidData</i> IoError if the value is not an integer.
+pub fn i128_from_value(value : ValueRef) -> Result<i128> {
+	integer(value).ok_or_else(|| invalid("an i128", value))
+}
+
+impl Uuid {
+	///Return the UUID of 16 bytes.
+	pub fn from_bytes(bytes : [u8; 16]) -> Uuid {
+		Uuid(bytes)
+	}
+
+	///Return a random UUID, of version 4, or an <i>Unsupported</i> IoError if the system has no random source.
+	pub fn new_v4() -> Result<Uuid> {
+		let mut b = [0; 16];
+		random_bytes(&mut b)?;
+		b[6] = (b[6] & 0x0f) | 0x40;
+		b[8] = (b[8] & 0x3f) | 0x80;
+		Ok(Uuid(b))
+	}
+
+	///Return the nil UUID, whose bytes are all 0.
+	pub fn nil() -> Uuid {
+		Uuid([0; 16])
+	}
+
+	pub fn as_bytes(&self) -> &[u8; 16] {
+		&self.0
+	}
+
+	///Return the value stored for the UUID in the format.
+	pub fn to_value(&self, format : UuidFormat) -> Value {
+		match format {
+			UuidFormat::Blob => Value::Blob(self.0.to_vec()),
+			UuidFormat::Text => Value::Text(self.to_string())
+		}
+	}
+
+	///Read a UUID from a column value, a 16-byte blob or text.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not a UUID.
+	pub fn from_value(value : ValueRef) -> Result<Uuid> {
+		let u = match value {
+			ValueRef::Blob(b) if b.len() == 16 => { let mut u = [0; 16]; u.copy_from_slice(b); Some(Uuid(u)) },
+			ValueRef::Text(t) => ::std::str::from_utf8(t).ok().and_then(|t| t.parse().ok()),
+			_ => None
+		};
+		u.ok_or_else(|| invalid("a UUID", value))
+	}
+}
+
+impl fmt::Display for Uuid {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		for (i, b) in self.0.iter().enumerate() {
+			if i == 4 || i == 6 || i == 8 || i == 10 { f.write_str("-")?; }
+			write!(f, "{:02x}", b)?;
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for Uuid {
+	type Err = Error;
+
+	///Parse 32 hexadecimal digits in either case, with or without the hyphens, optionally in braces.
+	fn from_str(s : &str) -> Result<Uuid> {
+		let t = if s.starts_with('{') && s.ends_with('}') && s.len() >= 2 { &s[1..s.len() - 1] } else { s };
+		let hyphens = t.len() == 36 && t.char_indices().all(|(i, c)| (c == '-') == (i == 8 || i == 13 || i == 18 || i == 23));
+		let hex : String = if hyphens { t.chars().filter(|&c| c != '-').collect() } else { t.to_string() };
+		if hex.len() != 32 || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+			return Err(Error::new(ErrorKind::InvalidData, format!("'{}' is not a UUID", s)));
+		}
+		let mut u = [0; 16];
+		for (i, b) in u.iter_mut().enumerate() { *b = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap_or(0); }
+		Ok(Uuid(u))
+	}
+}
+
+impl Decimal {
+	///Return the decimal <i>mantissa</i> × 10<sup>-scale</sup>, such as 1250 with a scale of 2 for 12.50. None if the
+	///scale exceeds 38.
+	pub fn new(mantissa : i128, scale : u32) -> Option<Decimal> {
+		if scale > 38 { None } else { Some(Decimal { mantissa, scale }) }
+	}
+
+	pub fn mantissa(&self) -> i128 {
+		self.mantissa
+	}
+
+	///Return the number of digits after the decimal point.
+	pub fn scale(&self) -> u32 {
+		self.scale
+	}
+
+	///Return the same value with another scale, None if digits would be lost or the mantissa would overflow.
+	pub fn rescale(&self, scale : u32) -> Option<Decimal> {
+		if scale > 38 { return None; }
+		if scale >= self.scale {
+			return 10i128.checked_pow(scale - self.scale).and_then(|p| self.mantissa.checked_mul(p)).map(|mantissa| Decimal { mantissa, scale });
+		}
+		let p = 10i128.pow(self.scale - scale);
+		if self.mantissa % p != 0 { None } else { Some(Decimal { mantissa : self.mantissa / p, scale }) }
+	}
+
+	///Return the value rounded to <i>scale</i> digits after the decimal point, half away from zero. None if the mantissa
+	///would overflow.
+	pub fn round(&self, scale : u32) -> Option<Decimal> {
+		if scale >= self.scale { return self.rescale(scale); }
+		let p = 10i128.pow(self.scale - scale);
+		let (q, r) = (self.mantissa / p, self.mantissa % p);
+		let q = if r.unsigned_abs() * 2 >= p as u128 { q + self.mantissa.signum() } else { q };
+		Some(Decimal { mantissa : q, scale })
+	}
+
+	///Return the value stored for the decimal in the format.
+	///
+	///Returns an <i>InvalidInput</i> IoError if the decimal has more digits after the decimal point than the scale of
+	///the format, or if the scaled integer exceeds the range of an integer column.
+	pub fn to_value(&self, format : DecimalFormat) -> Result<Value> {
+		match format {
+			DecimalFormat::Text => Ok(Value::Text(self.to_string())),
+			DecimalFormat::Scaled(scale) => match self.rescale(scale) {
+				Some(d) => i128_to_value(d.mantissa),
+				None => Err(Error::new(ErrorKind::InvalidInput, format!("{} does not fit a scale of {}", self, scale)))
+			}
+		}
+	}
+
+	///Read a decimal from a column value: text, a real, or an integer scaled as in the format.
+	///
+	///Returns an <i>InvalidData</i> IoError if the value is not a decimal.
+	pub fn from_value(value : ValueRef, format : DecimalFormat) -> Result<Decimal> {
+		let d = match (value, format) {
+			(ValueRef::Integer(i), DecimalFormat::Scaled(scale)) => Decimal::new(i as i128, scale),
+			(ValueRef::Integer(i), DecimalFormat::Text) => Decimal::new(i as i128, 0),
+			(ValueRef::Real(f), _) if f.is_finite() => format!("{:?}", f).parse().ok(),
+			(ValueRef::Text(t), _) => ::std::str::from_utf8(t).ok().and_then(|t| t.trim().parse().ok()),
+			_ => None
+		};
+		d.ok_or_else(|| invalid("a decimal", value))
+	}
+}
+
+impl PartialEq for Decimal {
+	fn eq(&self, other : &Decimal) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+	fn partial_cmp(&self, other : &Decimal) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Decimal {
+	fn cmp(&self, other : &Decimal) -> Ordering {
+		let scale = self.scale.max(other.scale);
+		match (self.rescale(scale), other.rescale(scale)) {
+			(Some(a), Some(b)) => a.mantissa.cmp(&b.mantissa),
+			//The value whose mantissa overflows is the larger in magnitude.
+			(None, _) => if self.mantissa < 0 { Ordering::Less } else { Ordering::Greater },
+			(_, None) => if other.mantissa < 0 { Ordering::Greater } else { Ordering::Less }
+		}
+	}
+}
+
+impl fmt::Display for Decimal {
+	fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+		let digits = self.mantissa.unsigned_abs().to_string();
+		let scale = self.scale as usize;
+		let sign = if self.mantissa < 0 { "-" } else { "" };
+		if scale == 0 { return write!(f, "{}{}", sign, digits); }
+		let digits = format!("{:0>width$}", digits, width = scale + 1);
+		let (int, frac) = digits.split_at(digits.len() - scale);
+		write!(f, "{}{}.{}", sign, int, frac)
+	}
+}
+
+impl FromStr for Decimal {
+	type Err = Error;
+
+	///Parse a decimal number, such as -12.50 or 1.5e3, keeping the digits written after the decimal point.
+	fn from_str(s : &str) -> Result<Decimal> {
+		let error = || Error::new(ErrorKind::InvalidData, format!("'{}' is not a decimal", s));
+		let (number, exponent) = match s.find(['e', 'E']) {
+			Some(i) => (&s[..i], s[i + 1..].parse::<i32>().map_err(|_| error())?),
+			None => (s, 0)
+		};
+		let (negative, number) = match number.as_bytes().first() {
+			Some(b'-') => (true, &number[1..]),
+			Some(b'+') => (false, &number[1..]),
+			_ => (false, number)
+		};
+		let (int, frac) = match number.find('.') { Some(i) => (&number[..i], &number[i + 1..]), None => (number, "") };
+		if int.is_empty() && frac.is_empty() || !int.bytes().chain(frac.bytes()).all(|c| c.is_ascii_digit()) { return Err(error()); }
+		let mut mantissa : i128 = 0;
+		for c in int.bytes().chain(frac.bytes()) {
+			mantissa = mantissa.checked_mul(10).and_then(|m| m.checked_add((c - b'0') as i128)).ok_or_else(error)?;
+		}
+		if negative { mantissa = -mantissa; }
+		let scale = frac.len() as i32 - exponent;
+		let d = if scale >= 0 {
+			Decimal::new(mantissa, scale as u32)
+		} else {
+			10i128.checked_pow(-scale as u32).and_then(|p| mantissa.checked_mul(p)).map(|mantissa| Decimal { mantissa, scale : 0 })
+		};
+		d.ok_or_else(error)
+	}
+}
@@ -0,0 +1,305 @@
+use std::borrow::Cow;
+use std::str::from_utf8;
+use sql::crypto::{base64_decode, base64_encode, from_hex, to_hex};
+
+///An owned column value, tagged with its SQL storage class.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+	Null,
+	Integer(i64),
+	Real(f64),
+	Text(String),
+	Blob(Vec<u8>)
+}
+
+///A column value borrowed from a row, tagged with its SQL storage class.
+///
+///Text is kept as raw bytes, because the database does not guarantee it is valid UTF-8.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValueRef<'a> {
+	Null,
+	Integer(i64),
+	Real(f64),
+	Text(&'a [u8]),
+	Blob(&'a [u8])
+}
+
+impl Value {
+	///Borrow the value as a ValueRef.
+	pub fn as_value_ref(&self) -> ValueRef<'_> {
+		match *self {
+			Value::Null => ValueRef::Null,
+			Value::Integer(i) => ValueRef::Integer(i),
+			Value::Real(f) => ValueRef::Real(f),
+			Value::Text(ref s) => ValueRef::Text(s.as_bytes()),
+			Value::Blob(ref b) => ValueRef::Blob(b)
+		}
+	}
+}
+
+impl<'a> ValueRef<'a> {
+	///Copy the value into an owned Value. Invalid UTF-8 sequences in text are replaced by U+FFFD.
+	pub fn to_value(&self) -> Value {
+		match *self {
+			ValueRef::Null => Value::Null,
+			ValueRef::Integer(i) => Value::Integer(i),
+			ValueRef::Real(f) => Value::Real(f),
+			ValueRef::Text(t) => Value::Text(String::from_utf8_lossy(t).into_owned()),
+			ValueRef::Blob(b) => Value::Blob(b.to_vec())
+		}
+	}
+
+	///Return true if the value is NULL.
+	pub fn is_null(&self) -> bool {
+		matches!(*self, ValueRef::Null)
+	}
+
+	///Convert the value to an i64 the way the database does: reals are truncated,
+	///text and blobs are parsed from their leading numeric prefix, NULL is 0.
+	pub fn as_long(&self) -> i64 {
+		match *self {
+			ValueRef::Null => 0,
+			ValueRef::Integer(i) => i,
+			ValueRef::Real(f) => f as i64,
+			ValueRef::Text(t) | ValueRef::Blob(t) => parse_prefix(t).0
+		}
+	}
+
+	///Convert the value to an f64 the way the database does: text and blobs are parsed from their leading numeric prefix,
+	///NULL is 0.0.
+	pub fn as_double(&self) -> f64 {
+		match *self {
+			ValueRef::Null => 0.0,
+			ValueRef::Integer(i) => i as f64,
+			ValueRef::Real(f) => f,
+			ValueRef::Text(t) | ValueRef::Blob(t) => parse_prefix(t).1
+		}
+	}
+
+	///Convert the value to text. Numbers are formatted, NULL is "", invalid UTF-8 sequences are replaced by U+FFFD.
+	pub fn as_text(&self) -> Cow<'a, str> {
+		match *self {
+			ValueRef::Null => Cow::Borrowed(""),
+			ValueRef::Integer(i) => Cow::Owned(i.to_string()),
+			ValueRef::Real(f) => Cow::Owned(format_real(f)),
+			ValueRef::Text(t) | ValueRef::Blob(t) => String::from_utf8_lossy(t)
+		}
+	}
+
+	///Convert the value to bytes. Numbers are formatted as text, NULL is empty.
+	pub fn as_bytes(&self) -> Cow<'a, [u8]> {
+		match *self {
+			ValueRef::Null => Cow::Borrowed(&[]),
+			ValueRef::Integer(i) => Cow::Owned(i.to_string().into_bytes()),
+			ValueRef::Real(f) => Cow::Owned(format_real(f).into_bytes()),
+			ValueRef::Text(t) | ValueRef::Blob(t) => Cow::Borrowed(t)
+		}
+	}
+}
+
+impl<'a> From<&'a Value> for ValueRef<'a> {
+	fn from(v : &'a Value) -> ValueRef<'a> {
+		v.as_value_ref()
+	}
+}
+
+macro_rules! value_from {
+	($($t:ty => $v:ident $(as $c:ty)*),*) => {
+		$(impl From<$t> for Value {
+			fn from(v : $t) -> Value {
+				Value::$v(v $(as $c)*)
+			}
+		})*
+	}
+}
+
+value_from!(i8 => Integer as i64, i16 => Integer as i64, i32 => Integer as i64, i64 => Integer, u8 => Integer as i64,
+			u16 => Integer as i64, u32 => Integer as i64, bool => Integer as i64, f32 => Real as f64, f64 => Real,
+			String => Text, Vec<u8> => Blob);
+
+impl<'a> From<&'a str> for Value {
+	fn from(v : &'a str) -> Value {
+		Value::Text(v.to_string())
+	}
+}
+
+impl<'a> From<&'a [u8]> for Value {
+	fn from(v : &'a [u8]) -> Value {
+		Value::Blob(v.to_vec())
+	}
+}
+
+impl<'a, T : Clone + Into<Value>> From<&'a T> for Value {
+	fn from(v : &'a T) -> Value {
+		v.clone().into()
+	}
+}
+
+impl<T : Into<Value>> From<Option<T>> for Value {
+	fn from(v : Option<T>) -> Value {
+		v.map_or(Value::Null, |v| v.into())
+	}
+}
+
+///How blobs are written in text formats such as CSV.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlobEncoding {
+	///Lower case hexadecimal digits, read in either case.
+	Hex,
+	///Standard base64 with padding, read with or without padding.
+	Base64
+}
+
+impl BlobEncoding {
+	///Encode bytes as text.
+	pub fn encode(&self, data : &[u8]) -> String {
+		match *self {
+			BlobEncoding::Hex => to_hex(data),
+			BlobEncoding::Base64 => base64_encode(data)
+		}
+	}
+
+	///Decode a text, None if it is not valid in the encoding.
+	pub fn decode(&self, text : &str) -> Option<Vec<u8>> {
+		match *self {
+			BlobEncoding::Hex => from_hex(text),
+			BlobEncoding::Base64 => base64_decode(text)
+		}
+	}
+}
+
+///An owned copy of a result row, detached from the Statement that produced it.
+///
+///Unlike a Row, an OwnedRow can be kept after the cursor has moved, compared, or sent to another thread.
+///Getters follow the conventions of the Row getters.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct OwnedRow {
+	values : Vec<Value>
+}
+
+impl OwnedRow {
+	///Create a row from its column values.
+	pub fn new(values : Vec<Value>) -> OwnedRow {
+		OwnedRow { values }
+	}
+
+	///Return the number of columns in the row.
+	pub fn len(&self) -> usize {
+		self.values.len()
+	}
+
+	///Return true if the row has no column.
+	pub fn is_empty(&self) -> bool {
+		self.values.is_empty()
+	}
+
+	///Return the column value with index <i>column_index</i>, the first column is 0. Returns None if out of range.
+	pub fn get(&self, column_index : i32) -> Option<&Value> {
+		if column_index < 0 { return None; }
+		self.values.get(column_index as usize)
+	}
+
+	///Return all the column values of the row.
+	pub fn values(&self) -> &[Value] {
+		&self.values
+	}
+
+	///Consume the row and return its column values.
+	pub fn into_values(self) -> Vec<Value> {
+		self.values
+	}
+
+	///Retrieve the column value as int with index <i>column_index</i>, the first column is 0.
+	pub fn get_int(&self, column_index : i32) -> i32 {
+		self.value_ref(column_index).as_long() as i32
+	}
+
+	///Retrieve the column value as i64 with index <i>column_index</i>, the first column is 0.
+	pub fn get_long(&self, column_index : i32) -> i64 {
+		self.value_ref(column_index).as_long()
+	}
+
+	///Retrieve the column value as float with index <i>column_index</i>, the first column is 0.
+	pub fn get_float(&self, column_index : i32) -> f32 {
+		self.value_ref(column_index).as_double() as f32
+	}
+
+	///Retrieve the column value as double with index <i>column_index</i>, the first column is 0.
+	pub fn get_double(&self, column_index : i32) -> f64 {
+		self.value_ref(column_index).as_double()
+	}
+
+	///Retrieve the column value as String with index <i>column_index</i>, the first column is 0.
+	///If column value is NULL, returns "NULL"
+	pub fn get_string(&self, column_index : i32) -> String {
+		if self.is_null(column_index) { return "NULL".to_string(); }
+		self.value_ref(column_index).as_text().into_owned()
+	}
+
+	///Retrieve the column value as an array of bytes with index <i>column_index</i>, the first column is 0.
+	///If column value is NULL, returns ['N', 'U', 'L', 'L']
+	pub fn get_blob(&self, column_index : i32) -> Vec<u8> {
+		if self.is_null(column_index) { return vec![78, 85, 76, 76]; }
+		self.value_ref(column_index).as_bytes().into_owned()
+	}
+
+	///Return true if the column value with index <i>column_index</i> is NULL, the first column is 0.
+	pub fn is_null(&self, column_index : i32) -> bool {
+		self.value_ref(column_index).is_null()
+	}
+
+	fn value_ref(&self, column_index : i32) -> ValueRef<'_> {
+		match self.get(column_index) { Some(v) => v.as_value_ref(), None => ValueRef::Null }
+	}
+}
+
+//Parse the leading numeric prefix of a text, as an integer and as a real.
+pub fn parse_prefix(t : &[u8]) -> (i64, f64) {
+	let s = match from_utf8(t) { Ok(s) => s, Err(e) => from_utf8(&t[..e.valid_up_to()]).unwrap_or("") };
+	let s = s.trim_start();
+	let b = s.as_bytes();
+	let mut end = 0;
+	if end < b.len() && (b[end] == b'+' || b[end] == b'-') { end += 1; }
+	while end < b.len() && b[end].is_ascii_digit() { end += 1; }
+	let int_end = end;
+	if end < b.len() && b[end] == b'.' {
+		end += 1;
+		while end < b.len() && b[end].is_ascii_digit() { end += 1; }
+	}
+	if end < b.len() && (b[end] == b'e' || b[end] == b'E') {
+		let mut exp = end + 1;
+		if exp < b.len() && (b[exp] == b'+' || b[exp] == b'-') { exp += 1; }
+		if exp < b.len() && b[exp].is_ascii_digit() {
+			while exp < b.len() && b[exp].is_ascii_digit() { exp += 1; }
+			end = exp;
+		}
+	}
+	let real = s[..end].parse::<f64>().unwrap_or(0.0);
+	let int = if int_end == end { s[..end].parse::<i64>().unwrap_or(real as i64) } else { real as i64 };
+	(int, real)
+}
+
+//Format a real the way SQLite renders it as text, with 15 significant digits (printf "%!.15g"): a decimal point is kept
+//for integral values, and the exponent form is used below 1e-4 and from 1e15.
+pub fn format_real(f : f64) -> String {
+	if f.is_infinite() { return if f > 0.0 { "Inf".to_string() } else { "-Inf".to_string() }; }
+	if f.is_nan() { return "NaN".to_string(); }
+	if f == 0.0 { return "0.0".to_string(); }
+	let e = format!("{:.14e}", f.abs());
+	let (mantissa, exp) = e.split_at(e.find('e').unwrap_or(e.len()));
+	let exp : i32 = exp.get(1..).and_then(|x| x.parse().ok()).unwrap_or(0);
+	let digits = mantissa.replace('.', "");
+	let digits = digits.trim_end_matches('0');
+	let sign = if f < 0.0 { "-" } else { "" };
+	if !(-4..15).contains(&exp) {
+		let rest = if digits.len() > 1 { &digits[1..] } else { "0" };
+		format!("{}{}.{}e{}{:02}", sign, &digits[..1], rest, if exp < 0 { '-' } else { '+' }, exp.abs())
+	} else if exp < 0 {
+		format!("{}0.{}{}", sign, "0".repeat((-exp - 1) as usize), digits)
+	} else {
+		let point = exp as usize + 1;
+		let int = if digits.len() > point { digits[..point].to_string() } else { format!("{:0<width$}", digits, width = point) };
+		let frac = if digits.len() > point { &digits[point..] } else { "0" };
+		format!("{}{}.{}", sign, int, frac)
+	}
+}
@@ -6,6 +6,12 @@ pub struct Properties {
 	props : HashMap<String, String>
 }
 
+impl Default for Properties {
+	fn default() -> Properties {
+		Properties::new()
+	}
+}
+
 impl Properties {
 	/// Create an empty properties list.
 	pub fn new() -> Properties {
@@ -56,47 +62,47 @@ impl Properties {
 	/// Load properties from an UTF-8 input character stream (for example, but not restricted to, file).
 	///
 	/**Reader is already buffered during reading; so before invoking this method,
-	there is no need for any additional BufferedReader to wrap around the reader.
-	This method load properties from an input character stream processed in term of lines according to the following rules&nbsp;:
-	<ul>
-	<li>leading white-spaces (Unicode definition) are skipped.</li>
-	<li>line that contains only white-spaces is considered blank and is ignored</li>
-	<li>if the the first non-white character is a '#' or '!', line is considered as a comment and is skipped.</li>
-	<li>the key contains all of the characters in the line starting with the first non-white space character and up to,
-	but not including, the first '=', ':' or white-space character other than a line terminator</li>
-	<li>after the key, the first occurrence of '=' or ':' and all white-spaces are skipped</li>
-	<li>the value contains all the remaining characters others than a line terminator</li>
-	</ul>
-	<u>Notes&nbsp;:</u>
-	<ul>
-	<li>a line terminator is either '\n' or '\r\n'</li>
-	<li>the following characters can be escaped&nbsp;: tab '\t', form feed '\f', line terminators '\r' or '\n'</li>
-	<li>'\' before a non-valid escape character is not an error, the backslash is simply dropped;
-	useful to escape '\\\', '\ ', '\\#', '\\!', '\=', '\\:'</li>
-	<li>a key-element pair may be spread out across several adjacent lines by terminating the line with a backslash character '\'</li>
-	<pre class='rust fn'>targetCities=\
+    there is no need for any additional BufferedReader to wrap around the reader.
+    This method load properties from an input character stream processed in term of lines according to the following rules&nbsp;:
+    <ul>
+    <li>leading white-spaces (Unicode definition) are skipped.</li>
+    <li>line that contains only white-spaces is considered blank and is ignored</li>
+    <li>if the the first non-white character is a '#' or '!', line is considered as a comment and is skipped.</li>
+    <li>the key contains all of the characters in the line starting with the first non-white space character and up to,
+    but not including, the first '=', ':' or white-space character other than a line terminator</li>
+    <li>after the key, the first occurrence of '=' or ':' and all white-spaces are skipped</li>
+    <li>the value contains all the remaining characters others than a line terminator</li>
+    </ul>
+    <u>Notes&nbsp;:</u>
+    <ul>
+    <li>a line terminator is either '\n' or '\r\n'</li>
+    <li>the following characters can be escaped&nbsp;: tab '\t', form feed '\f', line terminators '\r' or '\n'</li>
+    <li>'\' before a non-valid escape character is not an error, the backslash is simply dropped;
+    useful to escape '\\\', '\ ', '\\#', '\\!', '\=', '\\:'</li>
+    <li>a key-element pair may be spread out across several adjacent lines by terminating the line with a backslash character '\'</li>
+    <pre class='rust fn'>targetCities=\
         Detroit, \
         Chicago, \
         Los Angeles</pre>
-	is equivalent to <pre class='rust fn'>targetCities=Detroit, Chicago, Los Angeles</pre>
-	</ul>
-	*/
+    is equivalent to <pre class='rust fn'>targetCities=Detroit, Chicago, Los Angeles</pre>
+    </ul>
+    */
 	pub fn load<T : Read>(&mut self,  reader : T)-> Option<Error> {
 		let mut multi = String::new();
 		for line in BufReader::new(reader).lines() {
 			match line {
 				Ok(l) => {
-					let mut l_str=(&l[..]).trim_left();
+					let mut l_str=l[..].trim_start();
 					if l_str.starts_with("#") || l_str.starts_with("!") { continue; } // Comment line
 
 					if l_str.ends_with("\r") { l_str=&l_str[..l_str.len()-1]; } // Line ends with '\r'
 
-					if l_str.len()==0 { continue; } //Empty line
+					if l_str.is_empty() { continue; } //Empty line
 
 					// line finishing with an odd number of '\' is a multiline
 					let mut esc = false;
 					for c in l_str.chars().rev() {
-						if c=='\\' { esc=!esc; } else { break; }
+						if c=='\\' { esc = !esc; } else { break; }
 					}
 					if esc { multi.push_str(&l_str[..l_str.len()-1]); continue; }
 					if !multi.is_empty() { multi.push_str(l_str); l_str=multi.as_ref(); }
@@ -111,9 +117,9 @@ impl Properties {
 					}
 					let key = decode_chars(&l_str[..idx]);
 					
-					l_str = &l_str[idx..]; l_str = l_str.trim_left();
+					l_str = &l_str[idx..]; l_str = l_str.trim_start();
 					if l_str.starts_with("=") || l_str.starts_with(":") { 	l_str = &l_str[1..];
-																			l_str = l_str.trim_left(); }					
+																			l_str = l_str.trim_start(); }					
 					self.props.insert(key, decode_chars(l_str));
 				}
 				Err(e) => { return Some(e); }
@@ -129,16 +135,11 @@ impl Properties {
 	///title="std::io::Reader">Reader</a>&gt;</b></code> method.
 	pub fn store<T : Write>(&mut self,  writer : T) -> Option<Error> {
 		let mut buf = BufWriter::new(writer);
-		for kv in self.props.iter() {
-			match kv {
-				(k,v) => {  let mut line = encode_chars(k.as_ref(), true);
-							line.push('=');
-							line.push_str(encode_chars(v.as_ref(), false).as_ref()); line.push('\n');
-							match buf.write(line.as_bytes()) 	{ 	Ok(_)  => continue,
-															Err(e) => { return Some(e); }
-														}
-						}
-			}
+		for (k,v) in self.props.iter() {
+			let mut line = encode_chars(k.as_ref(), true);
+			line.push('=');
+			line.push_str(encode_chars(v.as_ref(), false).as_ref()); line.push('\n');
+			if let Err(e) = buf.write_all(line.as_bytes()) { return Some(e); }
 		}
 		None
 	}
@@ -186,7 +187,7 @@ fn decode_chars(s : &str) -> String {
 	buf
 }
 
-fn encode_chars<'a>(s : &str, is_key : bool) -> String {
+fn encode_chars(s : &str, is_key : bool) -> String {
 	let mut buf = s.to_string();
 	let mut esc=true;
 	let mut idx = 0;
@@ -0,0 +1,55 @@
+extern crate rustic;
+
+use std::fs::remove_file;
+use rustic::sql::{AttachTarget, Connection, OpenMode};
+use rustic::sql::DbType::SQLite3;
+
+//Split data across two files joined with ATTACH, introspect the attached schema, then reach the attach limit.
+fn main() {
+	for f in &["test-attach-a.db", "test-attach-b.db"] { let _ = remove_file(f); }
+	let db = match Connection::new(SQLite3, "test-attach-a.db") {
+		Ok(db) => db,
+		Err(e) => { println!("{}", e); return; }
+	};
+	if let Err(e) = db.execute_batch("CREATE TABLE customer(id INTEGER PRIMARY KEY, name TEXT); INSERT INTO customer VALUES (1, 'Ada'), (2, 'Alan')") {
+		println!("{}", e);
+	}
+	println!("read-write, no file: {:?}", db.attach(AttachTarget::File("test-attach-b.db"), "sales", OpenMode::ReadWrite).map_err(|e| e.to_string()));
+	println!("create: {:?}", db.attach(AttachTarget::File("test-attach-b.db"), "sales", OpenMode::Create));
+	let setup = "CREATE TABLE sales.orders(id INTEGER PRIMARY KEY, customer INTEGER, total REAL);
+				 CREATE VIEW sales.big AS SELECT * FROM orders WHERE total > 100;
+				 INSERT INTO sales.orders(customer, total) VALUES (1, 250), (1, 20), (2, 75)";
+	if let Err(e) = db.execute_batch(setup) { println!("{}", e); }
+	let join = "SELECT c.name, sum(o.total) FROM main.customer c JOIN sales.orders o ON o.customer = c.id GROUP BY c.name ORDER BY c.name";
+	match db.prepare_statement(join).and_then(|mut s| s.fetch_all()) {
+		Ok(rs) => for r in &rs { println!("{}: {}", r.get_string(0), r.get_double(1)); },
+		Err(e) => println!("{}", e)
+	}
+	println!("name in use: {:?}", db.attach(AttachTarget::Memory, "SALES", OpenMode::Create).map_err(|e| e.kind()));
+	println!("main: {:?}", db.attach(AttachTarget::Memory, "main", OpenMode::Create).map_err(|e| e.kind()));
+	println!("read-only: {:?}", db.attach(AttachTarget::File("test-attach-b.db"), "archive", OpenMode::ReadOnly));
+	println!("write to read-only: {:?}", db.execute_batch("DELETE FROM archive.orders").map_err(|e| e.to_string()));
+	for d in db.databases().unwrap_or_default() {
+		println!("{} {:?} read-only {} attached {}", d.name(), d.file().map(|f| f.rsplit('/').next().unwrap_or(f)), d.is_read_only(), d.is_attached());
+	}
+	match db.introspector() {
+		Ok(i) => {
+			println!("schemas {:?}", i.schemas());
+			println!("sales tables {:?}, views {:?}", i.table_names(Some("sales")), i.views(Some("sales")).map(|v| v.iter().map(|v| v.name().to_string()).collect::<Vec<_>>()));
+			println!("orders columns {:?}", i.table(Some("sales"), "orders").map(|t| t.map(|t| t.columns().iter().map(|c| c.name().to_string()).collect::<Vec<_>>())));
+			println!("unknown views {:?}", i.views(Some("nowhere")).map_err(|e| e.kind()));
+		},
+		Err(e) => println!("{}", e)
+	}
+	println!("detach: {:?}, again: {:?}, main: {:?}", db.detach("archive"), db.detach("archive").map_err(|e| e.kind()), db.detach("main").map_err(|e| e.kind()));
+	let mut n = 0;
+	let limit = loop {
+		match db.attach(AttachTarget::Memory, &format!("scratch{}", n), OpenMode::Create) {
+			Ok(()) => n += 1,
+			Err(e) => break e
+		}
+	};
+	println!("{} more attached: {:?} {}", n, limit.kind(), limit);
+	drop(db);
+	for f in &["test-attach-a.db", "test-attach-b.db"] { let _ = remove_file(f); }
+}
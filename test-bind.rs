@@ -0,0 +1,46 @@
+extern crate rustic;
+
+use rustic::sql::Connection;
+use rustic::sql::DbType::SQLite3;
+
+//Bind texts and blobs holding NUL characters, copied and borrowed, read them back by length with the strict and lossy
+//getters, then execute again a statement whose borrowed values have been rebound. The SQL length() of a text and
+//expanded_sql stop at the first NUL, as SQLite does.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	if let Err(e) = db.execute_batch("CREATE TABLE t(id INTEGER PRIMARY KEY, s TEXT, b BLOB)") { println!("{}", e); return; }
+	let text = String::from("a\0b\0");
+	let blob = vec![0u8, 1, 0, 255];
+	let invalid = [b'o', b'k', 0xff, 0xfe];
+	{
+		let mut st = match db.prepare_statement("INSERT INTO t(s, b) VALUES (?, ?)") { Ok(st) => st, Err(e) => { println!("{}", e); return } };
+		let errors = vec![st.set_string(1, "copied\0text"), st.set_blob(2, &[0, 0, 7])];
+		for e in errors.into_iter().flatten() { println!("{}", e); }
+		if let Err(e) = st.execute_update() { println!("{}", e); }
+		let errors = vec![st.set_string_ref(1, &text), st.set_blob_ref(2, &blob)];
+		for e in errors.into_iter().flatten() { println!("{}", e); }
+		if let Err(e) = st.execute_update() { println!("{}", e); }
+		//The text borrowed above stays bound for the second column, the first is rebound.
+		if let Some(e) = st.set_blob_ref(1, &invalid) { println!("{}", e); }
+		if let Err(e) = st.execute_update() { println!("{}", e); }
+	}
+	let mut st = match db.prepare_statement("SELECT s, b, length(s), length(b), typeof(s) FROM t ORDER BY id") { Ok(st) => st, Err(e) => { println!("{}", e); return } };
+	let mut rows = st.query();
+	while let Some(r) = rows.next() {
+		match r {
+			Ok(r) => println!("{:?} / {:?} / {:?} / {:?} / {:?}, lengths {} {}, {}", r.get_str(0).map_err(|e| e.kind()), r.get_str_lossy(0), r.get_string(0),
+							  r.get_bytes(0), r.get_bytes(1), r.get_long(2), r.get_long(3), r.get_string(4)),
+			Err(e) => println!("{}", e)
+		}
+	}
+	drop(rows);
+	let mut st = match db.prepare_statement("SELECT count(*) FROM t WHERE s = ? OR b = ?") { Ok(st) => st, Err(e) => { println!("{}", e); return } };
+	for &(s, b) in &[("a\0b\0", &[9u8][..]), ("a", &[0, 0, 7][..]), ("a\0b", &[0, 1, 0, 255][..])] {
+		let errors = vec![st.set_string_ref(1, s), st.set_blob_ref(2, b)];
+		for e in errors.into_iter().flatten() { println!("{}", e); }
+		match st.fetch_all() {
+			Ok(rs) => println!("{:?} or {:?}: {} row(s), expanded {:?}", s, b, rs.row(0).map_or(0, |r| r.get_long(0)), st.expanded_sql()),
+			Err(e) => println!("{}", e)
+		}
+	}
+}
@@ -0,0 +1,39 @@
+extern crate rustic;
+
+use rustic::sql::{Connection, Select, Insert, Update, col, func};
+use rustic::sql::DbType::{SQLite3, PostgreSQL, MySQL};
+
+//Build queries for SQLite, PostgreSQL and MySQL, then run them on an in-memory SQLite database.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	match db.execute_batch("CREATE TABLE users(id INTEGER PRIMARY KEY, name TEXT UNIQUE, visits INT DEFAULT 0);") {
+		Ok(()) => (),
+		Err(e) => println!("{}", e)
+	}
+	let insert = Insert::into("users").columns(&["name"]).values(vec!["ann".into()]).values(vec!["bob".into()])
+								  .on_conflict(&["name"]).do_nothing();
+	let update = Update::table("users").set("visits", col("visits") + 1).filter(col("name").eq("ann"));
+	let select = Select::from("users").columns(&["name", "visits"]).filter(col("name").in_list(vec!["ann", "bob"]))
+								  .order_by(col("name")).limit(10);
+	for db_type in &[SQLite3, PostgreSQL, MySQL] {
+		for q in &[insert.build(db_type.clone()), update.build(db_type.clone()), select.build(db_type.clone())] {
+			match *q {
+				Ok(ref q) => println!("{:?}: {} {:?}", db_type, q, q.params()),
+				Err(ref e) => println!("{}", e)
+			}
+		}
+	}
+	for q in &[insert.build(SQLite3), insert.build(SQLite3), update.build(SQLite3)] {
+		match q.as_ref().map_err(|e| e.to_string()).and_then(|q| q.prepare(&db).and_then(|mut st| st.execute_update()).map_err(|e| e.to_string())) {
+			Ok(n) => println!("{} row(s) changed", n),
+			Err(e) => println!("{}", e)
+		}
+	}
+	let count = Select::from("users").expr_as(func("count", vec![col("*")]), "n");
+	for q in &[select.build(SQLite3), count.build(SQLite3)] {
+		match q.as_ref().map_err(|e| e.to_string()).and_then(|q| q.prepare(&db).and_then(|mut st| st.fetch_all()).map_err(|e| e.to_string())) {
+			Ok(rs) => for r in rs.iter() { println!("{:?}", (0..r.column_count()).map(|i| r.get_string(i)).collect::<Vec<String>>()) },
+			Err(e) => println!("{}", e)
+		}
+	}
+}
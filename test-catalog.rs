@@ -0,0 +1,77 @@
+extern crate rustic;
+
+use rustic::sql::Connection;
+use rustic::sql::DbType::SQLite3;
+use rustic::sql::catalog::Column;
+
+fn show(c : &Column) -> String {
+	format!("{} {:?}{}{}{}{}{}", c.name(), c.decl_type(), if c.is_not_null() { " NOT NULL" } else { "" },
+			c.default().map_or(String::new(), |d| format!(" DEFAULT {}", d)), if c.primary_key() > 0 { format!(" PK{}", c.primary_key()) } else { String::new() },
+			if c.is_hidden() { " hidden" } else { "" }, c.generated().map_or(String::new(), |g| format!(" {:?}", g)))
+}
+
+//Describe a schema holding rowid, WITHOUT ROWID, STRICT and virtual tables, composite and self-referencing foreign keys,
+//unique, partial and expression indexes, generated columns, views, triggers with quoted names, an attached
+//database and the temp schema before and after its first table.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	if let Err(e) = db.execute_batch("CREATE TABLE parent(a INTEGER, b TEXT COLLATE NOCASE, PRIMARY KEY(a, b)) WITHOUT ROWID;
+									  CREATE TABLE \"child \"\"x\"\"\"(id INTEGER PRIMARY KEY, pa INTEGER NOT NULL, pb TEXT DEFAULT 'none',
+										  up INTEGER REFERENCES \"child \"\"x\"\"\" ON DELETE SET NULL,
+										  total REAL GENERATED ALWAYS AS (pa * 2) STORED, half REAL AS (pa / 2.0),
+										  code TEXT UNIQUE, noted,
+										  FOREIGN KEY(pa, pb) REFERENCES parent(a, b) ON UPDATE CASCADE ON DELETE RESTRICT);
+									  CREATE INDEX child_partial ON \"child \"\"x\"\"\"(pb DESC, pa) WHERE pa > 0;
+									  CREATE UNIQUE INDEX child_expr ON \"child \"\"x\"\"\"(lower(code) COLLATE BINARY);
+									  CREATE TABLE s(i INT, t TEXT) STRICT;
+									  CREATE VIRTUAL TABLE v USING fts5(body);
+									  CREATE VIEW totals(pa, n) AS SELECT pa, count(*) FROM \"child \"\"x\"\"\" GROUP BY pa;
+									  CREATE TRIGGER \"before update\" INSTEAD OF DELETE ON totals BEGIN SELECT 1; END;
+									  CREATE TRIGGER t_ins AFTER INSERT ON s BEGIN SELECT 1; END;
+									  CREATE TRIGGER t_upd UPDATE OF t ON s BEGIN SELECT 1; END;
+									  ATTACH ':memory:' AS aux; CREATE TABLE aux.other(x PRIMARY KEY)") {
+		println!("{}", e); return;
+	}
+	let intro = match db.introspector() { Ok(i) => i, Err(e) => { println!("{}", e); return } };
+	println!("schemas: {:?}", intro.schemas());
+	println!("tables: {:?}", intro.table_names(None));
+	println!("aux tables: {:?}", intro.table_names(Some("aux")));
+	println!("unknown schema: {:?}", intro.table_names(Some("nowhere")).map_err(|e| e.kind()));
+	println!("missing table: {:?}, view as table: {:?}", intro.table(None, "missing").map(|t| t.is_none()), intro.table(None, "totals").map(|t| t.is_none()));
+	let tables = match intro.tables(None) { Ok(t) => t, Err(e) => { println!("{}", e); return } };
+	for t in &tables {
+		println!("{}.{}: without rowid {}, strict {}, virtual {}, primary key {:?}", t.schema(), t.name(), t.is_without_rowid(), t.is_strict(),
+				 t.is_virtual(), t.primary_key());
+		for c in t.columns() { println!("  column {}", show(c)); }
+		for i in t.indexes() {
+			println!("  index {} on {}: unique {}, {:?}, partial {}, keys {:?}, sql {}", i.name(), i.table(), i.is_unique(), i.origin(), i.is_partial(),
+					 i.columns().iter().map(|k| (k.name(), k.is_descending(), k.collation())).collect::<Vec<_>>(), i.sql().is_some());
+		}
+		for k in t.foreign_keys() {
+			println!("  foreign key {:?} -> {} {:?}, on update {}, on delete {}", k.columns(), k.referenced_table(), k.referenced_columns(),
+					 k.on_update().as_sql(), k.on_delete().as_sql());
+		}
+	}
+	match intro.table(None, "child \"x\"") {
+		Ok(Some(t)) => println!("lookup by quoted name: {:?}, column code {:?}, column missing {:?}", t.name(), t.column("code").map(show), t.column("missing")),
+		Ok(None) => println!("lookup by quoted name: none"),
+		Err(e) => println!("{}", e)
+	}
+	match intro.views(None) {
+		Ok(views) => for v in views { println!("view {}.{}: {:?}, sql {}", v.schema(), v.name(), v.columns().iter().map(show).collect::<Vec<_>>(), v.sql().is_some()); },
+		Err(e) => println!("{}", e)
+	}
+	match intro.triggers(None) {
+		Ok(triggers) => for t in triggers { println!("trigger {:?} on {}: {:?} {:?}", t.name(), t.table(), t.timing(), t.event()); },
+		Err(e) => println!("{}", e)
+	}
+	match intro.table(Some("aux"), "other") {
+		Ok(Some(t)) => println!("aux.other: {:?}, indexes {:?}", t.columns().iter().map(show).collect::<Vec<_>>(),
+								 t.indexes().iter().map(|i| (i.name(), i.origin())).collect::<Vec<_>>()),
+		Ok(None) => println!("aux.other: none"),
+		Err(e) => println!("{}", e)
+	}
+	println!("temp before any temporary object: {:?}, {:?}", intro.table_names(Some("temp")), intro.triggers(Some("temp")).map(|t| t.len()));
+	if let Err(e) = db.execute_batch("CREATE TEMP TABLE scratch(k TEXT PRIMARY KEY) WITHOUT ROWID") { println!("{}", e); return; }
+	println!("temp after: {:?}, scratch {:?}", intro.table_names(Some("temp")), intro.table(Some("temp"), "scratch").map(|t| t.map(|t| t.is_without_rowid())));
+}
@@ -0,0 +1,80 @@
+extern crate rustic;
+
+use rustic::sql::{ColumnData, Connection};
+use rustic::sql::DbType::SQLite3;
+
+fn show(data : &ColumnData) -> String {
+	match *data {
+		ColumnData::Integer(ref v, ref n) => format!("Integer {:?} nulls {:?}", v, (0..v.len()).filter(|&i| n.is_null(i)).collect::<Vec<_>>()),
+		ColumnData::Real(ref v, ref n) => format!("Real {:?} nulls {:?}", v, (0..v.len()).filter(|&i| n.is_null(i)).collect::<Vec<_>>()),
+		ColumnData::Text(ref v) => format!("Text {:?}", v),
+		ColumnData::Blob(ref v) => format!("Blob {:?}", v)
+	}
+}
+
+//Fetch results in column batches: sizes around the number of rows and the 64 values of a null bitmap word, NULL values,
+//types decided by the declared type or by the first non NULL value whatever the batch size, values of another type
+//converted, and values that cannot be converted rejected.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	if let Err(e) = db.execute_batch("CREATE TABLE n(i INTEGER, r REAL);
+									  WITH RECURSIVE s(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM s WHERE x < 130)
+									  INSERT INTO n SELECT CASE WHEN x % 10 = 0 THEN NULL ELSE x END, x / 2.0 FROM s") {
+		println!("{}", e); return;
+	}
+	for &size in &[0, 1, 64, 65, 129, 130, 131, 1000] {
+		let mut st = match db.prepare_statement("SELECT i, r FROM n ORDER BY rowid") { Ok(st) => st, Err(e) => { println!("{}", e); return } };
+		let mut lens = Vec::new();
+		let (mut nulls, mut sum, mut words) = (0, 0i64, 0);
+		for b in st.fetch_columns(size) {
+			match b {
+				Ok(b) => {
+					lens.push(b.len());
+					if let Some(&ColumnData::Integer(ref v, ref n)) = b.column(0) {
+						nulls += n.null_count();
+						words += n.words().len();
+						sum += v.iter().enumerate().filter(|&(i, _)| !n.is_null(i)).map(|(_, x)| x).sum::<i64>();
+					}
+				}
+				Err(e) => println!("{}", e)
+			}
+		}
+		let shown = if lens.len() > 4 { format!("{} batches, last {:?}", lens.len(), lens.last()) } else { format!("{:?}", lens) };
+		println!("batch size {}: {}, {} nulls, sum {}, {} bitmap words", size, shown, nulls, sum, words);
+	}
+	let mut st = match db.prepare_statement("SELECT i FROM n WHERE i IS NULL LIMIT 70") { Ok(st) => st, Err(e) => { println!("{}", e); return } };
+	for b in st.fetch_columns(100).flatten() {
+		let f = &b.schema().fields()[0];
+		println!("all NULL: {:?} {:?}, {} of {} NULL", f.column_type(), f.decl_type(), b.column(0).map_or(0, |c| (0..c.len()).filter(|&i| c.is_null(i)).count()), b.len());
+	}
+	if let Err(e) = db.execute_batch("CREATE TABLE m(i INTEGER, r REAL, t TEXT, b BLOB, x);
+									  INSERT INTO m VALUES (1, 1, 'one', x'01', NULL), ('2', '2.5', 3, 4, NULL), (3.0, ' 4 ', 4.5, 'text', 7),
+									  (NULL, NULL, NULL, NULL, 8.0), (' 12 ', 7, x'41', 5.5, '9'), (9223372036854775807, 1e308, '', x'', 10)") {
+		println!("{}", e); return;
+	}
+	for &size in &[6, 1, 2] {
+		let mut st = match db.prepare_statement("SELECT i, r, t, b, x, i + 0 AS e FROM m ORDER BY rowid") { Ok(st) => st, Err(e) => { println!("{}", e); return } };
+		println!("mixed types, batches of {}:", size);
+		let mut columns : Vec<Vec<String>> = Vec::new();
+		for b in st.fetch_columns(size) {
+			match b {
+				Ok(b) => {
+					if columns.is_empty() {
+						println!("  {:?}", b.schema().fields().iter().map(|f| (f.name().to_string(), f.column_type())).collect::<Vec<_>>());
+						columns.resize(b.columns().len(), Vec::new());
+					}
+					for (c, data) in columns.iter_mut().zip(b.columns()) { c.push(show(data)); }
+				}
+				Err(e) => println!("{}", e)
+			}
+		}
+		if size == 6 { for c in &columns { println!("  {}", c.join(" | ")); } } else { println!("  x: {}", columns[4].join(" | ")); }
+	}
+	//The types of i and r come from the first row, the values of the last row cannot be converted to them.
+	for &(i, r) in &[("'eight'", "2.5"), ("x'ff'", "2.5"), ("9.5", "2.5"), ("1e19", "2.5"), ("2", "'nan?'"), ("2", "x'00'")] {
+		let sql = format!("SELECT 1 AS i, 1.5 AS r UNION ALL SELECT 2, 2.5 UNION ALL SELECT {}, {}", i, r);
+		let mut st = match db.prepare_statement(&sql) { Ok(st) => st, Err(e) => { println!("{}", e); return } };
+		let batches : Vec<Result<usize, String>> = st.fetch_columns(2).map(|b| b.map(|b| b.len()).map_err(|e| format!("{:?} {}", e.kind(), e))).collect();
+		println!("{}, {}: {:?}", i, r, batches);
+	}
+}
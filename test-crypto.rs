@@ -0,0 +1,79 @@
+//The hash functions and encodings of the network backends are private to the crate: the module is compiled in
+//this program from its source.
+#[allow(dead_code)]
+#[path = "src/sql/crypto.rs"]
+mod crypto;
+
+use crypto::*;
+
+//An RSA public key of 1024 bits, in SubjectPublicKeyInfo and PKCS#1 formats, and the encryption of "secret\0" under it
+//with the seed 1, 2 ... 20, computed and decrypted by another implementation.
+const SPKI : &str = concat!("-----BEGIN PUBLIC KEY-----\n", "MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQDcMl6VYJOq4yNlAzT9uUp4mArX\n", "3jtlkJyV8r9LfUqpNjlkGLsoPnN5nwOCxeFJRLq4Fgo7tAPHGEOTJrCwS0DdwajR\n", "WRC8Mri639BTlDpQw5Fb2aS3LxVIm7HCSvTUjqemWkm3FI+z5CAOVg0h3wx1dzlU\n", "HhYwJQm1JehScpCTDwIDAQAB\n", "-----END PUBLIC KEY-----\n");
+const PKCS1 : &str = concat!("-----BEGIN RSA PUBLIC KEY-----\n", "MIGJAoGBANwyXpVgk6rjI2UDNP25SniYCtfeO2WQnJXyv0t9Sqk2OWQYuyg+c3mf\n", "A4LF4UlEurgWCju0A8cYQ5MmsLBLQN3BqNFZELwyuLrf0FOUOlDDkVvZpLcvFUib\n", "scJK9NSOp6ZaSbcUj7PkIA5WDSHfDHV3OVQeFjAlCbUl6FJykJMPAgMBAAE=\n", "-----END RSA PUBLIC KEY-----\n");
+const CIPHERTEXT : &str = "ad2f31cfe2d3093c6affef93f8b42ff9e80c8390263bd28b197855419091de311452eb9bb33d15f8a99c92dbe4aee4a320357dd897c4707291a3ee036a73f4938d4cad5968e08cb25d1625a11abd84a67fb3da86323cbd932565d14bfb502d394e4ea1b8491552c89b519f622ef8ddff87e7046a2ad67ad0dd9d07daed2b3c25";
+
+fn check(name : &str, got : String, expected : &str) {
+	println!("{} {}", if got == expected { "ok  " } else { "FAIL" }, name);
+	if got != expected { println!("     got      {}\n     expected {}", got, expected); }
+}
+
+//Check each primitive against the known-answer vectors of its specification.
+fn main() {
+	//RFC 1321, appendix A.5.
+	for &(data, digest) in &[("", "d41d8cd98f00b204e9800998ecf8427e"),
+							 ("a", "0cc175b9c0f1b6a831c399e269772661"),
+							 ("abc", "900150983cd24fb0d6963f7d28e17f72"),
+							 ("message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+							 ("abcdefghijklmnopqrstuvwxyz", "c3fcd3d76192e4007dfb496cca67e13b"),
+							 ("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789", "d174ab98d277d9f5a5611c2c9f419d9f"),
+							 ("12345678901234567890123456789012345678901234567890123456789012345678901234567890", "57edf4a22be3c955ac49da2e2107b67a")] {
+		check(&format!("md5 {:?}", data), to_hex(&md5(data.as_bytes())), digest);
+	}
+	//FIPS 180-2, appendices A and B: one block, two blocks and a million 'a'.
+	let million = vec![b'a'; 1000000];
+	let two_blocks = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+	check("sha1 abc", to_hex(&sha1(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+	check("sha1 two blocks", to_hex(&sha1(two_blocks)), "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+	check("sha1 million", to_hex(&sha1(&million)), "34aa973cd4c4daa4f61eeb2bdbad27316534016f");
+	check("sha1 empty", to_hex(&sha1(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+	check("sha256 abc", to_hex(&sha256(b"abc")), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+	check("sha256 two blocks", to_hex(&sha256(two_blocks)), "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1");
+	check("sha256 million", to_hex(&sha256(&million)), "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0");
+	check("sha256 empty", to_hex(&sha256(b"")), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+	//RFC 4231, test cases 1, 2, 3, 4, 6 and 7 (case 5 is truncated).
+	let key4 : Vec<u8> = (1..26).collect();
+	for &(case, ref key, ref data, mac) in &[
+		(1, vec![0x0b; 20], b"Hi There".to_vec(), "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"),
+		(2, b"Jefe".to_vec(), b"what do ya want for nothing?".to_vec(), "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"),
+		(3, vec![0xaa; 20], vec![0xdd; 50], "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe"),
+		(4, key4, vec![0xcd; 50], "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b"),
+		(6, vec![0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+		 "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"),
+		(7, vec![0xaa; 131], b"This is a test using a larger than block-size key and a larger than block-size data. The key needs to be hashed before being used by the HMAC algorithm.".to_vec(),
+		 "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2")] {
+		check(&format!("hmac-sha256 case {}", case), to_hex(&hmac_sha256(key, data)), mac);
+	}
+	//The inputs of RFC 6070 with SHA-256 instead of SHA-1, the first 32 bytes of the derived key.
+	for &(password, salt, iterations, key) in &[
+		(&b"password"[..], &b"salt"[..], 1, "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"),
+		(b"password", b"salt", 2, "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"),
+		(b"password", b"salt", 4096, "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"),
+		(b"passwordPASSWORDpassword", b"saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, "348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1"),
+		(b"pass\0word", b"sa\0lt", 4096, "89b69d0516f829893c696226650a86878c029ac13ee276509d5ae58b6466a724")] {
+		check(&format!("pbkdf2-sha256 {:?} {}", String::from_utf8_lossy(password), iterations), to_hex(&pbkdf2_sha256(password, salt, iterations)), key);
+	}
+	//RFC 4648, section 10.
+	for &(data, text) in &[("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foob", "Zm9vYg=="), ("fooba", "Zm9vYmE="), ("foobar", "Zm9vYmFy")] {
+		check(&format!("base64 {:?}", data), base64_encode(data.as_bytes()), text);
+		check(&format!("base64 decode {:?}", text), base64_decode(text).map_or("None".to_string(), |d| String::from_utf8_lossy(&d).into_owned()), data);
+	}
+	check("hex", from_hex("00ff7F").map_or("None".to_string(), |b| to_hex(&b)), "00ff7f");
+	let seed : [u8; 20] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20];
+	for &(name, pem) in &[("spki", SPKI), ("pkcs1", PKCS1)] {
+		check(&format!("rsa-oaep {}", name), rsa_oaep_sha1_encrypt_seeded(pem, b"secret\0", seed).map_or("None".to_string(), |c| to_hex(&c)), CIPHERTEXT);
+	}
+	check("rsa-oaep too long", format!("{:?}", rsa_oaep_sha1_encrypt_seeded(SPKI, &[0; 87], seed)), "None");
+	check("rsa-oaep bad key", format!("{:?}", rsa_oaep_sha1_encrypt_seeded("-----BEGIN PUBLIC KEY-----\nAAAA\n", b"x", seed)), "None");
+	let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+	check("random", format!("{:?}", random_bytes(&mut a).and_then(|_| random_bytes(&mut b)).map(|_| a != b)), "Ok(true)");
+}
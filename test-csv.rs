@@ -0,0 +1,29 @@
+extern crate rustic;
+
+use std::io::stdout;
+use rustic::sql::{Connection, CsvOptions};
+use rustic::sql::DbType::SQLite3;
+
+//Import a CSV feed with a rejected record into a table, then export the table as CSV to the standard output.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	match db.execute_batch("CREATE TABLE t(i INTEGER PRIMARY KEY, t TEXT, n INT, b BLOB);") {
+		Ok(()) => (),
+		Err(e) => println!("{}", e)
+	}
+	let feed = "id,label,n,b\r\n1,one,10,0102\r\n2,\"two, \"\"quoted\"\"\",x,\r\n3,,30,\r\n";
+	match db.import_csv(feed.as_bytes(), "t", &CsvOptions::new().map("id", "i").map("label", "t").max_errors(1)) {
+		Ok(report) => {
+			println!("{} row(s) imported", report.rows());
+			for e in report.errors() { println!("{}", e); }
+		},
+		Err(e) => println!("{:?}: {}", e.kind(), e)
+	}
+	match db.prepare_statement("SELECT i,t,n,b FROM t;") {
+		Ok(mut st) => match st.write_csv(stdout(), &CsvOptions::new().delimiter(b';').null("NULL")) {
+			Ok(n) => println!("{} row(s) exported", n),
+			Err(e) => println!("{}", e)
+		},
+		Err(e) => println!("{}", e)
+	};
+}
@@ -0,0 +1,118 @@
+extern crate rustic;
+
+use std::any::Any;
+use std::cell::Cell;
+use std::io::{Error, ErrorKind, Result};
+use std::sync::Arc;
+use rustic::sql::{Connection, Driver, RawConnection, RawStatement, ValueRef, register_driver};
+use rustic::sql::DbType::{Custom, SQLite3};
+
+//A sequence backend: "NEXT" draws the next number as a generated key, "CURRENT" reads the last one drawn.
+struct SequenceDriver;
+
+struct Sequence {
+	next : Cell<i64>,
+	last : Cell<i64>,
+	changes : Cell<i64>,
+	total : Cell<i64>
+}
+
+struct SequenceStatement<'c> {
+	con : &'c Sequence,
+	sql : String,
+	text : Vec<u8>,
+	row : bool,
+	done : bool
+}
+
+impl Driver for SequenceDriver {
+	fn connect(&self, target : &str) -> Result<Box<dyn RawConnection>> {
+		let start = target.parse().map_err(|_| Error::new(ErrorKind::InvalidInput, format!("not a number: {}", target)))?;
+		Ok(Box::new(Sequence { next : Cell::new(start), last : Cell::new(0), changes : Cell::new(0), total : Cell::new(0) }))
+	}
+}
+
+impl RawConnection for Sequence {
+	fn prepare<'c>(&'c self, sql : &str) -> Result<Box<dyn RawStatement + 'c>> {
+		match sql {
+			"NEXT" | "CURRENT" => Ok(Box::new(SequenceStatement { con : self, sql : sql.to_string(), text : Vec::new(), row : false, done : false })),
+			_ => Err(Error::new(ErrorKind::InvalidInput, format!("unknown command: {}", sql)))
+		}
+	}
+
+	fn last_insert_rowid(&self) -> i64 { self.last.get() }
+	fn changes(&self) -> i64 { self.changes.get() }
+	fn total_changes(&self) -> i64 { self.total.get() }
+	fn is_autocommit(&self) -> bool { true }
+	fn as_any(&self) -> &dyn Any { self }
+}
+
+impl<'c> RawStatement for SequenceStatement<'c> {
+	fn reset(&mut self) -> Result<()> { self.row = false; self.done = false; Ok(()) }
+
+	fn step(&mut self) -> Result<bool> {
+		if self.done { return Ok(false); }
+		self.done = true;
+		if self.sql == "NEXT" {
+			let n = self.con.next.get();
+			self.con.last.set(n);
+			self.con.next.set(n + 1);
+			self.con.changes.set(1);
+			self.con.total.set(self.con.total.get() + 1);
+			return Ok(false);
+		}
+		self.text = self.con.last.get().to_string().into_bytes();
+		self.row = true;
+		Ok(true)
+	}
+
+	fn bind_null(&mut self, _ : i32) -> Result<()> { Err(Error::new(ErrorKind::InvalidInput, "no parameters")) }
+	fn bind_int64(&mut self, i : i32, _ : i64) -> Result<()> { self.bind_null(i) }
+	fn bind_double(&mut self, i : i32, _ : f64) -> Result<()> { self.bind_null(i) }
+	fn bind_text(&mut self, i : i32, _ : &str) -> Result<()> { self.bind_null(i) }
+	fn bind_blob(&mut self, i : i32, _ : &[u8]) -> Result<()> { self.bind_null(i) }
+	fn column_count(&self) -> i32 { if self.sql == "CURRENT" { 1 } else { 0 } }
+	fn column_name(&self, index : i32) -> String { if index == 0 && self.sql == "CURRENT" { "value".to_string() } else { String::new() } }
+
+	fn column_value(&self, index : i32) -> ValueRef<'_> {
+		if self.row && index == 0 { ValueRef::Integer(self.con.last.get()) } else { ValueRef::Null }
+	}
+
+	fn column_text(&self, index : i32) -> &[u8] { if self.row && index == 0 { &self.text } else { &[] } }
+	fn sql(&self) -> String { self.sql.clone() }
+	fn is_readonly(&self) -> bool { self.sql == "CURRENT" }
+}
+
+//Register a Custom driver, open it and read the rowid, the change counters and the statement introspection through
+//Connection and Statement, then compare with SQLite.
+fn main() {
+	println!("before registering: {:?}", Connection::new(Custom("sequence"), "100").err().map(|e| e.kind()));
+	println!("replaced: {}", register_driver(Custom("sequence"), Arc::new(SequenceDriver)).is_some());
+	println!("bad target: {:?}", Connection::new(Custom("sequence"), "x").err().map(|e| e.to_string()));
+	let db = match Connection::new(Custom("sequence"), "100") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	println!("type {:?}, rowid {}, changes {}, total {}, autocommit {}", db.db_type(), db.last_insert_rowid(), db.changes64(), db.total_changes(), db.is_autocommit());
+	match db.prepare_statement("NEXT") {
+		Ok(mut st) => {
+			for _ in 0..3 { if let Some(e) = st.execute() { println!("{}", e); } }
+			println!("{}: rowid {}, changes {}, total {}, readonly {}, explain {}, expanded {:?}", st.sql(), db.last_insert_rowid(), db.changes64(),
+					 db.total_changes(), st.is_readonly(), st.is_explain(), st.expanded_sql());
+		}
+		Err(e) => println!("{}", e)
+	}
+	match db.prepare_statement("CURRENT").and_then(|mut st| { let ro = st.is_readonly(); st.fetch_all().map(|rs| (ro, rs)) }) {
+		Ok((ro, rs)) => println!("CURRENT: readonly {}, {:?} = {:?}", ro, rs.columns().first().map(|c| c.name()), rs.row(0).map(|r| r.get_long(0))),
+		Err(e) => println!("{}", e)
+	}
+	println!("unknown: {:?}", db.prepare_statement("DROP").err().map(|e| e.kind()));
+	println!("replaced again: {}", register_driver(Custom("sequence"), Arc::new(SequenceDriver)).is_some());
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	if let Err(e) = db.execute_batch("CREATE TABLE t(v); BEGIN; INSERT INTO t VALUES (1), (2); INSERT INTO t VALUES (3)") { println!("{}", e); }
+	println!("SQLite: rowid {}, changes {}, total {}, autocommit {}", db.last_insert_rowid(), db.changes64(), db.total_changes(), db.is_autocommit());
+	match db.prepare_statement("SELECT v FROM t WHERE v > ?") {
+		Ok(mut st) => {
+			if let Some(e) = st.set_int(1, 1) { println!("{}", e); }
+			println!("SQLite: readonly {}, explain {}, expanded {:?}", st.is_readonly(), st.is_explain(), st.expanded_sql());
+		}
+		Err(e) => println!("{}", e)
+	};
+}
@@ -0,0 +1,46 @@
+extern crate rustic;
+
+use std::env::temp_dir;
+use std::fs::{remove_file, File};
+use std::io::stdout;
+use rustic::sql::{Connection, DumpContent, DumpOptions};
+use rustic::sql::DbType::SQLite3;
+
+//Dump a database to the standard output, whole, schema only and data only, then to test-dump.sql in the temporary
+//directory, restore the file in a new database and query it.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	match db.execute_batch("CREATE TABLE t(i INTEGER PRIMARY KEY, t TEXT, b BLOB);
+		CREATE INDEX t_t ON t(t);
+		INSERT INTO t VALUES (1, 'it''s', X'0102'), (2, NULL, NULL);") {
+		Ok(()) => (),
+		Err(e) => println!("{}", e)
+	}
+	match db.dump(stdout(), &DumpOptions::new()) { Ok(()) => (), Err(e) => println!("{}", e) }
+	for &content in &[DumpContent::Schema, DumpContent::Data] {
+		println!("-- {:?} of t", content);
+		match db.dump(stdout(), &DumpOptions::new().table("t").content(content)) { Ok(()) => (), Err(e) => println!("{}", e) }
+	}
+	let file = temp_dir().join("test-dump.sql");
+	match File::create(&file) {
+		Ok(f) => match db.dump(f, &DumpOptions::new()) { Ok(()) => (), Err(e) => println!("{}", e) },
+		Err(e) => println!("{}", e)
+	}
+	let copy = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	match File::open(&file) {
+		Ok(f) => match copy.restore(f) { Ok(()) => println!("restored"), Err(e) => println!("{}", e) },
+		Err(e) => println!("{}", e)
+	}
+	match copy.prepare_statement("SELECT i,t,hex(b) FROM t;") {
+		Ok(mut st) => {
+			for i in st.execute_query() {
+				match i {
+					Ok(s)  => println!("{}:{}:{}", s.get_long(0), s.get_string(1), s.get_string(2)),
+					Err(e) => println!("{}", e)
+				}
+			}
+		},
+		Err(e) => println!("{}", e)
+	};
+	let _ = remove_file(&file);
+}
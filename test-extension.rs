@@ -0,0 +1,59 @@
+extern crate rustic;
+
+use std::env::temp_dir;
+use std::fs::{remove_file, write};
+use std::process::Command;
+use rustic::sql::Connection;
+use rustic::sql::DbType::SQLite3;
+
+//An extension defining half(x), with the default entry point of a file named half.so and a named one.
+const SOURCE : &str = r#"
+#include <sqlite3ext.h>
+SQLITE_EXTENSION_INIT1
+
+static void half(sqlite3_context *ctx, int argc, sqlite3_value **argv) {
+	sqlite3_result_double(ctx, sqlite3_value_double(argv[0]) / 2);
+}
+
+int sqlite3_half_init(sqlite3 *db, char **err, const sqlite3_api_routines *api) {
+	SQLITE_EXTENSION_INIT2(api);
+	return sqlite3_create_function(db, "half", 1, SQLITE_UTF8 | SQLITE_DETERMINISTIC, 0, half, 0, 0);
+}
+
+int refuse(sqlite3 *db, char **err, const sqlite3_api_routines *api) {
+	SQLITE_EXTENSION_INIT2(api);
+	*err = sqlite3_mprintf("refused to load");
+	return SQLITE_ERROR;
+}
+"#;
+
+//Build the extension with gcc, load it and call its function, then check the failures and that SQL cannot load it.
+fn main() {
+	let dir = temp_dir();
+	let (source, library) = (dir.join("half.c"), dir.join("half.so"));
+	if let Err(e) = write(&source, SOURCE) { println!("{}", e); return; }
+	match Command::new("gcc").args(["-shared", "-fPIC", "-o"]).arg(&library).arg(&source).status() {
+		Ok(s) if s.success() => (),
+		r => { println!("gcc failed: {:?}", r); return; }
+	}
+	let library = library.to_string_lossy().into_owned();
+	let db = match Connection::new(SQLite3, ":memory:") {
+		Ok(db) => db,
+		Err(e) => { println!("{}", e); return; }
+	};
+	println!("before loading: {:?}", half(&db));
+	println!("SQL load_extension: {:?}", db.prepare_statement(&format!("SELECT load_extension('{}')", library)).and_then(|mut s| s.fetch_all()).err());
+	println!("load: {:?}", db.load_extension(&library, None));
+	println!("half(21) = {:?}", half(&db));
+	println!("SQL load_extension after: {:?}", db.prepare_statement(&format!("SELECT load_extension('{}')", library)).and_then(|mut s| s.fetch_all()).err());
+	println!("refuse: {:?}", db.load_extension(&library, Some("refuse")).map_err(|e| e.to_string()));
+	println!("no entry point: {:?}", db.load_extension(&library, Some("nothing")).map_err(|e| e.to_string()));
+	println!("no file: {:?}", db.load_extension("/nonexistent/ext.so", None).map_err(|e| e.kind()));
+	for f in &[&source.to_string_lossy().into_owned(), &library] { let _ = remove_file(f); }
+}
+
+fn half(db : &Connection) -> Result<f64, String> {
+	let mut st = db.prepare_statement("SELECT half(21)").map_err(|e| e.to_string())?;
+	let rs = st.fetch_all().map_err(|e| e.to_string())?;
+	Ok(rs.row(0).map_or(0.0, |r| r.get_double(0)))
+}
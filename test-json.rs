@@ -0,0 +1,24 @@
+extern crate rustic;
+
+use std::io::stdout;
+use rustic::sql::{Connection, JsonOptions, JsonFormat, BlobEncoding};
+use rustic::sql::DbType::SQLite3;
+
+//Write a query result to the standard output as a JSON array, then as JSON Lines with blobs in hexadecimal.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	match db.execute_batch("CREATE TABLE t(i INTEGER PRIMARY KEY, t TEXT, r REAL, b BLOB);
+		INSERT INTO t VALUES (1, 'say \"hi\"', 0.5, X'0102'), (2, NULL, NULL, NULL);") {
+		Ok(()) => (),
+		Err(e) => println!("{}", e)
+	}
+	for options in &[JsonOptions::new(), JsonOptions::new().format(JsonFormat::Lines).blobs(BlobEncoding::Hex)] {
+		match db.prepare_statement("SELECT i,t,r,b FROM t;") {
+			Ok(mut st) => match st.write_json(stdout(), options) {
+				Ok(n) => println!("{} row(s) written", n),
+				Err(e) => println!("{}", e)
+			},
+			Err(e) => println!("{}", e)
+		}
+	}
+}
@@ -0,0 +1,28 @@
+extern crate rustic;
+
+use rustic::sql::Connection;
+use rustic::sql::DbType::SQLite3;
+
+//Bind lists of several lengths to the IN (?) of a query, between two other parameters, one bound before
+//the first list and one after it.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	match db.execute_batch("CREATE TABLE t(id INTEGER PRIMARY KEY, grp INT, name TEXT);
+		INSERT INTO t VALUES (1, 1, 'a'), (2, 1, 'b'), (3, 2, 'c'), (4, 2, 'd'), (5, 3, 'e');") {
+		Ok(()) => (),
+		Err(e) => println!("{}", e)
+	}
+	let mut st = match db.prepare_statement("SELECT id FROM t WHERE grp <> ? AND id IN (?) OR name = ? ORDER BY id") {
+		Ok(st) => st,
+		Err(e) => { println!("{}", e); return }
+	};
+	if let Some(e) = st.set_int(1, 2) { println!("{}", e); }
+	if let Some(e) = st.set_string(3, "e") { println!("{}", e); }
+	for ids in &[vec![1, 2, 3], vec![4], vec![], vec![2, 3, 4, 5]] {
+		if let Some(e) = st.set_list(2, ids) { println!("{}", e); }
+		match st.fetch_all() {
+			Ok(rs) => println!("{:?}: {} -> {:?}", ids, st.sql(), rs.iter().map(|r| r.get_long(0)).collect::<Vec<i64>>()),
+			Err(e) => println!("{}", e)
+		}
+	}
+}
@@ -0,0 +1,42 @@
+extern crate rustic;
+
+use std::fs::remove_file;
+use rustic::sql::Connection;
+use rustic::sql::DbType::SQLite3;
+
+//Break a foreign key and check the database, free pages with an incremental vacuum, then write a compacted copy.
+fn main() {
+	let _ = remove_file("test-maintenance-copy.db");
+	let db = match Connection::new(SQLite3, "test-maintenance.db") {
+		Ok(db) => db,
+		Err(e) => { println!("{}", e); return; }
+	};
+	let setup = "PRAGMA auto_vacuum = INCREMENTAL;
+				 CREATE TABLE IF NOT EXISTS author(id INTEGER PRIMARY KEY, name TEXT);
+				 CREATE TABLE IF NOT EXISTS book(id INTEGER PRIMARY KEY, title TEXT, author INTEGER REFERENCES author(id));
+				 INSERT INTO author VALUES (1, 'Verne');
+				 INSERT INTO book(title, author) VALUES ('Nautilus', 1), ('Lost', 7), ('Unknown', 9);
+				 CREATE TABLE filler(data BLOB);
+				 WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 200) INSERT INTO filler SELECT zeroblob(2000) FROM n;";
+	if let Err(e) = db.execute_batch(setup) { println!("{}", e); }
+	println!("integrity_check {:?}, quick_check {:?}", db.integrity_check(None), db.quick_check(None));
+	match db.foreign_key_check(None, None) {
+		Ok(found) => for v in found { println!("{} row {:?} -> {} {:?}", v.table(), v.rowid(), v.parent(), v.columns()); },
+		Err(e) => println!("{}", e)
+	}
+	if let Err(e) = db.execute_batch("DROP TABLE filler") { println!("{}", e); }
+	match db.page_stats(None) {
+		Ok(s) => {
+			println!("{} pages of {} bytes, {} free", s.page_count(), s.page_size(), s.freelist_count());
+			for o in s.objects().unwrap_or(&[]) { println!("  {}: {} pages, {} bytes of data", o.name(), o.pages(), o.payload()); }
+		},
+		Err(e) => println!("{}", e)
+	}
+	println!("incremental_vacuum(50) {:?}, then all {:?}", db.incremental_vacuum(None, Some(50)), db.incremental_vacuum(None, None));
+	println!("analyze {:?}, optimize {:?}", db.analyze(None, Some("book")), db.optimize());
+	println!("vacuum_into {:?}", db.vacuum_into(None, "test-maintenance-copy.db"));
+	println!("vacuum_into again {:?}", db.vacuum_into(None, "test-maintenance-copy.db").map_err(|e| e.to_string()));
+	println!("unknown schema {:?}", db.integrity_check(Some("nowhere")).map_err(|e| e.to_string()));
+	drop(db);
+	for f in &["test-maintenance.db", "test-maintenance-copy.db"] { let _ = remove_file(f); }
+}
@@ -0,0 +1,107 @@
+extern crate rustic;
+
+use rustic::sql::{Connection, Migration};
+use rustic::sql::DbType::SQLite3;
+
+//Bring a database to a desired schema: print the plan, refuse the destructive one, apply it, then check nothing is left.
+//On a database enforcing foreign keys, the plan disables them while it rebuilds tables, and a rebuild breaking a
+//constraint fails with the constraints enforced again. A rebuilt table gets a temporary name no object uses, and the
+//script of a plan can be run statement by statement.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	match db.execute_batch("CREATE TABLE t(i INTEGER PRIMARY KEY, t TEXT, old TEXT);
+		CREATE TABLE junk(x);
+		INSERT INTO t VALUES (1, 'one', 'x');") {
+		Ok(()) => (),
+		Err(e) => println!("{}", e)
+	}
+	let desired = "CREATE TABLE t(i INTEGER PRIMARY KEY, t TEXT NOT NULL);
+		CREATE TABLE u(i INTEGER PRIMARY KEY, t INT REFERENCES t);
+		CREATE INDEX u_t ON u(t);
+		CREATE TRIGGER t_del AFTER DELETE ON t BEGIN DELETE FROM u WHERE t = old.i; END;";
+	match Migration::new(desired).plan(&db) {
+		Ok(plan) => print!("{}", plan),
+		Err(e) => println!("{}", e)
+	}
+	match Migration::new(desired).run(&db) {
+		Ok(_) => println!("applied"),
+		Err(e) => println!("{:?}: {}", e.kind(), e)
+	}
+	match Migration::new(desired).allow_destructive(true).run(&db) {
+		Ok(plan) => println!("{} step(s) applied", plan.steps().len()),
+		Err(e) => println!("{}", e)
+	}
+	match db.prepare_statement("SELECT i,t FROM t;") {
+		Ok(mut st) => {
+			for i in st.execute_query() {
+				match i {
+					Ok(s)  => println!("{}:{}", s.get_long(0), s.get_string(1)),
+					Err(e) => println!("{}", e)
+				}
+			}
+		},
+		Err(e) => println!("{}", e)
+	}
+	match Migration::new(desired).plan(&db) {
+		Ok(plan) => print!("{}", plan),
+		Err(e) => println!("{}", e)
+	}
+
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	if let Err(e) = db.execute_batch("PRAGMA foreign_keys = ON;
+		CREATE TABLE p(id INTEGER PRIMARY KEY, name TEXT);
+		CREATE TABLE c(id INTEGER PRIMARY KEY, p INTEGER REFERENCES p);
+		INSERT INTO p VALUES (1, 'one'), (2, 'two'); INSERT INTO c VALUES (10, 2)") {
+		println!("{}", e); return;
+	}
+	let desired = "CREATE TABLE p(id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+		CREATE TABLE c(id INTEGER PRIMARY KEY, p INTEGER REFERENCES p);";
+	match Migration::new(desired).plan(&db) {
+		Ok(plan) => print!("{}", plan),
+		Err(e) => println!("{}", e)
+	}
+	let enforced = |db : &Connection| db.prepare_statement("PRAGMA foreign_keys").and_then(|mut st| st.fetch_all()).map(|rs| rs.row(0).map(|r| r.get_long(0)));
+	//Rebuilding c to reference an empty table leaves its row referencing nothing.
+	let shrink = "CREATE TABLE p(id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+		CREATE TABLE q(id INTEGER PRIMARY KEY);
+		CREATE TABLE c(id INTEGER PRIMARY KEY, p INTEGER REFERENCES q);";
+	match Migration::new(shrink).run(&db) {
+		Ok(_) => println!("applied"),
+		Err(e) => println!("{:?}: {}", e.kind(), e)
+	}
+	println!("foreign keys after the failure: {:?}, autocommit {}", enforced(&db), db.is_autocommit());
+	match Migration::new(desired).run(&db) {
+		Ok(plan) => println!("{} step(s) applied, foreign keys {:?}, tables {:?}", plan.steps().len(), enforced(&db),
+							 db.introspector().and_then(|i| i.table_names(None))),
+		Err(e) => println!("{}", e)
+	}
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	let taken = "CREATE TABLE p(id INTEGER PRIMARY KEY, name TEXT);
+		CREATE TABLE new_p(x);
+		CREATE VIEW new_p_2 AS SELECT * FROM new_p;
+		CREATE INDEX new_p_3 ON new_p(x);";
+	if let Err(e) = db.execute_batch(taken) { println!("{}", e); return; }
+	if let Err(e) = db.execute_batch("INSERT INTO p VALUES (1, 'one'); INSERT INTO new_p VALUES ('kept')") { println!("{}", e); return; }
+	let desired = taken.replace("name TEXT)", "name TEXT NOT NULL)");
+	match Migration::new(&desired).plan(&db) {
+		Ok(plan) => {
+			println!("temporary name: {:?}", plan.steps().iter().flat_map(|s| s.statements().first()).find(|s| s.starts_with("CREATE TABLE")));
+			for sql in plan.statements() {
+				match db.prepare_statement(&sql).and_then(|mut st| st.fetch_all()) {
+					Ok(rs) => if sql.contains("foreign_key_check") { println!("{:?}: {} row(s)", sql, rs.row_count()); },
+					Err(e) => println!("{}: {}", sql, e)
+				}
+			}
+		},
+		Err(e) => println!("{}", e)
+	}
+	match db.prepare_statement("SELECT (SELECT group_concat(name) FROM p), (SELECT x FROM new_p), (SELECT count(*) FROM new_p_2)").and_then(|mut st| st.fetch_all()) {
+		Ok(rs) => println!("after the script: p {:?}, new_p {:?}, view rows {:?}", rs.row(0).map(|r| r.get_string(0)), rs.row(0).map(|r| r.get_string(1)),
+						   rs.row(0).map(|r| r.get_long(2))),
+		Err(e) => println!("{}", e)
+	}
+	match Migration::new(&desired).plan(&db) {
+		Ok(plan) => print!("{}", plan),
+		Err(e) => println!("{}", e)
+	}
+}
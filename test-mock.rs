@@ -0,0 +1,51 @@
+extern crate rustic;
+
+use std::io::ErrorKind;
+use rustic::sql::{MockDb, Expectation, Value};
+
+fn main() {
+	let mock = MockDb::new();
+	mock.expect(Expectation::sql("INSERT INTO t VALUES (?,?);").affects(1).inserts_rowid(10).times(2));
+	mock.expect(Expectation::regex("(?i)^select .* from t where i > \\?").unwrap().columns(&["i", "t"])
+				.row(vec![Value::Integer(10), Value::Text("one".to_string())])
+				.row(vec![Value::Integer(15), Value::Null]));
+	mock.expect(Expectation::sql("DELETE FROM t;").fails(ErrorKind::PermissionDenied, "attempt to write a readonly database"));
+	let db = mock.connection();
+	match db.prepare_statement("INSERT INTO t VALUES (?,?);") {
+		Ok(mut st) => {
+			match st.set_long(1,10) { None=>(), Some(e) => println!("{}", e) }
+			match st.set_string(2, "one") { None=>(), Some(e) => println!("{}", e) }
+			match st.execute_update() { Ok(n) => println!("{} row inserted, rowid {}", n, db.last_insert_rowid()), Err(e) => println!("{}", e) }
+			match st.set_long(1,15) { None=>(), Some(e) => println!("{}", e) }
+			match st.set_null(2) { None=>(), Some(e) => println!("{}", e) }
+			match st.execute() { None=> (), Some(e) => 	println!("{}", e) }
+			match st.set_long(3,20) { None=>(), Some(e) => println!("{}", e) }
+		},
+		Err(e) => println!("{}", e)
+	}
+	match db.prepare_statement("SELECT i,t FROM t WHERE i > ?;") {
+		Ok(mut st) => {
+			st.set_long(1, 0);
+			for i in st.execute_query() {
+				match i {
+					Ok(s)  => println!("{}:{}", s.get_long(0), s.get_string(1)),
+					Err(e) => println!("{}", e)
+				}
+			}
+		},
+		Err(e) => println!("{}", e)
+	}
+	match db.prepare_statement("DELETE FROM t;") {
+		Ok(mut st) => match st.execute() { None=> (), Some(e) => println!("{:?}: {}", e.kind(), e) },
+		Err(e) => println!("{}", e)
+	}
+	match db.prepare_statement("UPDATE t SET i = 0;") {
+		Ok(mut st) => match st.execute() { None=> (), Some(e) => println!("{:?}: {}", e.kind(), e) },
+		Err(e) => println!("{}", e)
+	}
+	for x in mock.executions() { println!("{} {:?}", x.sql(), x.params()); }
+	match mock.verify() {
+		Ok(()) => println!("all expectations met"),
+		Err(e) => println!("{}", e)
+	}
+}
@@ -0,0 +1,118 @@
+extern crate rustic;
+
+use std::env;
+use rustic::sql::{Connection, MySqlError};
+use rustic::sql::DbType::MySQL;
+
+//The server is given by the RUSTIC_MYSQL environment variable, as a URL or a keyword/value connection string, and the
+//users to authenticate as by RUSTIC_MYSQL_USERS, as user:password separated by spaces. To run it against MySQL 8, whose
+//root user authenticates with caching_sha2_password, and MariaDB 11:
+//
+//	docker run -d --name rustic-mysql -p 3306:3306 -e MYSQL_ROOT_PASSWORD=root -e MYSQL_DATABASE=test mysql:8.4 --mysql-native-password=ON
+//	docker exec rustic-mysql mysql -uroot -proot -e "CREATE USER native IDENTIFIED WITH mysql_native_password BY 'npw';
+//		CREATE USER sha2 IDENTIFIED WITH caching_sha2_password BY 'spw'; GRANT ALL ON test.* TO native, sha2"
+//	RUSTIC_MYSQL="host=127.0.0.1 port=3306 user=root password=root dbname=test" RUSTIC_MYSQL_USERS="native:npw sha2:spw sha2:spw" ./test-mysql
+//
+//	docker run -d --name rustic-mariadb -p 3307:3306 -e MARIADB_ROOT_PASSWORD=root -e MARIADB_DATABASE=test mariadb:11
+//	docker exec rustic-mariadb mariadb -uroot -proot -e "CREATE USER native IDENTIFIED BY 'npw'; GRANT ALL ON test.* TO native"
+//	RUSTIC_MYSQL="host=127.0.0.1 port=3307 user=root password=root dbname=test" RUSTIC_MYSQL_USERS="native:npw" ./test-mysql
+//
+//Wait for the servers to accept connections before running the test. Over TCP, the first connection of a
+//caching_sha2_password user goes through the full authentication, with the RSA key of the server, and the next ones
+//through the fast authentication; a mysql_native_password user makes MySQL 8 switch plugins.
+fn main() {
+	let target = env::var("RUSTIC_MYSQL").unwrap_or_else(|_| "host=localhost dbname=test".to_string());
+	match Connection::new(MySQL, &target) {
+		Ok(db) => {
+					match db.prepare_statement("CREATE TEMPORARY TABLE t(i BIGINT PRIMARY KEY, f DOUBLE, t TEXT, b BLOB, ok BOOLEAN);") {
+						Ok(mut st) => {
+							match st.execute() {
+									None    => (),
+									Some(e) => 	println!("{}", e)
+							}
+						},
+						Err(e) =>  	println!("{}", e)
+					}
+					match db.prepare_statement("INSERT INTO t VALUES (?,?,?,?,?);") {
+						Ok(mut st) => {
+							match st.set_long(1,10) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_double(2,10.1) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_string(3, "one") { None=>(), Some(e) => println!("{}", e) }
+							match st.set_blob(4, &[1, 2, 3]) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_long(5, 1) { None=>(), Some(e) => println!("{}", e) }
+							match st.execute() { None=> (), Some(e) => 	println!("{}", e) }
+							match st.set_long(1,15) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_double(2,15.1) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_null(3) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_blob(4, &[4, 5, 6]) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_long(5, 0) { None=>(), Some(e) => println!("{}", e) }
+							match st.execute() { None=> (), Some(e) => 	println!("{}", e) }
+							match st.set_long(1,20) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_double(2,20.2) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_string(3, "two") { None=>(), Some(e) => println!("{}", e) }
+							match st.set_blob(4, &[7, 8, 9]) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_null(5) { None=>(), Some(e) => println!("{}", e) }
+							match st.execute() { None=> (), Some(e) => 	println!("{}", e) }
+							match st.execute_update() {
+								Ok(n)  => println!("{} rows inserted", n),
+								Err(e) => match MySqlError::from_io(&e) {
+									Some(my) => println!("{} ({}) {}", my.code(), my.sqlstate(), my.message()),
+									None => println!("{}", e)
+								}
+							}
+						},
+						Err(e) => println!("{}", e)
+					}
+					match db.prepare_statement("SELECT i,f,t,b,ok FROM t WHERE t LIKE ? OR t IS NULL ORDER BY i;") {
+						Ok(mut st) => {
+							st.set_string(1, "%o%");
+							for i in st.execute_query() {
+								match i {
+									Ok(s)  => println!("{}:{}:{}:{:?}:{}", s.get_long(0), s.get_double(1),
+																		s.get_string(2), s.get_blob(3), s.get_string(4) ),
+									Err(e) => println!("{}", e)
+								}
+							}
+						},
+						Err(e) => println!("{}", e)
+					}
+					//Dates, times and decimals come in the binary protocol of prepared statements.
+					let temporal = "SELECT CAST('2024-02-29 13:45:30.123456' AS DATETIME(6)), CAST('2024-02-29 13:45:30' AS DATETIME), \
+									DATE '2024-02-29', CAST('-838:59:59.5' AS TIME(1)), CAST('36:00:01' AS TIME), \
+									TIMESTAMP('2024-01-01 00:00:00.25'), CAST(12345.6789 AS DECIMAL(10,4)), CAST(-0.5 AS DECIMAL(3,2)), \
+									CAST(18446744073709551615 AS UNSIGNED), YEAR('2024-02-29')";
+					let expected = ["2024-02-29 13:45:30.123456", "2024-02-29 13:45:30", "2024-02-29", "-838:59:59.5", "36:00:01",
+									"2024-01-01 00:00:00.25", "12345.6789", "-0.50", "18446744073709551615", "2024"];
+					match db.prepare_statement(temporal) {
+						Ok(mut st) => for i in st.execute_query() {
+							match i {
+								Ok(r) => for (c, e) in expected.iter().enumerate() {
+									let got = r.get_string(c as i32);
+									println!("{} {}{}", if got == *e { "ok  " } else { "FAIL" }, got, if got == *e { String::new() } else { format!(", expected {}", e) });
+								},
+								Err(e) => println!("{}", e)
+							}
+						},
+						Err(e) => println!("{}", e)
+					}
+					match db.prepare_statement("SELEC 1;") {
+						Ok(_)  => println!("syntax error not reported"),
+						Err(e) => println!("{:?}: {}", e.kind(), e)
+					}
+				},
+		Err(e) => println!("{}", e)
+	}
+	for user in env::var("RUSTIC_MYSQL_USERS").unwrap_or_default().split_whitespace() {
+		let (name, password) = user.split_at(user.find(':').unwrap_or(user.len()));
+		let target = format!("{} user={} password={}", target, name, password.trim_start_matches(':'));
+		match Connection::new(MySQL, &target).and_then(|db| db.prepare_statement("SELECT CURRENT_USER()").and_then(|mut st| st.fetch_all())) {
+			Ok(rs) => println!("{}: authenticated as {}", name, rs.row(0).map(|r| r.get_string(0)).unwrap_or_default()),
+			Err(e) => println!("{}: {}", name, e)
+		}
+	}
+	let wrong = format!("{} password=wrong", target);
+	match Connection::new(MySQL, &wrong) {
+		Ok(_) => println!("wrong password accepted"),
+		Err(e) => println!("wrong password: {:?}", e.kind())
+	}
+}
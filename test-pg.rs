@@ -0,0 +1,71 @@
+extern crate rustic;
+
+use std::env;
+use rustic::sql::{Connection, PgError};
+use rustic::sql::DbType::PostgreSQL;
+
+//The server is given by the RUSTIC_PG environment variable, as a URL or a keyword/value connection string.
+fn main() {
+	let target = env::var("RUSTIC_PG").unwrap_or_else(|_| "host=localhost dbname=postgres".to_string());
+	match Connection::new(PostgreSQL, &target) {
+		Ok(db) => {
+					match db.prepare_statement("CREATE TEMPORARY TABLE t(i BIGINT PRIMARY KEY, f DOUBLE PRECISION, t TEXT, b BYTEA, ok BOOLEAN);") {
+						Ok(mut st) => {
+							match st.execute() {
+									None    => (),
+									Some(e) => 	println!("{}", e)
+							}
+						},
+						Err(e) =>  	println!("{}", e)
+					}
+					match db.prepare_statement("INSERT INTO t VALUES ($1,$2,$3,$4,$5);") {
+						Ok(mut st) => {
+							match st.set_long(1,10) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_double(2,10.1) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_string(3, "one") { None=>(), Some(e) => println!("{}", e) }
+							match st.set_blob(4, &[1, 2, 3]) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_long(5, 1) { None=>(), Some(e) => println!("{}", e) }
+							match st.execute() { None=> (), Some(e) => 	println!("{}", e) }
+							match st.set_long(1,15) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_double(2,15.1) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_null(3) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_blob(4, &[4, 5, 6]) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_long(5, 0) { None=>(), Some(e) => println!("{}", e) }
+							match st.execute() { None=> (), Some(e) => 	println!("{}", e) }
+							match st.set_long(1,20) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_double(2,20.2) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_string(3, "two") { None=>(), Some(e) => println!("{}", e) }
+							match st.set_blob(4, &[7, 8, 9]) { None=>(), Some(e) => println!("{}", e) }
+							match st.set_null(5) { None=>(), Some(e) => println!("{}", e) }
+							match st.execute() { None=> (), Some(e) => 	println!("{}", e) }
+							match st.execute_update() {
+								Ok(n)  => println!("{} rows inserted", n),
+								Err(e) => match PgError::from_io(&e) {
+									Some(pg) => println!("{} [{}] {}", pg.code(), pg.constraint().unwrap_or(""), pg.detail().unwrap_or("")),
+									None => println!("{}", e)
+								}
+							}
+						},
+						Err(e) => println!("{}", e)
+					}
+					match db.prepare_statement("SELECT i,f,t,b,ok FROM t WHERE t LIKE $1 OR t IS NULL ORDER BY i;") {
+						Ok(mut st) => {
+							st.set_string(1, "%o%");
+							for i in st.execute_query() {
+								match i {
+									Ok(s)  => println!("{}:{}:{}:{:?}:{}", s.get_long(0), s.get_double(1),
+																		s.get_string(2), s.get_blob(3), s.get_string(4) ),
+									Err(e) => println!("{}", e)
+								}
+							}
+						},
+						Err(e) => println!("{}", e)
+					}
+					match db.prepare_statement("SELEC 1;") {
+						Ok(_)  => println!("syntax error not reported"),
+						Err(e) => println!("{:?}: {}", e.kind(), e)
+					}
+				},
+		Err(e) => println!("{}", e)
+	}
+}
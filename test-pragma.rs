@@ -0,0 +1,28 @@
+extern crate rustic;
+
+use std::fs::remove_file;
+use std::time::Duration;
+use rustic::sql::{Connection, ConnectionConfig, JournalMode, Synchronous, TempStore};
+use rustic::sql::DbType::SQLite3;
+
+//Open a database file with a WAL profile and print its settings, then show that WAL is refused for an in-memory database.
+fn main() {
+	let config = ConnectionConfig::new().journal_mode(JournalMode::Wal).synchronous(Synchronous::Normal).foreign_keys(true)
+										.busy_timeout(Duration::from_secs(5)).cache_size(-20000).temp_store(TempStore::Memory);
+	match Connection::open(SQLite3, "test-pragma.db", &config) {
+		Ok(db) => {
+			println!("journal_mode {:?}, synchronous {:?}, foreign_keys {:?}", db.journal_mode(), db.synchronous(), db.foreign_keys());
+			println!("busy_timeout {:?}, cache_size {:?}, temp_store {:?}", db.busy_timeout(), db.cache_size(), db.temp_store());
+			match db.set_mmap_size(64 << 20) {
+				Ok(n) => println!("mmap_size {}", n),
+				Err(e) => println!("{}", e)
+			}
+		},
+		Err(e) => println!("{}", e)
+	}
+	match Connection::open(SQLite3, ":memory:", &config) {
+		Ok(_) => println!("WAL enabled in memory"),
+		Err(e) => println!("{:?}: {}", e.kind(), e)
+	}
+	for f in &["test-pragma.db", "test-pragma.db-wal", "test-pragma.db-shm"] { let _ = remove_file(f); }
+}
@@ -0,0 +1,73 @@
+//The regular expressions of the mock driver are private to the crate: the module is compiled in this program from its
+//source.
+#[allow(dead_code)]
+#[path = "src/sql/regex.rs"]
+mod regex;
+
+use std::time::Instant;
+use regex::Regex;
+
+//Patterns and texts with whether the pattern matches somewhere in each text, as another implementation finds it except
+//where noted: $ only matches at the end of the text, not before a final newline, and {,n} is not a repetition.
+const CASES : &[(&str, &[(&str, bool)])] = &[
+	(r"^abc$", &[("abc", true), ("xabc", false), ("abcx", false), ("", false)]),
+	(r"^$", &[("", true), ("a", false)]),
+	(r"a$", &[("ba", true), ("ab", false), ("a\n", false)]),
+	(r"^\s*SELECT\b", &[("  SELECT x", true), ("SELECTED", false), ("\tselect 1", false)]),
+	(r"(?i)^\s*select\b", &[("  SeLeCt x", true), ("selected", false)]),
+	(r"\bid\b", &[("id = 1", true), ("valid", false), ("(id)", true), ("_id", false)]),
+	(r"\Bor\B", &[("word", true), ("or", false), ("for", false)]),
+	(r"[a-c]+\d{2}", &[("bb12", true), ("abc1", false), ("zz99", false), ("a123", true)]),
+	(r"^[^0-9 ]+$", &[("abc", true), ("ab c", false), ("a1", false), ("", false)]),
+	(r"^[\w.-]+$", &[("a.b-c_d", true), ("a b", false), ("-", true)]),
+	(r"^[]a]+$", &[("]a]", true), ("b", false)]),
+	(r"^[a\]]+$", &[("a]a", true), ("b", false)]),
+	(r"^[\d\s]+$", &[("1 2\t3", true), ("1a", false)]),
+	(r"^\D\W\S$", &[("a-b", true), ("1-b", false), ("a b", true)]),
+	(r"(?i)^[A-Z]+$", &[("abc", true), ("ABC", true), ("a1", false)]),
+	(r"^a{3}$", &[("aaa", true), ("aa", false), ("aaaa", false)]),
+	(r"^a{2,}$", &[("a", false), ("aa", true), ("aaaaa", true)]),
+	(r"^a{2,3}$", &[("a", false), ("aa", true), ("aaa", true), ("aaaa", false)]),
+	(r"^a{0}b$", &[("b", true), ("ab", false)]),
+	(r"^x{,3}$", &[("x{,3}", true), ("xxx", false)]),
+	(r"^a{1,2}?b+?c*?$", &[("ab", true), ("aabbcc", true), ("aaab", false)]),
+	(r"^(ab|cd)+$", &[("abcdab", true), ("abc", false), ("", false)]),
+	(r"^(?:a|b|)c$", &[("ac", true), ("bc", true), ("c", true), ("dc", false)]),
+	(r"^(a|ab)(c|bcd)(d*)$", &[("abcd", true), ("abcdd", true), ("ac", true)]),
+	(r"^(a*)*$", &[("", true), ("aaaa", true), ("aab", false)]),
+	(r"^(a?){3}a{3}$", &[("aaa", true), ("aaaaaa", true), ("aa", false)]),
+	(r"^(|a)+b$", &[("b", true), ("aab", true)]),
+	(r"^.+$", &[("abc", true), ("a\nb", false)]),
+	(r"a.c", &[("abc", true), ("a\nc", false)]),
+	(r"^\.\*\+\?\(\)\[\{\|\\$", &[(".*+?()[{|\\", true), ("x", false)]),
+	(r"^a\tb\nc$", &[("a\tb\nc", true)]),
+	(r"^é+\w$", &[("éé1", true), ("ée", true)]),
+	(r"(?i)^ÉCOLE$", &[("école", true)]),
+	(r"^INSERT INTO \w+ VALUES \((\?, )*\?\)$", &[("INSERT INTO t VALUES (?, ?, ?)", true), ("INSERT INTO t VALUES ()", false)])
+];
+
+//Check matches of anchors, classes, repetitions, groups and alternations, malformed patterns, then time patterns that
+//take an exponential time with a backtracking matcher on texts they almost match, and a repetition expanded into a
+//thousand states, the largest cost per character.
+fn main() {
+	for &(pattern, texts) in CASES {
+		let re = match Regex::new(pattern) { Ok(re) => re, Err(e) => { println!("FAIL {}", e); continue } };
+		let got : Vec<(&str, bool)> = texts.iter().map(|&(t, _)| (t, re.is_match(t))).collect();
+		println!("{} {}", if got == texts { "ok  " } else { "FAIL" }, pattern);
+		if got != texts { println!("     got      {:?}\n     expected {:?}", got, texts); }
+	}
+	for &pattern in &["a**", "*a", "(a", "a)", "[a", "[z-a]", "a{3,2}", "^*", r"\b+", "(?=a)", "a\\", "a{1001}", "(a{1000}){1000}"] {
+		println!("{} {:?}: {:?}", if Regex::new(pattern).is_err() { "ok  " } else { "FAIL" }, pattern, Regex::new(pattern).err().map(|e| e.to_string()));
+	}
+	let long = "a".repeat(2000);
+	for &(pattern, text) in &[(r"^(a*)*b$", &*"a".repeat(30)), (r"(a|aa)*c", &*"a".repeat(40)), (r"^(x+x+)+y$", &*"x".repeat(30)),
+							  (r"^(a|a?)+$", &*format!("{}b", "a".repeat(30))), (r"(\w+\s?)*;", &*format!("{} !", "word ".repeat(2000))),
+							  (r"^(a{1,10}){1,10}$", &*"a".repeat(101)), (r"a{1000}b", &long), (r"^(a?){500}a{500}$", &*"a".repeat(500))] {
+		let re = match Regex::new(pattern) { Ok(re) => re, Err(e) => { println!("FAIL {}", e); continue } };
+		let start = Instant::now();
+		let matched = re.is_match(text);
+		let elapsed = start.elapsed();
+		//A backtracking matcher takes seconds to years on most of these, a limit of a second leaves room for slow machines.
+		println!("{} {} on {} characters: {} in {:?}", if elapsed.as_secs() < 1 { "ok  " } else { "FAIL" }, pattern, text.chars().count(), matched, elapsed);
+	}
+}
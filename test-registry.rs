@@ -0,0 +1,60 @@
+extern crate rustic;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::io::Result;
+use std::thread;
+use rustic::sql::{Connection, Driver, RawConnection, Sqlite3Connection, Sqlite3Driver, get_driver, register_driver, unregister_driver};
+use rustic::sql::DbType::{Custom, MySQL, PostgreSQL, SQLite3};
+
+//A driver counting the connections it opens through the driver it wraps.
+struct Counted {
+	inner : Arc<dyn Driver>,
+	count : AtomicUsize
+}
+
+impl Driver for Counted {
+	fn connect(&self, target : &str) -> Result<Box<dyn RawConnection>> {
+		self.count.fetch_add(1, Ordering::SeqCst);
+		self.inner.connect(target)
+	}
+}
+
+fn query(db : &Connection, sql : &str) -> String {
+	match db.prepare_statement(sql).and_then(|mut st| st.fetch_all()) {
+		Ok(rs) => rs.row(0).map_or("no row".to_string(), |r| r.get_string(0)),
+		Err(e) => e.to_string()
+	}
+}
+
+//Look up the built-in drivers, register a driver wrapping SQLite under Custom names from another thread, open
+//connections through it, then unregister and register again the SQLite driver itself.
+fn main() {
+	for &t in &[SQLite3, PostgreSQL, MySQL, Custom("sqlite3")] {
+		println!("{:?}: {}", t, get_driver(t).map(|_| "registered".to_string()).unwrap_or_else(|e| format!("{:?}", e.kind())));
+	}
+	let sqlite = match get_driver(SQLite3) { Ok(d) => d, Err(e) => { println!("{}", e); return } };
+	let counted = Arc::new(Counted { inner : sqlite.clone(), count : AtomicUsize::new(0) });
+	let registering = counted.clone();
+	let previous = thread::spawn(move || register_driver(Custom("counted"), registering).is_some()).join();
+	println!("registered from another thread, replaced: {:?}", previous);
+	for _ in 0..2 {
+		match Connection::new(Custom("counted"), ":memory:") {
+			Ok(db) => println!("{:?} {} downcast to SQLite: {}", db.db_type(), query(&db, "SELECT sqlite_version() IS NOT NULL"),
+							   db.raw().as_any().downcast_ref::<Sqlite3Connection>().is_some()),
+			Err(e) => println!("{}", e)
+		}
+	}
+	println!("Custom(\"other\"): {:?}", Connection::new(Custom("other"), ":memory:").err().map(|e| e.kind()));
+	println!("connections counted: {}", counted.count.load(Ordering::SeqCst));
+	let raw = sqlite.connect(":memory:");
+	match raw {
+		Ok(raw) => { let db = Connection::from_raw(Custom("wrapped"), raw); println!("from_raw {:?}: {}", db.db_type(), query(&db, "SELECT 40 + 2")); }
+		Err(e) => println!("{}", e)
+	}
+	println!("unregister counted: {}, again: {}", unregister_driver(Custom("counted")).is_some(), unregister_driver(Custom("counted")).is_some());
+	println!("unregister SQLite3: {}", unregister_driver(SQLite3).is_some());
+	println!("SQLite3 without driver: {:?}", Connection::new(SQLite3, ":memory:").err().map(|e| e.to_string()));
+	println!("register SQLite3 again, replaced: {}", register_driver(SQLite3, Arc::new(Sqlite3Driver)).is_some());
+	match Connection::new(SQLite3, ":memory:") { Ok(db) => println!("SQLite3: {}", query(&db, "SELECT 'back'")), Err(e) => println!("{}", e) }
+}
@@ -0,0 +1,48 @@
+extern crate rustic;
+
+use std::env::temp_dir;
+use std::fs::remove_file;
+use rustic::sql::{Connection, RecordDriver, ReplayDriver};
+use rustic::sql::DbType::SQLite3;
+
+//Run once against SQLite while recording to test-replay.txt in the temporary directory, then again from the recording,
+//without database.
+fn run(db : &Connection) {
+	match db.prepare_statement("CREATE TABLE t(i INTEGER PRIMARY KEY, t TEXT);") {
+		Ok(mut st) => match st.execute() { None=> (), Some(e) => println!("{}", e) },
+		Err(e) => println!("{}", e)
+	}
+	match db.prepare_statement("INSERT INTO t VALUES (?,?);") {
+		Ok(mut st) => {
+			match st.set_long(1,10) { None=>(), Some(e) => println!("{}", e) }
+			match st.set_string(2, "one") { None=>(), Some(e) => println!("{}", e) }
+			match st.execute_update() { Ok(n) => println!("{} row inserted, rowid {}", n, db.last_insert_rowid()), Err(e) => println!("{}", e) }
+			match st.execute_update() { Ok(n) => println!("{} row inserted", n), Err(e) => println!("{:?}: {}", e.kind(), e) }
+		},
+		Err(e) => println!("{}", e)
+	}
+	match db.prepare_statement("SELECT i,t FROM t;") {
+		Ok(mut st) => {
+			for i in st.execute_query() {
+				match i {
+					Ok(s)  => println!("{}:{}", s.get_long(0), s.get_string(1)),
+					Err(e) => println!("{}", e)
+				}
+			}
+		},
+		Err(e) => println!("{}", e)
+	}
+}
+
+fn main() {
+	let recording = temp_dir().join("test-replay.txt");
+	match RecordDriver::open(SQLite3, ":memory:", &recording) {
+		Ok(db) => run(&db),
+		Err(e) => println!("{}", e)
+	}
+	match ReplayDriver::open(SQLite3, &recording) {
+		Ok(db) => run(&db),
+		Err(e) => println!("{}", e)
+	}
+	let _ = remove_file(&recording);
+}
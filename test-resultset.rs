@@ -0,0 +1,58 @@
+extern crate rustic;
+
+use std::fs::read_to_string;
+use std::thread;
+use std::time::Instant;
+use rustic::sql::{Connection, OwnedRow};
+use rustic::sql::DbType::SQLite3;
+
+//Resident memory of the process in bytes, from /proc/self/statm, 0 where it does not exist.
+fn resident() -> usize {
+	read_to_string("/proc/self/statm").ok().and_then(|s| s.split_whitespace().nth(1).and_then(|p| p.parse::<usize>().ok())).unwrap_or(0) * 4096
+}
+
+//Check that ResultRow and Row getters agree on each storage class, then fetch a million rows: row count, random access,
+//sorting, reading from another thread, and the memory taken compared with the same rows collected from a Cursor.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	let sql = "SELECT NULL, 42, 2.5, 1e100, 'text', x'6279746573', x'ff'";
+	let rs = match db.prepare_statement(sql).and_then(|mut st| st.fetch_all()) { Ok(rs) => rs, Err(e) => { println!("{}", e); return } };
+	let mut st = match db.prepare_statement(sql) { Ok(st) => st, Err(e) => { println!("{}", e); return } };
+	let mut rows = st.query();
+	if let (Some(Ok(row)), Some(result)) = (rows.next(), rs.row(0)) {
+		for i in 0..7 {
+			let (a, b) = (row.get_str(i).map(|s| s.to_string()).map_err(|e| e.kind()), result.get_str(i).map(|s| s.into_owned()).map_err(|e| e.kind()));
+			let (c, d) = (row.get_string(i), result.get_string(i));
+			let (e, f) = (row.get_bytes(i).to_vec(), result.get_bytes(i).into_owned());
+			println!("column {}: get_str {:?}, get_string {:?}, get_bytes {:?} {}", i, b, d, f, if a == b && c == d && e == f { "agree" } else { "DIFFER" });
+		}
+	}
+	drop(rows);
+
+	if let Err(e) = db.execute_batch("CREATE TABLE t(id INTEGER PRIMARY KEY, name TEXT, score REAL);
+									  WITH RECURSIVE s(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM s WHERE x < 1000000)
+									  INSERT INTO t SELECT x, 'name ' || x, (x * 7919) % 1000 / 10.0 FROM s") {
+		println!("{}", e); return;
+	}
+	let sql = "SELECT id, name, score FROM t";
+	let before = resident();
+	let start = Instant::now();
+	let mut rs = match db.prepare_statement(sql).and_then(|mut st| st.fetch_all()) { Ok(rs) => rs, Err(e) => { println!("{}", e); return } };
+	let elapsed = start.elapsed();
+	let set_bytes = resident().saturating_sub(before);
+	println!("{} rows in {} columns, row 999999: {:?}", rs.row_count(), rs.column_count(), rs.row(999999).map(|r| r.to_owned().into_values()));
+	rs.sort_by(|a, b| b.get_double(2).partial_cmp(&a.get_double(2)).unwrap().then(a.get_long(0).cmp(&b.get_long(0))));
+	println!("sorted by score: first {:?}, last {:?}", rs.row(0).map(|r| r.get_string(1)), rs.row(rs.row_count() - 1).map(|r| r.get_string(1)));
+	let page : Vec<i64> = rs.iter().skip(500000).take(3).map(|r| r.get_long(0)).collect();
+	println!("page at 500000: {:?}", page);
+	let total = thread::spawn(move || rs.iter().map(|r| r.get_long(0)).sum::<i64>()).join();
+	println!("sum of ids from another thread: {:?}", total);
+
+	let before = resident();
+	let owned : Vec<OwnedRow> = match db.prepare_statement(sql) { Ok(mut st) => st.execute_query().filter_map(|r| r.ok()).collect(), Err(e) => { println!("{}", e); return } };
+	let owned_bytes = resident().saturating_sub(before);
+	println!("{} rows as OwnedRow", owned.len());
+	//The figures depend on the allocator and the build, they are shown for comparison.
+	println!("fetch_all: {} ms, about {} MiB ({} bytes a row); Vec<OwnedRow>: about {} MiB ({} bytes a row)", elapsed.as_millis(),
+			 set_bytes >> 20, set_bytes / 1000000, owned_bytes >> 20, owned_bytes / 1000000);
+}
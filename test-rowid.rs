@@ -0,0 +1,68 @@
+extern crate rustic;
+
+use rustic::sql::Connection;
+use rustic::sql::DbType::SQLite3;
+
+//Execute a statement, print the count it returns and the rowid and change counters of the connection after it.
+fn update(db : &Connection, sql : &str) {
+	let n = db.prepare_statement(sql).and_then(|mut st| st.execute_update());
+	println!("{:<58} -> {:?}, rowid {}, changes {}, total {}, autocommit {}", sql, n.map_err(|e| e.to_string()), db.last_insert_rowid(),
+			 db.changes64(), db.total_changes(), db.is_autocommit());
+}
+
+//Follow the rowid and the change counters of an SQLite connection through inserts, updates, deletes, a trigger, a
+//failed statement and a transaction, then read the text, expansion, kind and state of statements. As in SQLite, the
+//statements other than INSERT, UPDATE and DELETE leave the count of changes of the previous one.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	println!("new connection: rowid {}, changes {}, total {}, autocommit {}", db.last_insert_rowid(), db.changes64(), db.total_changes(), db.is_autocommit());
+	for sql in &["CREATE TABLE t(id INTEGER PRIMARY KEY, v TEXT UNIQUE)",
+				 "CREATE TABLE log(n)",
+				 "INSERT INTO t(v) VALUES ('a')",
+				 "INSERT INTO t VALUES (42, 'b')",
+				 "INSERT INTO t(v) VALUES ('c'), ('d'), ('e')",
+				 "SELECT count(*) FROM t",
+				 "UPDATE t SET v = v || v",
+				 "DELETE FROM t WHERE id > 42",
+				 "CREATE TRIGGER t_log AFTER INSERT ON t BEGIN INSERT INTO log VALUES (new.id), (new.id); END",
+				 "INSERT INTO t VALUES (7, 'f')",
+				 "INSERT INTO t VALUES (8, 'aa')",
+				 "BEGIN",
+				 "INSERT INTO t(v) VALUES ('g')",
+				 "COMMIT"] {
+		update(&db, sql);
+	}
+	match db.prepare_statement("INSERT INTO t(v) VALUES (?)") {
+		Ok(mut st) => {
+			for v in &["x", "y"] {
+				if let Some(e) = st.set_string(1, v) { println!("{}", e); }
+				if let Some(e) = st.execute() { println!("{}", e); }
+				println!("execute {:?}: rowid {}, changes {}, total {}", v, db.last_insert_rowid(), db.changes64(), db.total_changes());
+			}
+		}
+		Err(e) => println!("{}", e)
+	}
+
+	for sql in &["SELECT v FROM t WHERE id > ? AND v <> ?", "INSERT INTO log VALUES (?)", "EXPLAIN SELECT 1", "EXPLAIN QUERY PLAN SELECT * FROM t WHERE id = ?",
+				 "  select 1 ; "] {
+		match db.prepare_statement(sql) {
+			Ok(mut st) => {
+				let params = sql.matches('?').count();
+				let errors = vec![if params > 0 { st.set_int(1, 1) } else { None }, if params > 1 { st.set_string(2, "it's") } else { None }];
+				for e in errors.into_iter().flatten() { println!("  bind: {}", e); }
+				println!("{:?}: sql {:?}, expanded {:?}, readonly {}, explain {}", sql, st.sql(), st.expanded_sql(), st.is_readonly(), st.is_explain());
+			}
+			Err(e) => println!("{}", e)
+		}
+	}
+	match db.prepare_statement("SELECT id FROM t ORDER BY id") {
+		Ok(mut st) => {
+			println!("busy before the first row: {}", st.is_busy());
+			let first = st.execute_query().next().map(|r| r.map(|c| c.get_long(0)).map_err(|e| e.to_string()));
+			println!("first id {:?}, busy after it: {}", first, st.is_busy());
+			let ids : Vec<i64> = st.execute_query().filter_map(|r| r.ok()).map(|c| c.get_long(0)).collect();
+			println!("all ids {:?}, busy once done: {}", ids, st.is_busy());
+		}
+		Err(e) => println!("{}", e)
+	};
+}
@@ -0,0 +1,46 @@
+extern crate rustic;
+
+use rustic::sql::{Connection, OwnedRow};
+use rustic::sql::DbType::SQLite3;
+
+//Read a result in place with Rows and Row, keep some rows with to_owned, collect them with a Cursor, then check that an
+//error ends the iteration and that a statement can be read again.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	if let Err(e) = db.execute_batch("CREATE TABLE t(id INTEGER PRIMARY KEY, name TEXT, score REAL, data BLOB);
+									  INSERT INTO t VALUES (1, 'one', 1.5, x'0102'), (2, NULL, NULL, NULL), (3, 'three', 3, x'')") {
+		println!("{}", e); return;
+	}
+	let mut st = match db.prepare_statement("SELECT id, name, score, data FROM t ORDER BY id") { Ok(st) => st, Err(e) => { println!("{}", e); return } };
+	let mut kept : Vec<OwnedRow> = Vec::new();
+	{
+		let mut rows = st.query();
+		println!("{} columns: {:?}", rows.column_count(), (0..rows.column_count()).map(|i| rows.column_name(i)).collect::<Vec<_>>());
+		while let Some(row) = rows.next() {
+			match row {
+				Ok(r) => {
+					println!("{} {:?} {:?} {} {:?} null name: {} {:?}", r.get_long(0), r.get_str(1), r.get_str_lossy(1), r.get_double(2),
+							 r.get_bytes(3), r.is_null(1), r.get_value_ref(2));
+					if r.get_int(0) != 2 { kept.push(r.to_owned()); }
+				}
+				Err(e) => println!("{}", e)
+			}
+		}
+		println!("after the end: {}", rows.next().is_none());
+	}
+	for r in &kept { println!("kept {:?}", r.values()); }
+	let all : Vec<_> = st.execute_query().collect();
+	println!("cursor: {} rows, last name {:?}", all.len(), all.last().and_then(|r| r.as_ref().ok()).map(|r| r.get_string(1)));
+	let again = st.execute_query().filter_map(|r| r.ok()).map(|r| r.get_long(0)).sum::<i64>();
+	println!("read again, sum of ids {}", again);
+	//abs() of the smallest integer overflows on the third row.
+	let mut st = match db.prepare_statement("SELECT abs(CASE id WHEN 3 THEN -9223372036854775807 - 1 ELSE id END) FROM t ORDER BY id") {
+		Ok(st) => st, Err(e) => { println!("{}", e); return }
+	};
+	for r in st.execute_query() {
+		match r { Ok(r) => println!("abs {}", r.get_long(0)), Err(e) => println!("error: {}", e) }
+	}
+	let mut rows = st.query();
+	let mut count = 0;
+	while let Some(r) = rows.next() { count += 1; if let Err(e) = r { println!("rows error after {} rows: {}", count - 1, e); } }
+}
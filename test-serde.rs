@@ -0,0 +1,66 @@
+extern crate rustic;
+#[macro_use]
+extern crate serde_derive;
+
+use std::collections::BTreeMap;
+use std::env;
+use rustic::sql::Connection;
+use rustic::sql::DbType::{PostgreSQL, SQLite3};
+
+//Needs the serde feature: insert structs, query them back by column name and bind a struct to named parameters.
+//serde_derive is a dev-dependency of the crate, built with it:
+//	cargo build --features serde --all-targets
+//	rustc --edition 2015 test-serde.rs --extern rustic=target/debug/librustic.rlib -L target/debug/deps \
+//		--extern serde_derive=$(ls target/debug/deps/libserde_derive-*.so) --extern serde=$(ls target/debug/deps/libserde-*.rlib)
+//With RUSTIC_PG set to a connection string, a row is also inserted into PostgreSQL, which returns no rowid.
+#[derive(Serialize, Deserialize, Debug)]
+struct Person {
+	id : Option<i64>,
+	name : String,
+	photo : Vec<u8>,
+	nick : Option<String>
+}
+
+#[derive(Serialize)]
+struct Filter {
+	name : String
+}
+
+#[derive(Serialize)]
+#[serde(rename = "odd \"name\"")]
+struct Empty {}
+
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	match db.execute_batch("CREATE TABLE person(id INTEGER PRIMARY KEY, name TEXT, photo BLOB, nick TEXT);") {
+		Ok(()) => (),
+		Err(e) => println!("{}", e)
+	}
+	for p in &[Person { id : None, name : "one".to_string(), photo : vec![1, 2], nick : None },
+			   Person { id : Some(10), name : "ten".to_string(), photo : Vec::new(), nick : Some("t".to_string()) }] {
+		match db.insert(p) { Ok(rowid) => println!("rowid {:?}", rowid), Err(e) => println!("{}", e) }
+	}
+	match db.prepare_statement("SELECT * FROM person WHERE name <> :name;") {
+		Ok(mut st) => {
+			match st.bind(&Filter { name : "ten".to_string() }) { None => (), Some(e) => println!("{}", e) }
+			for p in st.query_as::<Person>() {
+				match p {
+					Ok(p)  => println!("{:?}", p),
+					Err(e) => println!("{}", e)
+				}
+			}
+		},
+		Err(e) => println!("{}", e)
+	};
+	if let Err(e) = db.execute_batch("CREATE TABLE \"odd \"\"name\"\"\"(id INTEGER PRIMARY KEY, v TEXT DEFAULT 'default')") { println!("{}", e); }
+	println!("default values: {:?}", db.insert(&Empty {}).map_err(|e| e.to_string()));
+	let mut map = BTreeMap::new();
+	map.insert("v", "from a map");
+	println!("map: {:?}, no table: {:?}", db.insert_into("odd \"name\"", &map).map_err(|e| e.to_string()), db.insert(&map).map_err(|e| e.kind()));
+	if let Ok(target) = env::var("RUSTIC_PG") {
+		let pg = match Connection::new(PostgreSQL, &target) { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+		if let Err(e) = pg.execute_batch("CREATE TEMPORARY TABLE person(id SERIAL PRIMARY KEY, name TEXT, photo BYTEA, nick TEXT)") { println!("{}", e); }
+		let p = Person { id : None, name : "pg".to_string(), photo : vec![3], nick : None };
+		println!("PostgreSQL rowid: {:?}", pg.insert_into("person", &Person { id : Some(5), ..p }).map_err(|e| e.to_string()));
+	}
+}
@@ -0,0 +1,54 @@
+extern crate rustic;
+
+use std::fs::remove_file;
+use rustic::sql::{Connection, ConnectionConfig, JournalMode};
+use rustic::sql::DbType::SQLite3;
+
+//Snapshot a database in WAL mode into bytes, open copies read-only and resizable, and replace an attached database.
+fn main() {
+	let fixture = match Connection::open(SQLite3, "test-serialize.db", &ConnectionConfig::new().journal_mode(JournalMode::Wal)) {
+		Ok(db) => db,
+		Err(e) => { println!("{}", e); return; }
+	};
+	if let Err(e) = fixture.execute_batch("CREATE TABLE planet(name TEXT); INSERT INTO planet VALUES ('Mercury'), ('Venus'), ('Earth')") {
+		println!("{}", e);
+	}
+	let bytes = match fixture.serialize(None) {
+		Ok(b) => b,
+		Err(e) => { println!("{}", e); return; }
+	};
+	println!("{} bytes, header {:?}", bytes.len(), String::from_utf8_lossy(&bytes[..15]));
+	println!("unknown schema: {:?}", fixture.serialize(Some("nowhere")).map_err(|e| e.kind()));
+	println!("empty database: {:?}", Connection::new(SQLite3, ":memory:").and_then(|db| db.serialize(None)));
+	match Connection::from_bytes(&bytes, true) {
+		Ok(db) => {
+			println!("read-only: {:?}", count(&db));
+			println!("insert: {:?}", db.execute_batch("INSERT INTO planet VALUES ('Mars')").map_err(|e| e.to_string()));
+		},
+		Err(e) => println!("{}", e)
+	}
+	match Connection::from_bytes(&bytes, false) {
+		Ok(db) => {
+			let insert = "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 1000) INSERT INTO planet SELECT 'Pluto' FROM n";
+			println!("insert: {:?}, resizable: {:?}", db.execute_batch(insert).map_err(|e| e.to_string()), count(&db));
+			println!("journal_mode {:?}, {} bytes", db.journal_mode(), db.serialize(None).map_or(0, |b| b.len()));
+			if let Err(e) = db.execute_batch("ATTACH ':memory:' AS copy") { println!("{}", e); }
+			println!("into attached: {:?}", db.deserialize(Some("copy"), &bytes, false));
+			println!("attached: {:?}", db.prepare_statement("SELECT count(*) FROM copy.planet").and_then(|mut s| s.fetch_all()).map(|rs| rs.row(0).map(|r| r.get_long(0))));
+			println!("unknown schema: {:?}", db.deserialize(Some("nowhere"), &bytes, false).map_err(|e| e.kind()));
+		},
+		Err(e) => println!("{}", e)
+	}
+	match Connection::from_bytes(b"not a database, surely", false) {
+		Ok(db) => println!("garbage: {:?}", count(&db)),
+		Err(e) => println!("garbage: {}", e)
+	}
+	drop(fixture);
+	for f in &["test-serialize.db", "test-serialize.db-wal", "test-serialize.db-shm"] { let _ = remove_file(f); }
+}
+
+fn count(db : &Connection) -> Result<i64, String> {
+	let mut st = db.prepare_statement("SELECT count(*) FROM planet").map_err(|e| e.to_string())?;
+	let rs = st.fetch_all().map_err(|e| e.to_string())?;
+	Ok(rs.row(0).map_or(0, |r| r.get_long(0)))
+}
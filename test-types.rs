@@ -0,0 +1,34 @@
+extern crate rustic;
+
+use std::time::Duration;
+use rustic::sql::{Connection, Timestamp, Date, Uuid, Decimal, TimeFormat, TimeUnit, UuidFormat, DecimalFormat};
+use rustic::sql::DbType::SQLite3;
+
+//Store an instant, a date, a duration, a UUID, a decimal amount and a u64 in their configurable formats and read them back.
+fn main() {
+	let db = match Connection::new(SQLite3, ":memory:") { Ok(db) => db, Err(e) => { println!("{}", e); return } };
+	let at : Timestamp = match "2024-02-29T23:30:00.25+01:00".parse() { Ok(t) => t, Err(e) => { println!("{}", e); return } };
+	let day = match Date::new(2024, 2, 29) { Some(d) => d, None => return };
+	let amount : Decimal = match "1234.50".parse() { Ok(d) => d, Err(e) => { println!("{}", e); return } };
+	let id = match Uuid::new_v4() { Ok(id) => id, Err(e) => { println!("{}", e); return } };
+	for &(time, uuid, decimal) in &[(TimeFormat::Text, UuidFormat::Text, DecimalFormat::Text),
+									(TimeFormat::UnixSeconds, UuidFormat::Blob, DecimalFormat::Scaled(2)),
+									(TimeFormat::JulianDay, UuidFormat::Blob, DecimalFormat::Scaled(4))] {
+		let mut st = match db.prepare_statement("SELECT ?, ?, ?, ?, ?, ?, typeof(?1), typeof(?5)") { Ok(st) => st, Err(e) => { println!("{}", e); return } };
+		let errors = vec![st.set_timestamp(1, &at, time), st.set_date(2, &day, time), st.set_duration(3, Duration::from_millis(1500), TimeUnit::Millis),
+						  st.set_uuid(4, &id, uuid), st.set_decimal(5, &amount, decimal), st.set_u64(6, 1 << 62)];
+		for e in errors.into_iter().flatten() { println!("{}", e); }
+		match st.fetch_all() {
+			Ok(rs) => for r in rs.iter() {
+				println!("{:?} {:?} {:?}: stored as {} and {}", time, uuid, decimal, r.get_string(6), r.get_string(7));
+				println!("  {:?} {:?} {:?} {:?} {:?} {:?}", r.get_timestamp(0).map(|t| t.to_string()), r.get_date(1).map(|d| d.to_string()),
+						 r.get_duration(2, TimeUnit::Millis), r.get_uuid(3).map(|u| u == id), r.get_decimal(4, decimal).map(|d| d.to_string()), r.get_u64(5));
+			},
+			Err(e) => println!("{}", e)
+		}
+	}
+	let mut st = match db.prepare_statement("SELECT ?") { Ok(st) => st, Err(e) => { println!("{}", e); return } };
+	for e in vec![st.set_u64(1, u64::MAX), st.set_i128(1, i128::MAX), st.set_decimal(1, &amount, DecimalFormat::Scaled(0))].into_iter().flatten() {
+		println!("{}", e);
+	}
+}
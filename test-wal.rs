@@ -0,0 +1,46 @@
+extern crate rustic;
+
+use std::cell::Cell;
+use std::fs::remove_file;
+use std::rc::Rc;
+use rustic::sql::{CheckpointMode, Connection, ConnectionConfig, JournalMode};
+use rustic::sql::DbType::SQLite3;
+
+//Write to a database in WAL mode with a hook that checkpoints every 4 frames, then checkpoint by hand in each mode.
+fn main() {
+	let config = ConnectionConfig::new().journal_mode(JournalMode::Wal);
+	let db = match Connection::open(SQLite3, "test-wal.db", &config) {
+		Ok(db) => db,
+		Err(e) => { println!("{}", e); return; }
+	};
+	let checkpoints = Rc::new(Cell::new(0));
+	let count = checkpoints.clone();
+	let hook = db.set_wal_hook(move |con, schema, frames| {
+		if frames >= 4 {
+			match con.wal_checkpoint(Some(schema), CheckpointMode::Passive) {
+				Ok(c) => { count.set(count.get() + 1); println!("{}: {} frames, {} checkpointed", schema, c.log_frames(), c.checkpointed_frames()); },
+				Err(e) => println!("{}", e)
+			}
+			if let Err(e) = con.clear_wal_hook() { println!("in the hook: {}", e); }
+		}
+	});
+	if let Err(e) = hook { println!("{}", e); }
+	if let Err(e) = db.execute_batch("CREATE TABLE t(id INTEGER PRIMARY KEY, v TEXT)") { println!("{}", e); }
+	for i in 0..8 {
+		if let Err(e) = db.execute_batch(&format!("INSERT INTO t(v) VALUES ('{}')", "x".repeat(i * 1000))) { println!("{}", e); }
+	}
+	println!("{} checkpoints from the hook", checkpoints.get());
+	if let Err(e) = db.set_wal_autocheckpoint(100) { println!("{}", e); }
+	for &mode in &[CheckpointMode::Passive, CheckpointMode::Full, CheckpointMode::Restart, CheckpointMode::Truncate] {
+		match db.wal_checkpoint(None, mode) {
+			Ok(c) => println!("{:?}: {} frames, {} checkpointed, busy {}", mode, c.log_frames(), c.checkpointed_frames(), c.is_busy()),
+			Err(e) => println!("{:?}: {}", mode, e)
+		}
+	}
+	match db.wal_checkpoint(Some("nowhere"), CheckpointMode::Passive) {
+		Ok(c) => println!("{:?}", c),
+		Err(e) => println!("{:?}: {}", e.kind(), e)
+	}
+	drop(db);
+	for f in &["test-wal.db", "test-wal.db-wal", "test-wal.db-shm"] { let _ = remove_file(f); }
+}